@@ -53,6 +53,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             last_frame = std::time::Instant::now();
 
             party.check_context_changes(&mut context, &mut renderer);
+            io.update_touch_momentum(&mut context);
 
             let mut target = display.draw();
             target.clear_color(0.21404, 0.21404, 0.21404, 1.0); // manual sRGB conversion for 0.5
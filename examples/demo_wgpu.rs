@@ -61,6 +61,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             last_frame = std::time::Instant::now();
 
             party.check_context_changes(&mut context, &mut renderer);
+            io.update_touch_momentum(&mut context);
 
             let frame = swap_chain.get_current_frame().unwrap().output;
             let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
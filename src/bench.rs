@@ -5,13 +5,14 @@
 //! You can pass a block to be timed using [`run`](fn.run.html), or create a handle with
 //! [`start`](fn.start.html) and end the timing with [`end`](struct.Handle.html#method.end).
 //! Use [`stats`](fn.stats.html) to get a [`Stats`](struct.Stats.html), which is the
-//! primary interface for reporting on the timings.
+//! primary interface for reporting on the timings.  The number of timings kept for
+//! each tag may be changed with [`set_sample_count`](fn.set_sample_count.html).
 
 use std::time::{Duration, Instant};
 
 use parking_lot::{const_mutex, Mutex};
 
-const MOVING_AVG_LEN: usize = 30;
+const DEFAULT_SAMPLE_COUNT: usize = 120;
 
 static BENCH: Mutex<BenchSet> = const_mutex(BenchSet::new());
 
@@ -53,6 +54,22 @@ pub fn stats(tag: &str) -> Stats {
     bench.stats(tag)
 }
 
+/// Returns a `Stats` object for the benchmark timings associated with
+/// the given `tag`, or `None` if there are no timings for that `tag`.
+/// This is useful for building your own display of the timings.
+pub fn stat(tag: &str) -> Option<Stats> {
+    let bench = BENCH.lock();
+    bench.stat(tag)
+}
+
+/// Sets the number of most recent timings kept for each tag and used
+/// to compute [`Stats`](struct.Stats.html).  The default is 120.  Changing
+/// this discards all existing timings.
+pub fn set_sample_count(count: usize) {
+    let mut bench = BENCH.lock();
+    bench.set_sample_count(count.max(1));
+}
+
 /// A convenience method to automatically generate a report
 /// String for the given `tag`.  The report will include all of the
 /// data in the [`Stats`](struct.Stats.html) associated with this `tag`,
@@ -69,13 +86,17 @@ fn end(handle: Handle) {
 
 /// Statistics associated with a given set of benchmark timings.
 /// These are obtained with the `stats` method for a given tag.
-/// Statistics are for a moving average of the last N timings for the
-/// tag, where N is currently hardcoded to 30.
+/// Statistics are for the last N timings for the tag, where N
+/// defaults to 120 and is set with [`set_sample_count`](fn.set_sample_count.html).
 #[derive(Debug, Copy, Clone)]
 pub struct Stats {
     average_s: f32,
     stdev_s: f32,
+    min_s: f32,
     max_s: f32,
+    p50_s: f32,
+    p95_s: f32,
+    p99_s: f32,
     unit: Unit,
 }
 
@@ -84,7 +105,11 @@ impl Default for Stats {
         Stats {
             average_s: 0.0,
             stdev_s: 0.0,
+            min_s: 0.0,
             max_s: 0.0,
+            p50_s: 0.0,
+            p95_s: 0.0,
+            p99_s: 0.0,
             unit: Unit::Seconds,
         }
     }
@@ -103,12 +128,36 @@ impl Stats {
         self.stdev_s * self.unit.multiplier()
     }
 
+    /// Returns the minimum of the timings, in the current unit
+    /// of this `Stats`.
+    pub fn min(&self) -> f32 {
+        self.min_s * self.unit.multiplier()
+    }
+
     /// Returns the maximum of the timings, in the current unit
     /// of this `Stats`.
     pub fn max(&self) -> f32 {
         self.max_s * self.unit.multiplier()
     }
 
+    /// Returns the median (50th percentile) of the timings, in the
+    /// current unit of this `Stats`.
+    pub fn p50(&self) -> f32 {
+        self.p50_s * self.unit.multiplier()
+    }
+
+    /// Returns the 95th percentile of the timings, in the current unit
+    /// of this `Stats`.
+    pub fn p95(&self) -> f32 {
+        self.p95_s * self.unit.multiplier()
+    }
+
+    /// Returns the 99th percentile of the timings, in the current unit
+    /// of this `Stats`.
+    pub fn p99(&self) -> f32 {
+        self.p99_s * self.unit.multiplier()
+    }
+
     /// Returns the postfix string of the Unit associated with this
     /// `Stats`, such as "s" for Seconds, "ms" for milliseconds, and
     /// "µs" for microseconds.
@@ -134,30 +183,24 @@ impl Stats {
     /// Converts this `Stats` to use seconds as a unit
     pub fn in_seconds(self) -> Stats {
         Stats {
-            average_s: self.average_s,
-            stdev_s: self.stdev_s,
-            max_s: self.max_s,
             unit: Unit::Seconds,
+            ..self
         }
     }
 
     /// Converts this `Stats` to use milliseconds as a unit
     pub fn in_millis(self) -> Stats {
         Stats {
-            average_s: self.average_s,
-            stdev_s: self.stdev_s,
-            max_s: self.max_s,
             unit: Unit::Millis,
+            ..self
         }
     }
 
     /// Converts this `Stats` to use microseconds as a unit
     pub fn in_micros(self) -> Stats {
         Stats {
-            average_s: self.average_s,
-            stdev_s: self.stdev_s,
-            max_s: self.max_s,
             unit: Unit::Micros,
+            ..self
         }
     }
 }
@@ -165,12 +208,21 @@ impl Stats {
 struct BenchSet {
     // TODO maybe use HashMap here once we can create a hashmap in const
     benches: Vec<Bench>,
+    sample_count: usize,
 }
 
 impl BenchSet {
     const fn new() -> BenchSet {
         BenchSet {
-            benches: Vec::new()
+            benches: Vec::new(),
+            sample_count: DEFAULT_SAMPLE_COUNT,
+        }
+    }
+
+    fn set_sample_count(&mut self, count: usize) {
+        self.sample_count = count;
+        for bench in self.benches.iter_mut() {
+            bench.history = History::new(count);
         }
     }
 
@@ -186,7 +238,7 @@ impl BenchSet {
         }
 
         // create new bench
-        let mut bench = Bench::new(tag.to_string());
+        let mut bench = Bench::new(tag.to_string(), self.sample_count);
         bench.start = Some(Instant::now());
         let index = self.benches.len();
         self.benches.push(bench);
@@ -201,13 +253,13 @@ impl BenchSet {
     }
 
     fn stats(&self, tag: &str) -> Stats {
-        for bench in self.benches.iter() {
-            if bench.tag == tag {
-                return bench.stats();
-            }
-        }
+        self.stat(tag).unwrap_or_default()
+    }
 
-        Stats::default()
+    fn stat(&self, tag: &str) -> Option<Stats> {
+        self.benches.iter()
+            .find(|bench| bench.tag == tag && !bench.history.samples.is_empty())
+            .map(|bench| bench.stats())
     }
 
     fn report(&self, tag: &str) -> String {
@@ -249,43 +301,77 @@ impl Unit {
     }
 }
 
+/// A fixed size ring buffer of the most recent timings.  Storage is allocated
+/// up front, so pushing a timing never allocates.
+struct History {
+    samples: Vec<Duration>,
+    next: usize,
+    capacity: usize,
+}
+
+impl History {
+    fn new(capacity: usize) -> History {
+        History {
+            samples: Vec::with_capacity(capacity),
+            next: 0,
+            capacity,
+        }
+    }
+
+    fn push(&mut self, duration: Duration) {
+        if self.samples.len() < self.capacity {
+            self.samples.push(duration);
+        } else {
+            self.samples[self.next] = duration;
+        }
+        self.next = (self.next + 1) % self.capacity;
+    }
+}
+
 struct Bench {
     tag: String,
-    history: Vec<Duration>,
+    history: History,
     start: Option<Instant>,
 }
 
 impl Bench {
-    fn new(tag: String) -> Bench {
+    fn new(tag: String, sample_count: usize) -> Bench {
         Bench {
-            history: Vec::new(),
+            history: History::new(sample_count),
             start: None,
             tag,
         }
     }
 
     fn stats(&self) -> Stats {
-        let count = std::cmp::min(MOVING_AVG_LEN, self.history.len());
+        let mut data: Vec<f32> = self.history.samples.iter().map(|d| d.as_secs_f32()).collect();
+        if data.is_empty() { return Stats::default(); }
+        data.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
 
-        let data = || { self.history.iter().rev().take(MOVING_AVG_LEN) };
+        let count = data.len();
+        let avg = data.iter().sum::<f32>() / (count as f32);
 
-        let sum = (data)().sum::<Duration>().as_secs_f32();
-        let max = match (data)().max() {
-            None => 0.0,
-            Some(dur) => dur.as_secs_f32(),
+        let stdev = if count > 1 {
+            let numer: f32 = data.iter().map(|d| (d - avg) * (d - avg)).sum();
+            (numer / (count as f32 - 1.0)).sqrt()
+        } else {
+            0.0
         };
 
-        let avg = sum / (count as f32);
-
-        let numer: f32 = (data)().map(|d| (d.as_secs_f32() - avg) * (d.as_secs_f32() - avg)).sum();
-
-        let stdev_sq = numer / (count as f32 - 1.0);
-        let stdev = stdev_sq.sqrt();
+        // nearest rank percentile of the sorted data
+        let percentile = |p: f32| {
+            let rank = (p * count as f32).ceil() as usize;
+            data[rank.max(1).min(count) - 1]
+        };
 
         Stats {
             average_s: avg,
             stdev_s: stdev,
-            max_s: max,
+            min_s: data[0],
+            max_s: data[count - 1],
+            p50_s: percentile(0.50),
+            p95_s: percentile(0.95),
+            p99_s: percentile(0.99),
             unit: Unit::Seconds,
         }
     }
@@ -293,8 +379,9 @@ impl Bench {
     fn report_str(&self) -> String {
         let stats = self.stats().pick_unit();
         format!(
-            "{}: {:.2} ± {:.2}; max {:.2} {}",
-            self.tag, stats.average(), stats.stdev(), stats.max(), stats.unit_postfix(),
+            "{}: {:.2} ± {:.2}; min {:.2}, p50 {:.2}, p95 {:.2}, p99 {:.2}, max {:.2} {}",
+            self.tag, stats.average(), stats.stdev(), stats.min(), stats.p50(), stats.p95(), stats.p99(),
+            stats.max(), stats.unit_postfix(),
         )
     }
 }
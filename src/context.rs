@@ -1,21 +1,105 @@
+use std::any::{Any, TypeId};
 use std::collections::{HashMap, HashSet};
 use std::cell::RefCell;
 use std::rc::Rc;
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 
-use crate::{Error, Point, Frame, Rect, frame::{RendGroup, RendGroupDef}};
+use serde::{Serialize, Deserialize};
+
+use crate::{Border, Error, Point, Frame, Rect, frame::{RendGroup, RendGroupDef}};
 use crate::{font::FontSummary, widget::Widget, image::ImageHandle, theme::ThemeSet, resource::ResourceSet};
-use crate::theme_definition::{AnimState, AnimStateKey};
+use crate::theme_definition::{AnimState, AnimStateKey, Color, ImageDefinition};
+use crate::recipes::TOASTS_ID;
+use crate::recipes::SortOrder;
+use crate::scrollpane::ScrollRequest;
 use crate::render::Renderer;
+use crate::winit_io::{CursorIcon, KeyCode};
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq)]
 pub(crate) struct PersistentStateData {
     pub is_open: bool,
     pub expanded: bool,
     pub resize: Point,
     pub moved: Point,
     pub scroll: Point,
+    pub selected: usize,
+    pub sort_order: SortOrder,
+    pub split_fraction: Option<f32>,
+    pub reorder: Option<(usize, usize)>,
+}
+
+const DEFAULT_DOUBLE_CLICK_MS: u32 = 500;
+
+/// The version of the format written by [`Context::serialize_state`](struct.Context.html#method.serialize_state).
+/// This should be incremented whenever `SavedState` changes.
+const SAVED_STATE_VERSION: u32 = 1;
+
+#[derive(Deserialize)]
+struct SavedStateVersion {
+    version: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedStates {
+    version: u32,
+    states: HashMap<String, SavedState>,
+}
+
+/// The parts of a [`PersistentState`](struct.PersistentState.html) that are saved by
+/// [`Context::serialize_state`](struct.Context.html#method.serialize_state).  Transient
+/// values such as animation, drag, and keyboard input state are not saved.
+#[derive(Serialize, Deserialize)]
+struct SavedState {
+    is_open: bool,
+    expanded: bool,
+    resize: Point,
+    moved: Point,
+    scroll: Point,
+    selected: usize,
+    sort_order: SortOrder,
+    split_fraction: Option<f32>,
+    text: Option<String>,
+}
+
+impl SavedState {
+    fn new(state: &PersistentState) -> SavedState {
+        SavedState {
+            is_open: state.is_open,
+            expanded: state.expanded,
+            resize: state.resize,
+            moved: state.moved,
+            scroll: state.scroll,
+            selected: state.selected,
+            sort_order: state.sort_order,
+            split_fraction: state.split_fraction,
+            text: state.text.clone(),
+        }
+    }
+
+    fn into_state(self) -> PersistentState {
+        PersistentState {
+            is_open: self.is_open,
+            expanded: self.expanded,
+            resize: self.resize,
+            moved: self.moved,
+            scroll: self.scroll,
+            selected: self.selected,
+            sort_order: self.sort_order,
+            split_fraction: self.split_fraction,
+            text: self.text,
+            ..Default::default()
+        }
+    }
+}
+
+/// The values of a [`PersistentState`](struct.PersistentState.html) that are compared
+/// between frames to detect changes.
+#[derive(Clone, PartialEq)]
+struct StateSnapshot {
+    data: PersistentStateData,
+    base_time_millis: u32,
+    text: Option<String>,
 }
 
 /**
@@ -49,6 +133,16 @@ pub struct PersistentState {
     /// An amount, in logical pixels that the widget has been resized by.  Default to zero.
     pub resize: Point,
 
+    /// The minimum size, in logical pixels, that the widget may be resized to.  This is set each
+    /// frame by resizable widgets such as [`windows`](struct.WindowBuilder.html#method.min_size).
+    /// Defaults to `None`.
+    pub min_size: Option<Point>,
+
+    /// The maximum size, in logical pixels, that the widget may be resized to.  This is set each
+    /// frame by resizable widgets such as [`windows`](struct.WindowBuilder.html#method.max_size).
+    /// Defaults to `None`.
+    pub max_size: Option<Point>,
+
     /// An amount, in logical pizels that the widget has been moved by.  Defaults to zero.
     pub moved: Point,
 
@@ -56,6 +150,33 @@ pub struct PersistentState {
     /// scrolled by.  Defaults to zero.
     pub scroll: Point,
 
+    /// A pending request to scroll a scrollpane, which is applied and cleared the next time the scrollpane
+    /// with this content ID is built.  Defaults to `None`.  See [`ScrollRequest`](enum.ScrollRequest.html).
+    pub scroll_request: Option<ScrollRequest>,
+
+    /// The index of the selected item, such as the active tab of a tab panel.  Defaults to zero.
+    pub selected: usize,
+
+    /// The sort order of a table or similar widget.  Defaults to `None`.
+    pub sort_order: SortOrder,
+
+    /// The fraction of the available space given to the first pane of a split pane.  Defaults
+    /// to `None`, in which case the split pane's initial fraction is used.
+    pub split_fraction: Option<f32>,
+
+    /// For a reorderable list, the index of the item being dragged and the index it will be moved to
+    /// if released.  Defaults to `None`, meaning no item is being dragged.
+    pub reorder: Option<(usize, usize)>,
+
+    /// The [`AnimState`](struct.AnimState.html) of this widget on the most recent frame.  This is only
+    /// tracked for widgets with animated images that blend between states, or with theme state overrides
+    /// such as `size_active`.  Defaults to `None`.
+    pub anim_state: Option<AnimState>,
+
+    /// The previous [`AnimState`](struct.AnimState.html) of this widget and the time, in milliseconds,
+    /// that it changed, used to blend animated images between states.  Defaults to `None`.
+    pub anim_transition: Option<(AnimState, u32)>,
+
     /// The "zero" time for timed images associated with this widget.  Defaults to zero,
     /// which is the internal [`Context`](struct.Context.html) init time.
     pub base_time_millis: u32,
@@ -66,9 +187,31 @@ pub struct PersistentState {
 
     /// The text for this widget, overriding default text.  Defaults to `None`.
     pub text: Option<String>,
+
+    // arbitrary user data, at most one value per type
+    typed: HashMap<TypeId, Box<dyn Any>>,
 }
 
 impl PersistentState {
+    /// Returns the user data of type `T` stored in this state with [`set_typed`](#method.set_typed),
+    /// if any.  This allows widgets to store arbitrary data, such as a `Vec<String>` of previous
+    /// entries, alongside the built-in fields.
+    pub fn get_typed<T: 'static>(&self) -> Option<&T> {
+        self.typed.get(&TypeId::of::<T>()).and_then(|val| val.downcast_ref())
+    }
+
+    /// Returns a mutable reference to the user data of type `T` stored in this state, if any.
+    pub fn get_typed_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.typed.get_mut(&TypeId::of::<T>()).and_then(|val| val.downcast_mut())
+    }
+
+    /// Stores `val` in this state, replacing any previous value of type `T`.  The value may be
+    /// retrieved with [`get_typed`](#method.get_typed).  Note that user data is not saved by
+    /// [`Context::serialize_state`](struct.Context.html#method.serialize_state).
+    pub fn set_typed<T: 'static>(&mut self, val: T) {
+        self.typed.insert(TypeId::of::<T>(), Box::new(val));
+    }
+
     pub(crate) fn copy_data(&self) -> PersistentStateData {
         PersistentStateData {
             is_open: self.is_open,
@@ -76,6 +219,18 @@ impl PersistentState {
             resize: self.resize,
             moved: self.moved,
             scroll: self.scroll,
+            selected: self.selected,
+            sort_order: self.sort_order,
+            split_fraction: self.split_fraction,
+            reorder: self.reorder,
+        }
+    }
+
+    fn snapshot(&self) -> StateSnapshot {
+        StateSnapshot {
+            data: self.copy_data(),
+            base_time_millis: self.base_time_millis,
+            text: self.text.clone(),
         }
     }
 }
@@ -86,18 +241,28 @@ impl Default for PersistentState {
             is_open: true,
             expanded: true,
             resize: Point::default(),
+            min_size: None,
+            max_size: None,
             moved: Point::default(),
             scroll: Point::default(),
+            scroll_request: None,
+            selected: 0,
+            sort_order: SortOrder::None,
+            split_fraction: None,
+            reorder: None,
+            anim_state: None,
+            anim_transition: None,
             base_time_millis: 0,
             characters: Vec::default(),
             text: None,
+            typed: HashMap::new(),
         }
     }
 }
 
 /// The current state of the various keyboard modifier keys - Shift, Control, and Alt
 /// You can get this using [`Frame.input_modiifers`](struct.Frame.html#method.input_modifiers)
-#[derive(Default, Copy, Clone)]
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
 pub struct InputModifiers {
     /// whether the Shift key is pressed
     pub shift: bool,
@@ -109,6 +274,30 @@ pub struct InputModifiers {
     pub alt: bool,
 }
 
+pub(crate) struct Toast {
+    pub(crate) id: u32,
+    pub(crate) message: String,
+    pub(crate) start_millis: u32,
+    pub(crate) duration_millis: u32,
+}
+
+struct Shortcut {
+    id: String,
+    key: KeyCode,
+    modifiers: InputModifiers,
+}
+
+/// A drag and drop operation that is currently in progress
+pub(crate) struct DragState {
+    pub(crate) source_id: String,
+    pub(crate) theme_id: String,
+    pub(crate) payload: Box<dyn Any>,
+    pub(crate) size: Point,
+
+    // offset of the mouse from the top left corner of the source widget
+    pub(crate) offset: Point,
+}
+
 pub struct ContextInternal {
     resources: ResourceSet,
     themes: ThemeSet,
@@ -124,9 +313,58 @@ pub struct ContextInternal {
     mouse_pressed_outside: [bool; 3],
 
     keyboard_focus_widget: Option<String>,
+    keyboard_input: Vec<char>,
+    captured_widget: Option<String>,
+
+    // the widget id and time of the last click, for detecting double clicks
+    last_click: Option<(String, u32)>,
+    double_click_ms: u32,
+    dropped_files: Vec<PathBuf>,
+
+    // the IME composition text and cursor range, if composition is in progress
+    ime_preedit: Option<(String, Option<(usize, usize)>)>,
+
+    // the OS cursor shape requested in the current frame
+    os_cursor: CursorIcon,
     persistent_state: HashMap<String, PersistentState>,
     empty_persistent_state: PersistentState,
 
+    // state restored from a save for widgets that have not yet been created
+    restored_state: HashMap<String, PersistentState>,
+    state_snapshots: HashMap<String, StateSnapshot>,
+    changed_states: HashSet<String>,
+
+    // states that may have been modified or removed since the changed states were last updated
+    dirty_states: HashSet<String>,
+    all_states_dirty: bool,
+    widget_rects: HashMap<String, Rect>,
+
+    // widget rects are only recorded once they have been queried
+    widget_rects_queried: bool,
+    child_counts: HashMap<String, usize>,
+    overflowed_widgets: HashSet<String>,
+
+    // the id and theme id of each visible widget in the last frame, in draw order
+    last_frame_widgets: Vec<(String, String)>,
+    inspected_widget: Option<String>,
+
+    toasts: Vec<Toast>,
+    next_toast_id: u32,
+
+    grid_columns: HashMap<String, Vec<f32>>,
+    spring_extents: HashMap<String, f32>,
+
+    drag: Option<DragState>,
+
+    shortcuts: Vec<Shortcut>,
+    triggered_shortcuts: HashSet<String>,
+    keys_pressed: HashSet<KeyCode>,
+    keys_pressed_this_frame: HashSet<KeyCode>,
+    shortcut_consumed_character: bool,
+
+    tooltip_hover_start: Option<u32>,
+    tooltip_hover_requested: bool,
+
     input_modifiers: InputModifiers,
     last_mouse_pos: Point,
     mouse_pos: Point,
@@ -136,9 +374,15 @@ pub struct ContextInternal {
 
     display_size: Point,
     scale_factor: f32,
+    global_scale: f32,
+    safe_area_insets: Border,
 
-    start_instant: Instant,
+    last_time_instant: Instant,
+    scaled_time_millis: f64,
+    time_scale: f32,
     time_millis: u32,
+    first_frame_instant: Option<Instant>,
+    frame_number: u64,
 
     errors: HashSet<String>,
 }
@@ -204,17 +448,131 @@ impl ContextInternal {
         }
     }
 
+    pub(crate) fn push_toast(&mut self, message: String, duration_millis: u32) {
+        let id = self.next_toast_id;
+        self.next_toast_id = self.next_toast_id.wrapping_add(1);
+
+        self.toasts.push(Toast {
+            id,
+            message,
+            start_millis: self.time_millis,
+            duration_millis,
+        });
+    }
+
+    /// Removes any toasts that have expired, along with their persistent state, and returns
+    /// the remaining toasts
+    pub(crate) fn active_toasts(&mut self) -> &[Toast] {
+        let time = self.time_millis;
+        let persistent_state = &mut self.persistent_state;
+        let dirty_states = &mut self.dirty_states;
+        self.toasts.retain(|toast| {
+            let active = toast.start_millis.saturating_add(toast.duration_millis) > time;
+            if !active {
+                let id = format!("{}/{}", TOASTS_ID, toast.id);
+                persistent_state.remove(&id);
+                dirty_states.insert(id);
+            }
+            active
+        });
+        &self.toasts
+    }
+
+    /// Returns the column widths of the grid layout widget with the specified `id`, as
+    /// measured in the previous frame
+    pub(crate) fn grid_columns(&self, id: &str) -> Vec<f32> {
+        self.grid_columns.get(id).cloned().unwrap_or_default()
+    }
+
+    pub(crate) fn set_grid_columns(&mut self, id: String, columns: Vec<f32>) {
+        self.grid_columns.insert(id, columns);
+    }
+
+    /// Returns the extent of the children placed after a spring in the widget with the
+    /// specified `id`, as measured in the previous frame
+    pub(crate) fn spring_extent(&self, id: &str) -> f32 {
+        self.spring_extents.get(id).copied().unwrap_or_default()
+    }
+
+    pub(crate) fn set_spring_extent(&mut self, id: String, extent: f32) {
+        self.spring_extents.insert(id, extent);
+    }
+
+    pub(crate) fn drag(&self) -> Option<&DragState> { self.drag.as_ref() }
+
+    pub(crate) fn begin_drag(&mut self, drag: DragState) {
+        self.drag = Some(drag);
+    }
+
+    pub(crate) fn take_drag(&mut self) -> Option<DragState> { self.drag.take() }
+
+    pub(crate) fn shortcut_triggered(&self, id: &str) -> bool {
+        self.triggered_shortcuts.contains(id)
+    }
+
+    pub(crate) fn take_key_pressed(&mut self, key: KeyCode) -> bool {
+        self.keys_pressed_this_frame.remove(&key)
+    }
+
+    pub(crate) fn key_pressed(&self, key: KeyCode) -> bool {
+        self.keys_pressed_this_frame.contains(&key)
+    }
+
+    pub(crate) fn key_held(&self, key: KeyCode) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
     pub(crate) fn base_time_millis_for(&self, id: &str) -> u32 {
         self.persistent_state.get(id).map_or(0, |state| state.base_time_millis)
     }
 
     pub(crate) fn time_millis(&self) -> u32 { self.time_millis }
+
+    /// Returns the previous anim state of the widget with the specified `id`, and the
+    /// number of milliseconds since it changed, if the widget has changed state.
+    pub(crate) fn anim_transition_for(&self, id: &str) -> Option<(AnimState, u32)> {
+        let (prev, time) = self.persistent_state.get(id)?.anim_transition?;
+        Some((prev, self.time_millis.saturating_sub(time)))
+    }
+
+    /// Returns the number of milliseconds the mouse has been stationary while a delayed
+    /// tooltip has been requested.  Moving the mouse restarts the timer.
+    pub(crate) fn tooltip_hover_millis(&mut self) -> u32 {
+        let now = self.time_millis;
+        if self.mouse_pos != self.last_mouse_pos || self.tooltip_hover_start.is_none() {
+            self.tooltip_hover_start = Some(now);
+        }
+        self.tooltip_hover_requested = true;
+
+        now - self.tooltip_hover_start.unwrap_or(now)
+    }
     pub(crate) fn mouse_pos(&self) -> Point { self.mouse_pos }
     pub(crate) fn last_mouse_pos(&self) -> Point { self.last_mouse_pos }
     pub(crate) fn mouse_pressed(&self, index: usize) -> bool { self.mouse_pressed[index] }
     pub(crate) fn mouse_clicked(&self, index: usize) -> bool { self.mouse_clicked[index] }
 
+    pub(crate) fn captured_widget(&self) -> Option<&str> { self.captured_widget.as_deref() }
+
+    /// Records a click on the widget with the specified `id`, returning whether it completes a double click.
+    pub(crate) fn check_double_click(&mut self, id: &str) -> bool {
+        let time = self.time_millis;
+        match self.last_click.take() {
+            Some((last_id, last_time)) if last_id == id && time - last_time <= self.double_click_ms => true,
+            _ => {
+                self.last_click = Some((id.to_string(), time));
+                false
+            }
+        }
+    }
+
+    pub(crate) fn set_captured_widget(&mut self, id: Option<String>) {
+        self.captured_widget = id;
+    }
+
     pub (crate) fn set_focus_keyboard(&mut self, id: String) {
+        if self.keyboard_focus_widget.as_deref() != Some(&id) {
+            self.keyboard_input.clear();
+        }
         self.keyboard_focus_widget = Some(id);
     }
 
@@ -232,34 +590,178 @@ impl ContextInternal {
         self.mouse_taken_last_frame.as_ref().map(|(id, _)| id.as_ref())
     }
 
-    pub(crate) fn scale_factor(&self) -> f32 { self.scale_factor }
+    /// The overall scale factor, combining the display scale factor and the global scale
+    pub(crate) fn scale_factor(&self) -> f32 { self.scale_factor * self.global_scale }
     pub(crate) fn display_size(&self) -> Point { self.display_size }
+    pub(crate) fn safe_area_insets(&self) -> Border { self.safe_area_insets }
 
     pub(crate) fn themes(&self) -> &ThemeSet { &self.themes }
 
     pub(crate) fn init_state<T: Into<String>>(&mut self, id: T, open: bool, expanded: bool) {
-        self.persistent_state.entry(id.into()).or_insert(
-            PersistentState {
+        let id = id.into();
+        self.mark_dirty(&id);
+        let restored = self.restored_state.remove(&id);
+        self.persistent_state.entry(id).or_insert_with(||
+            restored.unwrap_or(PersistentState {
                 is_open: open,
                 expanded,
                 ..Default::default()
-            }
+            })
         );
     }
 
     pub(crate) fn clear_state(&mut self, id: &str) {
+        self.mark_dirty(id);
         self.persistent_state.remove(id);
+        self.restored_state.remove(id);
+    }
+
+    /// Removes the state for `id` and all of its children, as well as the modal if it is
+    /// one of those widgets.
+    // `Option::is_some_and` is avoided to support older compilers
+    #[allow(clippy::unnecessary_map_or)]
+    pub(crate) fn clear_state_recursive(&mut self, id: &str) {
+        let prefix = format!("{}/", id);
+        let matches = |key: &str| key == id || key.starts_with(&prefix);
+
+        self.persistent_state.retain(|key, _| !matches(key));
+        self.restored_state.retain(|key, _| !matches(key));
+        self.all_states_dirty = true;
+
+        if self.modal_id().map_or(false, matches) {
+            self.modal.take();
+        }
+    }
+
+    pub(crate) fn clear_all_state(&mut self) {
+        self.all_states_dirty = true;
+        self.persistent_state.clear();
+        self.restored_state.clear();
+        self.modal.take();
     }
 
     pub(crate) fn state(&self, id: &str) -> &PersistentState {
         match self.persistent_state.get(id) {
-            None => &self.empty_persistent_state,
+            None => self.restored_state.get(id).unwrap_or(&self.empty_persistent_state),
             Some(state) => state,
         }
     }
 
+    /// Records the screen space bounds of each visible widget in `widgets`, if widget rects
+    /// have been queried.  The existing map is reused to avoid reallocating it each frame.
+    pub(crate) fn update_widget_rects(&mut self, widgets: &[Widget]) {
+        if !self.widget_rects_queried { return; }
+
+        self.widget_rects.clear();
+        self.widget_rects.extend(widgets.iter()
+            .filter(|widget| widget.visible())
+            .map(|widget| (widget.id().to_string(), Rect::new(widget.pos(), widget.size())))
+        );
+    }
+
+    /// Returns the number of direct children the widget with the specified `id` had in the previous frame
+    pub(crate) fn child_count(&self, id: &str) -> usize {
+        self.child_counts.get(id).copied().unwrap_or_default()
+    }
+
+    /// Returns whether the children of the widget with the specified `id` extended outside of
+    /// its bounds in the previous frame
+    pub(crate) fn had_overflow(&self, id: &str) -> bool {
+        self.overflowed_widgets.contains(id)
+    }
+
+    pub(crate) fn set_child_counts(&mut self, counts: HashMap<String, usize>, overflowed: HashSet<String>) {
+        self.child_counts = counts;
+        self.overflowed_widgets = overflowed;
+    }
+
+    pub(crate) fn last_frame_widgets(&self) -> &[(String, String)] { &self.last_frame_widgets }
+
+    pub(crate) fn set_last_frame_widgets(&mut self, widgets: Vec<(String, String)>) {
+        self.last_frame_widgets = widgets;
+    }
+
+    /// Moves any restored state for widgets that were created in the frame that just finished
+    /// into the persistent state.  Restored state for all other widgets is kept until that widget
+    /// is created or its state is used or cleared.
+    pub(crate) fn apply_restored_state<'a, I: Iterator<Item=&'a str>>(&mut self, frame_widget_ids: I) {
+        if self.restored_state.is_empty() { return; }
+
+        for id in frame_widget_ids {
+            if let Some(state) = self.restored_state.remove(id) {
+                self.mark_dirty(id);
+                self.persistent_state.entry(id.to_string()).or_insert(state);
+            }
+        }
+    }
+
+    pub(crate) fn inspected_widget(&self) -> Option<&str> { self.inspected_widget.as_deref() }
+
+    pub(crate) fn set_inspected_widget(&mut self, id: Option<String>) {
+        self.inspected_widget = id;
+    }
+
+    pub(crate) fn ime_preedit(&self) -> Option<&(String, Option<(usize, usize)>)> {
+        self.ime_preedit.as_ref()
+    }
+
+    pub(crate) fn set_os_cursor(&mut self, icon: CursorIcon) {
+        self.os_cursor = icon;
+    }
+
+    pub(crate) fn state_changed(&self, id: &str) -> bool {
+        self.changed_states.contains(id)
+    }
+
+    /// Records that the state for `id` may be modified or removed, so it is compared against
+    /// its previous value at the end of the frame
+    fn mark_dirty(&mut self, id: &str) {
+        if !self.all_states_dirty && !self.dirty_states.contains(id) {
+            self.dirty_states.insert(id.to_string());
+        }
+    }
+
+    /// Compares each `PersistentState` that may have been modified against its value at the end of the
+    /// previous frame, recording the IDs of any states that differ.  Missing states are treated as the default.
+    fn update_changed_states(&mut self) {
+        self.changed_states.clear();
+        let default = self.empty_persistent_state.snapshot();
+
+        let mut dirty = std::mem::take(&mut self.dirty_states);
+        if self.all_states_dirty {
+            self.all_states_dirty = false;
+            dirty.extend(self.persistent_state.keys().cloned());
+            dirty.extend(self.state_snapshots.keys().cloned());
+        }
+
+        for id in dirty.drain() {
+            match self.persistent_state.get(&id) {
+                Some(state) => {
+                    let snapshot = state.snapshot();
+                    if *self.state_snapshots.get(&id).unwrap_or(&default) != snapshot {
+                        self.state_snapshots.insert(id.clone(), snapshot);
+                        self.changed_states.insert(id);
+                    }
+                },
+                None => {
+                    if let Some(prev) = self.state_snapshots.remove(&id) {
+                        if prev != default {
+                            self.changed_states.insert(id);
+                        }
+                    }
+                }
+            }
+        }
+
+        // keep the allocation for the next frame
+        self.dirty_states = dirty;
+    }
+
     pub(crate) fn state_mut<T: Into<String>>(&mut self, id: T) -> &mut PersistentState {
-        self.persistent_state.entry(id.into()).or_default()
+        let id = id.into();
+        self.mark_dirty(&id);
+        let restored = self.restored_state.remove(&id);
+        self.persistent_state.entry(id).or_insert_with(|| restored.unwrap_or_default())
     }
 
     pub(crate) fn mouse_pressed_outside(&self) -> bool {
@@ -288,6 +790,23 @@ impl ContextInternal {
             self.state_mut(modal.id).is_open = false;
         }
 
+        // a drag that was released without being accepted by a drop target is cancelled
+        if !self.mouse_pressed[0] {
+            self.drag = None;
+        }
+
+        self.triggered_shortcuts.clear();
+        self.keys_pressed_this_frame.clear();
+        self.shortcut_consumed_character = false;
+
+        self.update_changed_states();
+
+        // stop timing the tooltip hover if no delayed tooltip was requested this frame
+        if !self.tooltip_hover_requested {
+            self.tooltip_hover_start = None;
+        }
+        self.tooltip_hover_requested = false;
+
         self.mouse_wheel = Point::default();
         self.mouse_clicked = [false; 3];
         self.mouse_taken_last_frame = mouse_taken;
@@ -319,9 +838,34 @@ impl Context {
             resources,
             display_size,
             scale_factor,
+            global_scale: 1.0,
+            safe_area_insets: Border::default(),
             themes,
             persistent_state: HashMap::new(),
             empty_persistent_state: PersistentState::default(),
+            restored_state: HashMap::new(),
+            state_snapshots: HashMap::new(),
+            changed_states: HashSet::new(),
+            dirty_states: HashSet::new(),
+            all_states_dirty: false,
+            widget_rects: HashMap::new(),
+            widget_rects_queried: false,
+            child_counts: HashMap::new(),
+            overflowed_widgets: HashSet::new(),
+            last_frame_widgets: Vec::new(),
+            inspected_widget: None,
+            toasts: Vec::new(),
+            next_toast_id: 0,
+            grid_columns: HashMap::new(),
+            spring_extents: HashMap::new(),
+            drag: None,
+            shortcuts: Vec::new(),
+            triggered_shortcuts: HashSet::new(),
+            keys_pressed: HashSet::new(),
+            keys_pressed_this_frame: HashSet::new(),
+            shortcut_consumed_character: false,
+            tooltip_hover_start: None,
+            tooltip_hover_requested: false,
             mouse_pos: Point::default(),
             last_mouse_pos: Point::default(),
             input_modifiers: InputModifiers::default(),
@@ -335,8 +879,19 @@ impl Context {
             mouse_pressed_outside: [false; 3],
             modal: None,
             time_millis: 0,
-            start_instant: Instant::now(),
+            last_time_instant: Instant::now(),
+            scaled_time_millis: 0.0,
+            time_scale: 1.0,
+            first_frame_instant: None,
+            frame_number: 0,
             keyboard_focus_widget: None,
+            keyboard_input: Vec::new(),
+            captured_widget: None,
+            last_click: None,
+            double_click_ms: DEFAULT_DOUBLE_CLICK_MS,
+            dropped_files: Vec::new(),
+            ime_preedit: None,
+            os_cursor: CursorIcon::Default,
             errors: HashSet::new(),
             frame_active: false,
         };
@@ -368,6 +923,13 @@ impl Context {
         }
     }
 
+    // Finds the base size of the specified image, without logging any error.
+    pub(crate) fn image_base_size(&self, id: &str) -> Option<Point> {
+        let internal = self.internal.borrow();
+        let themes = internal.themes();
+        themes.find_image(Some(id)).map(|handle| themes.image(handle).base_size())
+    }
+
     /// Returns true if thyme wants to use the mouse in the current frame, generally
     /// because the mouse is over a Thyme widget.  If this returns true, you probably
     /// want Thyme to handle input this frame, while if it returns false, your application
@@ -377,6 +939,32 @@ impl Context {
         internal.mouse_taken_last_frame.is_some() || internal.modal.is_some()
     }
 
+    /// Returns the number of frames that have been created with [`create_frame`](#method.create_frame).
+    /// During the first frame, this is one.  This may be called outside of a frame.
+    pub fn frame_number(&self) -> u64 {
+        self.internal.borrow().frame_number
+    }
+
+    /// Returns the number of milliseconds elapsed since the first frame was created with
+    /// [`create_frame`](#method.create_frame), or zero if no frame has been created yet.  Unlike
+    /// [`Frame::cur_time_millis`](struct.Frame.html#method.cur_time_millis), which is fixed for the
+    /// duration of a frame, this is measured when it is called, and may be called outside of a frame.
+    pub fn elapsed_ms(&self) -> u64 {
+        let internal = self.internal.borrow();
+        internal.first_frame_instant.map_or(0, |first| first.elapsed().as_millis() as u64)
+    }
+
+    /// Returns the screen space bounds of the widget with the specified `id`, as of the
+    /// most recently finished frame.  Returns `None` if the widget was not drawn in that frame.
+    /// This is useful for positioning OS windows or other content relative to a widget.
+    /// Widget rects are only recorded after this method is first called, so the first call
+    /// always returns `None`.
+    pub fn widget_rect(&self, id: &str) -> Option<Rect> {
+        let mut internal = self.internal.borrow_mut();
+        internal.widget_rects_queried = true;
+        internal.widget_rects.get(id).copied()
+    }
+
     /// Returns true if thyme wants to use keyboard input in the current frame, generally
     /// because a widget that accepts text input is keyboard focused.  If this returns true,
     /// you probably don't want to handle keyboard events in your own application code.
@@ -385,6 +973,20 @@ impl Context {
         internal.modal.is_some() || internal.keyboard_focus_widget.is_some()
     }
 
+    /// Returns the size of the bounding box of `text` when drawn with the font with the specified
+    /// `font_id`, wrapping lines that would be wider than `max_width`, if specified.  This uses
+    /// the most recently built theme, so it may be called outside of a frame, but returns `None`
+    /// if the theme has not been built or the font does not exist.
+    pub fn measure_text(&self, font_id: &str, text: &str, max_width: Option<f32>) -> Option<Point> {
+        let internal = self.internal.borrow();
+        let scale = internal.scale_factor();
+        let themes = internal.themes();
+        let font = themes.font(themes.find_font(Some(font_id))?.handle);
+
+        let size = font.measure(text, max_width.map(|width| width * scale));
+        Some(size / scale)
+    }
+
     pub(crate) fn internal(&self) -> &Rc<RefCell<ContextInternal>> {
         &self.internal
     }
@@ -395,12 +997,67 @@ impl Context {
         internal.scale_factor = scale;
     }
 
-    /// Set the display size
+    /// Sets a global scale `factor`, which uniformly scales the size and position of all widgets
+    /// and text.  This is applied in addition to the display scale factor, and is intended for user
+    /// controlled zoom, such as for accessibility.  The factor is clamped to between `0.5` and `2.0`.
+    /// Fonts are rasterized at a specific scale, so you will need to call
+    /// [`rebuild_fonts_only`](#method.rebuild_fonts_only) after changing the global scale to keep text sharp and
+    /// correctly sized.
+    pub fn set_global_scale(&mut self, factor: f32) {
+        let mut internal = self.internal.borrow_mut();
+        internal.global_scale = factor.clamp(0.5, 2.0);
+    }
+
+    /// Returns the current global scale factor.  See [`set_global_scale`](#method.set_global_scale).
+    pub fn global_scale(&self) -> f32 {
+        self.internal.borrow().global_scale
+    }
+
+    /// Set the display size, in physical pixels.  Thyme divides this by the current scale factor
+    /// to obtain the logical size used for layout.  This is equivalent to
+    /// [`set_display_size_physical`](#method.set_display_size_physical).
     pub fn set_display_size(&mut self, size: Point) {
         let mut internal = self.internal.borrow_mut();
         internal.display_size = size;
     }
 
+    /// Sets the display size, in physical pixels, such as the inner size of a window as reported by the
+    /// windowing system.  The logical size used for layout, as returned by
+    /// [`Frame::display_size`](struct.Frame.html#method.display_size), is this size divided by the current
+    /// scale factor.
+    pub fn set_display_size_physical(&mut self, physical_size: Point) {
+        self.set_display_size(physical_size);
+    }
+
+    /// Returns the display size, in physical pixels.  See
+    /// [`set_display_size_physical`](#method.set_display_size_physical).
+    pub fn display_size_physical(&self) -> Point {
+        self.internal.borrow().display_size()
+    }
+
+    /// Sets the insets, in logical pixels, from each edge of the display which are obscured by hardware,
+    /// such as a display notch or rounded corners.  This is normally called from platform specific code
+    /// whenever the insets change.  See [`Frame::safe_area_insets`](struct.Frame.html#method.safe_area_insets).
+    pub fn set_safe_area_insets(&mut self, insets: Border) {
+        let mut internal = self.internal.borrow_mut();
+        internal.safe_area_insets = insets;
+    }
+
+    /// Sets the rate at which the UI time, as returned by [`Frame::cur_time_millis`](struct.Frame.html#method.cur_time_millis),
+    /// advances relative to real time.  This affects timed and animated images, tooltip delays, toasts, and
+    /// anything else based on the UI time.  A `scale` of `1.0`, the default, runs at normal speed, `0.5` runs
+    /// at half speed, and `0.0` freezes the UI time.  Negative values are treated as zero.  The new scale
+    /// applies to time elapsed after the next call to [`create_frame`](#method.create_frame).
+    pub fn set_time_scale(&mut self, scale: f32) {
+        let mut internal = self.internal.borrow_mut();
+        internal.time_scale = scale.max(0.0);
+    }
+
+    /// Returns the current time scale.  See [`set_time_scale`](#method.set_time_scale).
+    pub fn time_scale(&self) -> f32 {
+        self.internal.borrow().time_scale
+    }
+
     /// Add mouse wheel event.
     pub fn add_mouse_wheel(&mut self, delta: Point) {
         let mut internal = self.internal.borrow_mut();
@@ -437,29 +1094,140 @@ impl Context {
         if internal.mouse_pressed[index] && !pressed {
             internal.mouse_clicked[index] = true;
             internal.keyboard_focus_widget = None;
+            internal.keyboard_input.clear();
         }
 
         internal.mouse_pressed[index] = pressed;
     }
 
-    /// Push a character
+    /// Registers a keyboard shortcut with the specified `id`, which is triggered when `key` is pressed while
+    /// exactly the specified `modifiers` are held, regardless of which widget has keyboard focus.  Check
+    /// whether it was triggered with [`Frame.shortcut_triggered`](struct.Frame.html#method.shortcut_triggered).
+    /// Multiple shortcuts may share a key if their modifiers differ.  Registering a shortcut with an `id` that
+    /// is already registered replaces it.
+    pub fn register_shortcut(&mut self, id: &str, key: KeyCode, modifiers: InputModifiers) {
+        let mut internal = self.internal.borrow_mut();
+        internal.shortcuts.retain(|shortcut| shortcut.id != id);
+        internal.shortcuts.push(Shortcut { id: id.to_string(), key, modifiers });
+    }
+
+    /// Set the pressed state of the specified keyboard `key`.  If this press triggers a
+    /// [`shortcut`](#method.register_shortcut), the character generated by the key press is not
+    /// sent to the keyboard focused widget.
+    pub fn set_key_pressed(&mut self, key: KeyCode, pressed: bool) {
+        let mut internal = self.internal.borrow_mut();
+
+        if !pressed {
+            internal.keys_pressed.remove(&key);
+            return;
+        }
+
+        // ignore key repeats
+        if !internal.keys_pressed.insert(key) { return; }
+        internal.keys_pressed_this_frame.insert(key);
+
+        let modifiers = internal.input_modifiers;
+        let triggered: Vec<String> = internal.shortcuts.iter()
+            .filter(|shortcut| shortcut.key == key && shortcut.modifiers == modifiers)
+            .map(|shortcut| shortcut.id.to_string())
+            .collect();
+
+        if !triggered.is_empty() {
+            // only keys that send a character have one to swallow
+            internal.shortcut_consumed_character = key.generates_text();
+            internal.triggered_shortcuts.extend(triggered);
+        }
+    }
+
+    /// Adds a file that has been dropped onto the window, such as from winit's `DroppedFile` event.  The path
+    /// is available during the next frame via [`Frame.dropped_files`](struct.Frame.html#method.dropped_files),
+    /// unless it is first removed with [`take_dropped_files`](#method.take_dropped_files).
+    pub fn push_dropped_file<P: Into<PathBuf>>(&mut self, path: P) {
+        let mut internal = self.internal.borrow_mut();
+        internal.dropped_files.push(path.into());
+    }
+
+    /// Removes and returns the paths of all files dropped onto the window since the last frame was created.
+    /// Files taken here will not be available in [`Frame.dropped_files`](struct.Frame.html#method.dropped_files).
+    pub fn take_dropped_files(&mut self) -> Vec<PathBuf> {
+        let mut internal = self.internal.borrow_mut();
+        std::mem::take(&mut internal.dropped_files)
+    }
+
+    /// Sets the maximum time, in milliseconds, between two clicks on a widget for them to count as a
+    /// double click.  See [`WidgetState.double_clicked`](struct.WidgetState.html#structfield.double_clicked).
+    /// Defaults to 500 milliseconds.
+    pub fn set_double_click_ms(&mut self, millis: u32) {
+        self.internal.borrow_mut().double_click_ms = millis;
+    }
+
+    /// Returns the ID of the widget that has captured the mouse, if any.  See
+    /// [`Frame.capture_mouse`](struct.Frame.html#method.capture_mouse).
+    pub fn captured_widget(&self) -> Option<String> {
+        self.internal.borrow().captured_widget.clone()
+    }
+
+    /// Sets the shape of the operating system mouse cursor.  The cursor is reset to
+    /// [`CursorIcon::Default`](enum.CursorIcon.html) at the start of each frame, and is then set by the
+    /// `cursor` theme field of the hovered widget, if any, or by [`Frame.set_os_cursor`](struct.Frame.html#method.set_os_cursor).
+    /// When using [`WinitIo`](struct.WinitIo.html), call [`WinitIo.update_cursor`](struct.WinitIo.html#method.update_cursor)
+    /// to apply the cursor to the window.
+    pub fn set_os_cursor(&mut self, icon: CursorIcon) {
+        self.internal.borrow_mut().set_os_cursor(icon);
+    }
+
+    /// Returns the operating system mouse cursor shape requested by the most recent frame.  See
+    /// [`set_os_cursor`](#method.set_os_cursor).
+    pub fn os_cursor(&self) -> CursorIcon {
+        self.internal.borrow().os_cursor
+    }
+
+    /// Sets the text currently being composed with an input method editor (IME), such as from winit's
+    /// `Ime::Preedit` event.  The text is shown inline in the keyboard focused
+    /// [`input_field`](struct.Frame.html#method.input_field), but is not added to the field's text until it is
+    /// committed with [`commit_ime`](#method.commit_ime).  `cursor` is the byte range of the cursor within `text`,
+    /// or `None` to hide the cursor.  An empty `text` ends composition.
+    pub fn set_ime_preedit(&mut self, text: String, cursor: Option<(usize, usize)>) {
+        let mut internal = self.internal.borrow_mut();
+        internal.ime_preedit = if text.is_empty() { None } else { Some((text, cursor)) };
+    }
+
+    /// Commits `text` composed with an input method editor (IME), such as from winit's `Ime::Commit` event.
+    /// This ends any composition and pushes each character of `text`, as in [`push_character`](#method.push_character).
+    pub fn commit_ime(&mut self, text: &str) {
+        self.internal.borrow_mut().ime_preedit = None;
+        for c in text.chars() {
+            self.push_character(c);
+        }
+    }
+
+    /// Push a character.  The character is sent to the widget that currently has keyboard focus, if any, and
+    /// is available during the next frame via [`Frame.keyboard_input`](struct.Frame.html#method.keyboard_input).
     pub fn push_character(&mut self, c: char) {
         let mut internal = self.internal.borrow_mut();
 
+        // the key press that generated this character was used by a shortcut
+        if internal.shortcut_consumed_character {
+            internal.shortcut_consumed_character = false;
+            return;
+        }
+
         let id = match &internal.keyboard_focus_widget {
             Some(id) => id.to_string(),
             None => return,
         };
 
+        internal.keyboard_input.push(c);
         let state = internal.state_mut(id);
         state.characters.push(c);
     }
 
     /// Set mouse position. 
     /// You need to take into account the scale factor when setting this. (see `demo_glium.rs`).
+    /// The [`global_scale`](#method.set_global_scale) is applied automatically.
     pub fn set_mouse_pos(&mut self, pos: Point) {
         let mut internal = self.internal.borrow_mut();
-        internal.mouse_pos = pos;
+        internal.mouse_pos = pos / internal.global_scale;
     }
 
     /// Adds the specified path as a source file for the resources being used
@@ -493,12 +1261,229 @@ impl Context {
         internal.resources.clear_data_cache();
         internal.resources.cache_data()?;
 
-        let scale_factor = internal.scale_factor;
+        let scale_factor = internal.scale_factor();
         let themes = internal.resources.build_assets(renderer, scale_factor)?;
         internal.themes = themes;
         Ok(())
     }
 
+    /// Rebuilds only the images of this context, re-reading the theme and any image files and registering
+    /// the textures with the `renderer` again.  The image sets are taken from the re-read theme, but fonts
+    /// and widget themes are kept as they are, making this much cheaper than [`rebuild_all`](#method.rebuild_all)
+    /// when only images have changed.  Images removed from the theme, and images added with
+    /// [`add_image_at_runtime`](#method.add_image_at_runtime) or
+    /// [`add_image_data_at_runtime`](#method.add_image_data_at_runtime), keep their current version.
+    /// The theme and images are read and built before anything is registered with the `renderer`.  If any
+    /// errors are encountered, this will return `Err` and the images of the context are unchanged, unless
+    /// the `renderer` itself fails to register a texture.
+    pub fn rebuild_images_only<R: Renderer>(&mut self, renderer: &mut R) -> Result<(), Error> {
+        let mut internal = self.internal.borrow_mut();
+        let internal = &mut *internal;
+        internal.resources.rebuild_images(renderer, &mut internal.themes)
+    }
+
+    /// Rebuilds only the fonts of this context, re-reading any font files and regenerating
+    /// the glyph atlases with the `renderer`.  Images and widget themes are kept as they are.
+    /// Fonts may be modified, but not removed from the theme.  If any errors are encountered,
+    /// this will return `Err` and the fonts of the context are unchanged.
+    pub fn rebuild_fonts_only<R: Renderer>(&mut self, renderer: &mut R) -> Result<(), Error> {
+        let mut internal = self.internal.borrow_mut();
+        let internal = &mut *internal;
+        let scale_factor = internal.scale_factor();
+        internal.resources.rebuild_fonts(renderer, &mut internal.themes, scale_factor)
+    }
+
+    /// Sets a `scale` multiplying the size of every font in the theme, and then rebuilds the fonts as in
+    /// [`rebuild_fonts_only`](#method.rebuild_fonts_only).  Unlike the [`global scale`](#method.set_global_scale),
+    /// this only affects text, making it useful for accessibility options.  The scale is clamped to between `0.5`
+    /// and `3.0`, and is kept when the context is [`rebuilt`](#method.rebuild_all).  The default is `1.0`.
+    /// If any errors are encountered in rebuilding, this will return `Err` and the fonts of the context are
+    /// unchanged, although the new scale is still applied by subsequent rebuilds.  A `scale` that is infinite
+    /// or NaN is rejected with `Err`, leaving both the scale and the fonts unchanged.
+    pub fn set_font_scale<R: Renderer>(&mut self, renderer: &mut R, scale: f32) -> Result<(), Error> {
+        if !scale.is_finite() {
+            return Err(Error::Theme(format!("Invalid font scale {}.  The scale must be finite.", scale)));
+        }
+
+        self.internal.borrow_mut().resources.set_font_scale(scale.clamp(0.5, 3.0));
+        self.rebuild_fonts_only(renderer)
+    }
+
+    /// Returns the current font scale.  See [`set_font_scale`](#method.set_font_scale).
+    pub fn font_scale(&self) -> f32 {
+        self.internal.borrow().resources.font_scale()
+    }
+
+    /// Sets the theme palette color `name` to `color`, adding it to the palette if it is not already present.
+    /// Every widget theme color referencing the palette color, such as `text_color: "$primary"`, is updated,
+    /// taking effect from the next frame.  Other widget themes are left as they are.  This is useful for
+    /// switching between light and dark color schemes at runtime.  Palette colors set this way are discarded
+    /// if the context is [`rebuilt`](#method.rebuild_all).
+    pub fn set_palette_color(&mut self, name: &str, color: Color) {
+        let mut internal = self.internal.borrow_mut();
+        internal.themes.set_palette_color(name, color);
+    }
+
+    /// Returns the current theme palette color `name`, if it exists.  See
+    /// [`set_palette_color`](#method.set_palette_color)
+    pub fn palette_color(&self, name: &str) -> Option<Color> {
+        let internal = self.internal.borrow();
+        internal.themes.palette_color(name)
+    }
+
+    /// Adds the image `image_id`, described by `def`, to the existing image set `set_id`.  The image is
+    /// then available to widgets as `set_id/image_id`, for example with
+    /// [`WidgetBuilder::foreground`](struct.WidgetBuilder.html#method.foreground).  The image is taken from the
+    /// texture the image set already uses, so this is useful for picking out procedurally placed regions, such as
+    /// icons, from a texture.  To add an image with new pixel data, use
+    /// [`add_image_data_at_runtime`](#method.add_image_data_at_runtime).  If the image already exists, it is
+    /// replaced.  Returns `Err` if the image set does not exist, the definition refers to images not in the set,
+    /// or the definition is an `Alias` or `Group`.
+    /// Images added this way are discarded if the context is [`rebuilt`](#method.rebuild_all).
+    pub fn add_image_at_runtime(&mut self, set_id: &str, image_id: &str, def: ImageDefinition) -> Result<(), Error> {
+        let mut internal = self.internal.borrow_mut();
+        internal.themes.add_image(set_id, image_id, def)
+    }
+
+    /// Adds the image `image_id` to the existing image set `set_id`, uploading the RGBA pixel `data` of the
+    /// specified `dimensions` to the `renderer` as a new texture.  The image is a `Simple` image covering the
+    /// whole texture, drawn at the image set's scale, and is available to widgets as `set_id/image_id`.  This is
+    /// useful for icons that are generated procedurally or loaded from save files.  The image may later be
+    /// changed with [`update_image_region`](#method.update_image_region).  If the image already exists, it is
+    /// replaced.  Returns `Err` if the image set does not exist, `data` does not contain 4 bytes for each texel,
+    /// or the renderer fails to register the texture.  Images added this way must be added again after the
+    /// context is [`rebuilt`](#method.rebuild_all), including when only [`images`](#method.rebuild_images_only)
+    /// are rebuilt.
+    pub fn add_image_data_at_runtime<R: Renderer>(
+        &mut self,
+        renderer: &mut R,
+        set_id: &str,
+        image_id: &str,
+        data: &[u8],
+        dimensions: (u32, u32),
+    ) -> Result<(), Error> {
+        let expected = dimensions.0 as usize * dimensions.1 as usize * 4;
+        if data.len() != expected || expected == 0 {
+            return Err(Error::Theme(format!(
+                "Expected {} bytes for a {}x{} image '{}', but got {}", expected, dimensions.0, dimensions.1, image_id, data.len()
+            )));
+        }
+
+        let mut internal = self.internal.borrow_mut();
+        internal.themes.add_texture_image(renderer, set_id, image_id, data, dimensions)
+    }
+
+    /// Replaces part of the `Simple` image `image_id` with the RGBA pixel `data`, via the `renderer`.
+    /// `rect` is the region to replace, in the same logical coordinates as the image's size in the theme,
+    /// and is converted to texels.  `data` must contain 4 bytes for each texel in the region, starting at
+    /// the top left.  This is useful for images which change frequently, such as a minimap.  Returns `Err`
+    /// if the image is not found or not `Simple`, the region lies outside the image, the data has the wrong
+    /// length, or the renderer does not support updating textures.
+    pub fn update_image_region<R: Renderer>(
+        &mut self,
+        renderer: &mut R,
+        image_id: &str,
+        data: &[u8],
+        rect: Rect,
+    ) -> Result<(), Error> {
+        let internal = self.internal.borrow();
+        let (texture, pos, size) = internal.themes.image_texel_region(image_id, rect)?;
+
+        let expected = (size.0 * size.1 * 4) as usize;
+        if data.len() != expected {
+            return Err(Error::Theme(format!(
+                "Expected {} bytes for a {}x{} region of image '{}', but got {}", expected, size.0, size.1, image_id, data.len()
+            )));
+        }
+
+        renderer.update_texture_region(texture, data, pos, size)
+    }
+
+    /// Removes the image with the specified `full_id`, in the form `set_id/image_id`, along with any
+    /// aliases to it.  Any widgets still using the image will draw nothing in its place.  Returns `Err`
+    /// if the image does not exist.
+    pub fn remove_image(&mut self, full_id: &str) -> Result<(), Error> {
+        let mut internal = self.internal.borrow_mut();
+        internal.themes.remove_image(full_id)
+    }
+
+    /// Returns the full IDs of all widget themes in the current theme, including child themes such as
+    /// `window/titlebar`, in sorted order.  This is useful for tools which show a picker of available themes.
+    pub fn all_widget_ids(&self) -> Vec<String> {
+        let internal = self.internal.borrow();
+        let mut ids: Vec<String> = internal.themes.all_widget_ids().map(|id| id.to_string()).collect();
+        ids.sort();
+        ids
+    }
+
+    /// Returns the full IDs of all widget themes starting with `prefix`, in sorted order.  For example,
+    /// a `prefix` of `window/` returns all children of the `window` theme.  See
+    /// [`all_widget_ids`](#method.all_widget_ids)
+    pub fn widgets_with_prefix(&self, prefix: &str) -> Vec<String> {
+        let internal = self.internal.borrow();
+        let mut ids: Vec<String> = internal.themes.widgets_with_prefix(prefix).map(|id| id.to_string()).collect();
+        ids.sort();
+        ids
+    }
+
+    /// Exports the current theme of this context using the specified `serializer`.  For example,
+    /// `serializer` could be a [`serde_yaml Serializer`](https://docs.serde.rs/serde_yaml/struct.Serializer.html).
+    /// The exported theme has all `from` references resolved, and may be passed back in to
+    /// [`ContextBuilder#register_theme`](struct.ContextBuilder.html#method.register_theme).  This is useful
+    /// for tools which read, modify, and then save a theme.
+    pub fn export_theme<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let internal = self.internal.borrow();
+        let definition = internal.themes.to_definition();
+        serde::Serialize::serialize(&definition, serializer)
+    }
+
+    /// Serializes the [`PersistentState`](struct.PersistentState.html) of all widgets, such as whether
+    /// windows are open, their positions, scroll amounts, and text.  Transient state, such as animations
+    /// and keyboard input, is not included.  The result may be saved and later passed to
+    /// [`deserialize_state`](#method.deserialize_state), even by a newer version of Thyme, as the format is versioned.
+    pub fn serialize_state(&self) -> Vec<u8> {
+        let internal = self.internal.borrow();
+        let states = internal.restored_state.iter()
+            .chain(internal.persistent_state.iter())
+            .map(|(id, state)| (id.to_string(), SavedState::new(state)))
+            .collect();
+
+        let saved = SavedStates { version: SAVED_STATE_VERSION, states };
+        serde_yaml::to_vec(&saved).expect("Persistent state should always be serializable")
+    }
+
+    /// Restores [`PersistentState`](struct.PersistentState.html) previously saved with
+    /// [`serialize_state`](#method.serialize_state).  The state for each widget is kept until that
+    /// widget is next created or its state is used, so widgets such as closed windows or inactive tabs
+    /// that are not created right away still receive their saved state.  Restored state is discarded when
+    /// the widget is cleared, such as with [`Frame::clear`](struct.Frame.html#method.clear), or by calling
+    /// this method again.
+    /// Returns an `Err` and makes no changes if `data` is not valid or was written in an
+    /// unsupported format version.
+    pub fn deserialize_state(&mut self, data: &[u8]) -> Result<(), Error> {
+        let version: SavedStateVersion = serde_yaml::from_slice(data).map_err(|e| Error::Serde(e.to_string()))?;
+        if version.version != SAVED_STATE_VERSION {
+            return Err(Error::Serde(format!("Unsupported persistent state version: {}", version.version)));
+        }
+
+        let saved: SavedStates = serde_yaml::from_slice(data).map_err(|e| Error::Serde(e.to_string()))?;
+
+        let mut internal = self.internal.borrow_mut();
+        internal.restored_state.clear();
+        for (id, state) in saved.states {
+            // widgets that already exist are updated immediately
+            match internal.persistent_state.get_mut(&id) {
+                Some(existing) => {
+                    *existing = state.into_state();
+                    internal.dirty_states.insert(id);
+                },
+                None => { internal.restored_state.insert(id, state.into_state()); },
+            }
+        }
+
+        Ok(())
+    }
+
     /// Checks the internal live reload thread to see if any file notifications have occurred
     /// since the last check.  If so, will fully rebuild the theme.  If any errors are encountered
     /// in the process of rebuilding the theme, will return the `Err` and no changes are made to
@@ -506,7 +1491,7 @@ impl Context {
     /// (see [`BuildOptions`](struct.BuildOptions.html)), this function will do nothing.
     pub fn check_live_reload<R: Renderer>(&mut self, renderer: &mut R) -> Result<(), Error> {
         let mut internal = self.internal.borrow_mut();
-        let scale_factor = internal.scale_factor;
+        let scale_factor = internal.scale_factor();
 
         let themes = internal.resources.check_live_reload(renderer, scale_factor)?;
 
@@ -524,6 +1509,8 @@ impl Context {
         let now = Instant::now();
 
         let anim_state;
+        let keyboard_input;
+        let dropped_files;
         let display_size = {
             let mut context = self.internal.borrow_mut();
 
@@ -533,22 +1520,35 @@ impl Context {
 
             context.frame_active = true;
 
-            let elapsed = (now - context.start_instant).as_millis() as u32;
-            context.time_millis = elapsed;
+            // the UI time advances by the real elapsed time multiplied by the time scale
+            let delta = (now - context.last_time_instant).as_secs_f64() * 1000.0;
+            context.last_time_instant = now;
+            context.scaled_time_millis += delta * context.time_scale as f64;
+            context.time_millis = context.scaled_time_millis as u32;
+            context.first_frame_instant.get_or_insert(now);
+            context.frame_number += 1;
 
             if context.mouse_pressed[0] {
                 anim_state = AnimState::new(AnimStateKey::Pressed);
             } else {
                 anim_state = AnimState::normal();
+
+                // capture only lasts while the primary button is held
+                context.captured_widget = None;
             }
 
+            let input = std::mem::take(&mut context.keyboard_input);
+            keyboard_input = context.keyboard_focus_widget.clone().map(|id| (id, input));
+            dropped_files = std::mem::take(&mut context.dropped_files);
+            context.os_cursor = CursorIcon::Default;
+
             context.display_size() / context.scale_factor()
         };
 
         let context = Context { internal: Rc::clone(&self.internal) };
 
         let root = Widget::root(display_size);
-        Frame::new(context, root, anim_state)
+        Frame::new(context, root, anim_state, keyboard_input, dropped_files)
     }
 }
 
@@ -2,7 +2,7 @@ use std::{path::Path};
 
 use crate::{Error, Context};
 use crate::{resource::ResourceSet};
-use crate::theme_definition::{ThemeDefinition};
+use crate::theme_definition::{FontStyle, ThemeDefinition};
 use crate::render::{Renderer, IO};
 
 /// Global options that may be specified when building the Thyme context with
@@ -47,6 +47,38 @@ impl ContextBuilder {
         ContextBuilder::new(BuildOptions::default())
     }
 
+    /**
+    Creates a new `ContextBuilder`, using the default [`BuildOptions`](struct.BuildOptions.html), with
+    Thyme's built-in theme already registered.  The built-in theme draws all widgets using solid colors,
+    so it does not need any image files, and includes definitions for all of the basic widgets in
+    [`recipes`](struct.Frame.html).  It does use the font source `default`, which you must register
+    yourself, i.e. with [`register_font_from_file`](#method.register_font_from_file).
+
+    The built-in theme is registered as a string source with the id `thyme_default` and the lowest possible
+    priority.  Themes you register with [`register_theme_from_string`](#method.register_theme_from_string),
+    [`register_theme_with_priority`](#method.register_theme_with_priority), or
+    [`register_theme_from_files`](#method.register_theme_from_files) are merged on top of it, so you only
+    need to specify the widgets you want to add or change.  Calling [`register_theme`](#method.register_theme)
+    or [`register_theme_definition`](#method.register_theme_definition) replaces it entirely.
+
+    # Example
+    ```no_run
+        let mut context_builder = thyme::ContextBuilder::with_default_theme();
+        context_builder.register_font_from_file("default", std::path::Path::new("fonts/Roboto-Medium.ttf"));
+        ...
+    ```
+    **/
+    pub fn with_default_theme() -> ContextBuilder {
+        let mut builder = ContextBuilder::with_defaults();
+        builder.resources.register_theme_from_string(
+            "thyme_default".to_string(),
+            include_str!("default_theme.yml").to_string(),
+            serde_yaml::from_str::<serde_yaml::Value>,
+            i32::MIN,
+        );
+        builder
+    }
+
     /// Creates a new `ContextBuilder`, using the specified [`BuildOptions`](struct.BuildOptions.html)
     pub fn new(options: BuildOptions) -> ContextBuilder {
         ContextBuilder {
@@ -67,6 +99,15 @@ impl ContextBuilder {
         Ok(())
     }
 
+    /// Sets the theme for this context to the specified `theme`, such as one constructed in code with a
+    /// [`ThemeDefinitionBuilder`](struct.ThemeDefinitionBuilder.html).  As with
+    /// [`register_theme`](#method.register_theme), only the last registered theme is used.
+    pub fn register_theme_definition(&mut self, theme: ThemeDefinition) {
+        log::debug!("Registering theme definition");
+
+        self.resources.register_theme(theme);
+    }
+
     /// Sets the theme for this context by reading from the file at the specified `path`.  The files are first
     /// read to a string and then passed to the function `f`, which returns a serde Deserializable object.  That
     /// object is then deserialized as the theme.  See [`register_theme`](#method.register_theme)
@@ -86,10 +127,13 @@ impl ContextBuilder {
         Ok(())
     }
 
-    /// Sets the theme for this context by reading from the specified list of files.  The files are each read into a
-    /// string and then concatenated together.  The string is passed to the function `f` which returns a serde
-    /// Deserializable object, which is finally deserialized into the theme.  See
-    /// [`register_theme`](#method.register_theme)
+    /// Sets the theme for this context by reading from the specified list of files.  Each file is read into a
+    /// string, which is passed to the function `f`.  This returns a serde Deserializable object, which is then
+    /// deserialized as a theme.  The themes from each file are merged together in the order of `paths`, with
+    /// later files overriding any fonts, image sets, or widgets with the same ID in earlier files.  Theme files
+    /// have a priority of zero, and are merged after any string sources with the same priority.  See
+    /// [`register_theme`](#method.register_theme) and
+    /// [`register_theme_with_priority`](#method.register_theme_with_priority)
     pub fn register_theme_from_files<T, E, F>(
         &mut self,
         paths: &[&Path],
@@ -105,6 +149,53 @@ impl ContextBuilder {
         Ok(())
     }
 
+    /// Registers the theme source `src` with the specified `id`, such as a theme embedded in your binary
+    /// with `include_str!`.  The string is passed to the function `f`, which returns a serde Deserializable
+    /// object, which is then deserialized as a theme.  String sources are merged together in the order they
+    /// are registered, followed by any theme files, with later sources overriding earlier ones.  Registering
+    /// another source with the same `id` replaces the previous one.  Unlike files, string sources never
+    /// trigger a live reload.  This is equivalent to calling
+    /// [`register_theme_with_priority`](#method.register_theme_with_priority) with a priority of zero.  See
+    /// [`register_theme_from_files`](#method.register_theme_from_files)
+    pub fn register_theme_from_string<T, E, F>(
+        &mut self,
+        id: &str,
+        src: &str,
+        f: F,
+    ) -> Result<(), Error> where
+        T: 'static + for<'de> serde::Deserializer<'de>,
+        E: 'static + std::error::Error,
+        F: 'static + Fn(&str) -> Result<T, E>
+    {
+        log::debug!("Registering theme from string '{}'", id);
+
+        self.resources.register_theme_from_string(id.to_string(), src.to_string(), f, 0);
+        Ok(())
+    }
+
+    /// Registers the theme source `src` with the specified `id` and `priority`.  This is the same as
+    /// [`register_theme_from_string`](#method.register_theme_from_string), except that sources are merged
+    /// from lowest to highest `priority`, so sources with a higher priority override those with a lower one,
+    /// regardless of the order they were registered in.  Sources with equal priorities are merged in
+    /// registration order.  Theme files have a priority of zero, and are merged after any string sources
+    /// with a priority of zero.
+    pub fn register_theme_with_priority<T, E, F>(
+        &mut self,
+        id: &str,
+        src: &str,
+        f: F,
+        priority: i32,
+    ) -> Result<(), Error> where
+        T: 'static + for<'de> serde::Deserializer<'de>,
+        E: 'static + std::error::Error,
+        F: 'static + Fn(&str) -> Result<T, E>
+    {
+        log::debug!("Registering theme from string '{}' with priority {}", id, priority);
+
+        self.resources.register_theme_from_string(id.to_string(), src.to_string(), f, priority);
+        Ok(())
+    }
+
     /// Registers the font data located in the file at the specified `path` with Thyme via the specified `id`.
     /// See [`register_font`](#method.register_font)
     pub fn register_font_from_file<T: Into<String>>(
@@ -114,7 +205,20 @@ impl ContextBuilder {
     ) {
         let id = id.into();
         log::debug!("Reading font source '{}' from file: '{:?}'", id, path);
-        self.resources.register_font_from_file(id, path);
+        self.resources.register_font_from_file(id, path, FontStyle::Normal);
+    }
+
+    /// Registers the font data located in the file at the specified `path` for a particular style variant
+    /// of the font source `id`.  See [`register_font_source_with_style`](#method.register_font_source_with_style)
+    pub fn register_font_file_with_style<T: Into<String>>(
+        &mut self,
+        id: T,
+        path: &Path,
+        style: FontStyle,
+    ) {
+        let id = id.into();
+        log::debug!("Reading font source '{}' with style {:?} from file: '{:?}'", id, style, path);
+        self.resources.register_font_from_file(id, path, style);
     }
 
     /// Registers the font data for use with Thyme via the specified `id`.  The `data` must consist
@@ -127,7 +231,34 @@ impl ContextBuilder {
     ) {
         let id = id.into();
         log::debug!("Registering font source '{}'", id);
-        self.resources.register_font_from_data(id, data);
+        self.resources.register_font_from_data(id, data, FontStyle::Normal);
+    }
+
+    /// Registers the font data for a particular style variant of the font source `id`.  The `data`
+    /// must consist of the full binary for a valid TTF or OTF file containing that style.
+    /// Fonts in your theme file select this variant by specifying both the `source` and the `style`.
+    /// See [`register_font`](#method.register_font)
+    pub fn register_font_source_with_style<T: Into<String>>(
+        &mut self,
+        id: T,
+        data: Vec<u8>,
+        style: FontStyle,
+    ) {
+        let id = id.into();
+        log::debug!("Registering font source '{}' with style {:?}", id, style);
+        self.resources.register_font_from_data(id, data, style);
+    }
+
+    /// Registers the font source `fallback_id` as the fallback for the font source `primary_id`.  Any characters
+    /// not present in the primary font are instead taken from the fallback, or from its own fallback, and so on.
+    /// This allows, for example, mixing a Latin font with a CJK font.  The fallback chain must not be circular,
+    /// or [`build`](#method.build) will return an error.  Registering another fallback for the same `primary_id`
+    /// replaces the previous one.
+    pub fn register_font_fallback<T: Into<String>, U: Into<String>>(&mut self, primary_id: T, fallback_id: U) {
+        let primary_id = primary_id.into();
+        let fallback_id = fallback_id.into();
+        log::debug!("Registering font source '{}' as the fallback for '{}'", fallback_id, primary_id);
+        self.resources.register_font_fallback(primary_id, fallback_id);
     }
 
     /// Reads a texture from the specified image file.  See [`register_texture`](#method.register_texture).
@@ -160,6 +291,22 @@ impl ContextBuilder {
         self.resources.register_image_from_data(id, data, dimensions.0, dimensions.1);
     }
 
+    /// Sets whether mipmaps should be generated for all registered textures.  Mipmapping reduces
+    /// aliasing when images are drawn at a smaller size than their source, such as scaled down
+    /// thumbnails.  Currently only the [`GLRenderer`](struct.GLRenderer.html) supports this.  The
+    /// default value is `false`.
+    pub fn set_texture_mipmaps(&mut self, enabled: bool) {
+        self.resources.set_texture_mipmaps(enabled);
+    }
+
+    /// Sets the font, specified by its `font_id` in the theme's `fonts` section, used by any widget
+    /// whose theme does not specify a font.  Without a default font, text on such widgets is not drawn.
+    /// Building the context will fail if the theme does not define this font.
+    /// See [`Frame::default_font`](struct.Frame.html#method.default_font).
+    pub fn set_default_font(&mut self, font_id: &str) {
+        self.resources.set_default_font(font_id.to_string());
+    }
+
     /// Consumes this builder and releases the borrows on the [`Renderer`](trait.Renderer.html) and [`IO`](trait.IO.html),
     /// so they can be used further.  Builds a [`Context`](struct.Context.html).
     pub fn build<R: Renderer, I: IO>(mut self, renderer: &mut R, io: &mut I) -> Result<Context, Error> {
@@ -1,17 +1,36 @@
+use std::borrow::Cow;
+
 use rustc_hash::FxHashMap;
 
-use crate::theme_definition::CharacterRange;
+use crate::theme_definition::{CharacterRange, FontStyle};
 use crate::render::{TexCoord, DrawList, FontHandle, DummyDrawList};
 use crate::{Point, Rect, Align, Color};
 
 pub struct FontSource {
     pub(crate) font: rusttype::Font<'static>,
+    pub(crate) style: FontStyle,
+    pub(crate) fallbacks: Vec<rusttype::Font<'static>>,
+}
+
+impl FontSource {
+    /// Returns the style variant this font source was registered with.
+    pub fn style(&self) -> FontStyle { self.style }
+
+    /// Returns the first font in the fallback chain, starting with this source's
+    /// own font, which contains a glyph for `c`.  If none do, returns this source's font.
+    fn font_for_char(&self, c: char) -> &rusttype::Font<'static> {
+        std::iter::once(&self.font)
+            .chain(self.fallbacks.iter())
+            .find(|font| font.glyph(c).id() != rusttype::GlyphId(0))
+            .unwrap_or(&self.font)
+    }
 }
 
 pub struct FontChar {
     pub size: Point,
     pub(crate) tex_coords: [TexCoord; 2],
     pub x_advance: f32,
+    pub x_offset: f32,
     pub y_offset: f32,
 }
 
@@ -21,14 +40,19 @@ impl Default for FontChar {
             size: Point::default(),
             tex_coords: [TexCoord::new(0.0, 0.0), TexCoord::new(0.0, 0.0)],
             x_advance: 0.0,
+            x_offset: 0.0,
             y_offset: 0.0,
         }
     }
 }
 
+/// A handle to a font in the current theme, along with its line height in logical pixels.
 #[derive(Copy, Clone, Debug)]
 pub struct FontSummary {
+    /// The handle identifying this font with the renderer
     pub handle: FontHandle,
+
+    /// The height of a single line of text, in logical pixels
     pub line_height: f32,
 }
 
@@ -59,6 +83,34 @@ impl Font {
 
     pub fn handle(&self) -> FontHandle { self.handle }
 
+    fn text_width(&self, text: &str) -> f32 {
+        text.chars().filter_map(|c| self.char(c)).map(|c| c.x_advance).sum()
+    }
+
+    /// Truncates `text` so that it fits on a single line of the specified `width`, replacing the
+    /// removed characters with `ellipsis`.  If any character of `ellipsis` is not present in this
+    /// font, `...` is used instead.  Text that already fits is returned unchanged.
+    pub(crate) fn truncate<'a>(&self, text: &'a str, width: f32, ellipsis: &str) -> Cow<'a, str> {
+        if self.text_width(text) <= width { return Cow::Borrowed(text); }
+
+        let ellipsis = if ellipsis.chars().all(|c| self.char(c).is_some()) {
+            ellipsis
+        } else {
+            "..."
+        };
+
+        let max_width = width - self.text_width(ellipsis);
+        let mut cur_width = 0.0;
+        let mut end = 0;
+        for (index, c) in text.char_indices() {
+            cur_width += self.char(c).map_or(0.0, |c| c.x_advance);
+            if cur_width > max_width { break; }
+            end = index + c.len_utf8();
+        }
+
+        Cow::Owned(format!("{}{}", &text[..end], ellipsis))
+    }
+
     pub(crate) fn layout(
         &self,
         area_size: Point,
@@ -89,6 +141,24 @@ impl Font {
         *cursor = renderer.pos;
     }
 
+    /// Computes the size of the bounding box of `text` when rendered with this font,
+    /// wrapping lines that would exceed `max_width`.  All values are in physical pixels.
+    pub(crate) fn measure(&self, text: &str, max_width: Option<f32>) -> Point {
+        let mut draw_list = DummyDrawList::new();
+        let mut renderer = FontRenderer::new(
+            self,
+            &mut draw_list,
+            Point::new(max_width.unwrap_or(f32::INFINITY), 0.0),
+            Point::default(),
+            Align::TopLeft,
+            Color::white(),
+            Rect::default(),
+        );
+        renderer.render(text);
+
+        Point::new(renderer.max_line_width, renderer.size.y)
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn draw<D: DrawList>(
         &self,
@@ -127,6 +197,7 @@ struct FontRenderer<'a, D> {
 
     pos: Point,
     size: Point,
+    max_line_width: f32,
     cur_line_index: usize,
 
     cur_word: Vec<&'a FontChar>,
@@ -156,6 +227,7 @@ impl<'a, D: DrawList> FontRenderer<'a, D> {
             initial_pos: pos,
             pos,
             size: Point::default(),
+            max_line_width: 0.0,
             cur_line_index: initial_index,
             cur_word: Vec::new(),
             cur_word_width: 0.0,
@@ -200,6 +272,7 @@ impl<'a, D: DrawList> FontRenderer<'a, D> {
         }
 
         self.draw_cur_word();
+        self.max_line_width = self.max_line_width.max(self.size.x);
 
         if self.cur_line_index < self.draw_list.len() {    
             // adjust characters on the last line
@@ -213,7 +286,7 @@ impl<'a, D: DrawList> FontRenderer<'a, D> {
     fn draw_cur_word(&mut self) {
         for font_char in self.cur_word.drain(..) {
             self.draw_list.push_rect(
-                [self.pos.x, self.pos.y + font_char.y_offset + self.font.ascent],
+                [self.pos.x + font_char.x_offset, self.pos.y + font_char.y_offset + self.font.ascent],
                 [font_char.size.x, font_char.size.y],
                 font_char.tex_coords,
                 self.color,
@@ -230,6 +303,7 @@ impl<'a, D: DrawList> FontRenderer<'a, D> {
         self.size.y += self.font.line_height;
 
         self.adjust_line_x();
+        self.max_line_width = self.max_line_width.max(self.size.x);
         self.pos.x = self.initial_pos.x;
         self.cur_line_index = self.draw_list.len();
         self.size.x = 0.0;
@@ -247,6 +321,7 @@ impl<'a, D: DrawList> FontRenderer<'a, D> {
             Bot =>      self.area_size.y - self.size.y,
             Top =>      0.0,
             Center =>   (self.area_size.y - self.size.y) / 2.0,
+            Stretch | StretchHorizontal | StretchVertical => 0.0,
         };
 
         self.pos.y += y_offset;
@@ -268,6 +343,7 @@ impl<'a, D: DrawList> FontRenderer<'a, D> {
             Bot =>      (self.area_size.x - self.size.x) / 2.0,
             Top =>      (self.area_size.x - self.size.x) / 2.0,
             Center =>   (self.area_size.x - self.size.x) / 2.0,
+            Stretch | StretchHorizontal | StretchVertical => 0.0,
         };
     
         self.pos.x += x_offset;
@@ -294,8 +370,9 @@ pub(crate) struct FontTextureWriter<'a> {
     //input
     tex_width: u32,
     tex_height: u32,
-    font: &'a rusttype::Font<'a>,
+    source: &'a FontSource,
     font_scale: rusttype::Scale,
+    sdf_spread: u32,
     
     //output
     data: Vec<u8>,
@@ -303,7 +380,23 @@ pub(crate) struct FontTextureWriter<'a> {
 }
 
 impl<'a> FontTextureWriter<'a> {
-    pub fn new(font: &'a rusttype::Font<'a>, ranges: &[CharacterRange], size: f32, scale: f32) -> FontTextureWriter<'a> {
+    pub fn new(source: &'a FontSource, ranges: &[CharacterRange], size: f32, scale: f32) -> FontTextureWriter<'a> {
+        FontTextureWriter::create(source, ranges, size, scale, 0)
+    }
+
+    /// Creates a writer which produces a signed distance field for each glyph, rather than its coverage.
+    /// Values of 0.5 lie on the glyph edge, increasing inside the glyph.
+    pub fn new_sdf(source: &'a FontSource, ranges: &[CharacterRange], size: f32, scale: f32) -> FontTextureWriter<'a> {
+        FontTextureWriter::create(source, ranges, size, scale, SDF_SPREAD)
+    }
+
+    fn create(
+        source: &'a FontSource,
+        ranges: &[CharacterRange],
+        size: f32,
+        scale: f32,
+        sdf_spread: u32,
+    ) -> FontTextureWriter<'a> {
         // TODO if the approximation here doesn't work in practice, may need to do 2 passes over the font.
         // first pass would just determine the texture bounds.
 
@@ -311,7 +404,7 @@ impl<'a> FontTextureWriter<'a> {
         let count = ranges.iter().fold(0, |accum, range| accum + (range.upper - range.lower + 1));
         let rows = (count as f32).sqrt().ceil();
         const FUDGE_FACTOR: f32 = 1.2; // factor for characters with tails and wider than usual characters
-        let tex_size = (rows * size * FUDGE_FACTOR * scale).ceil() as u32;
+        let tex_size = (rows * (size * FUDGE_FACTOR * scale + 2.0 * sdf_spread as f32)).ceil() as u32;
         log::info!("Using texture of size {} for {} characters in font of size {}.", tex_size, count, size * scale);
 
         let tex_width = tex_size;
@@ -326,8 +419,9 @@ impl<'a> FontTextureWriter<'a> {
             max_row_height: 0,
             tex_width,
             tex_height,
-            font,
+            source,
             font_scale,
+            sdf_spread,
             data,
             characters: FxHashMap::default(),
         }
@@ -350,7 +444,7 @@ impl<'a> FontTextureWriter<'a> {
             }
         }
 
-        let v_metrics = self.font.v_metrics(self.font_scale);
+        let v_metrics = self.source.font.v_metrics(self.font_scale);
 
         let font_out = Font::new(
             handle,
@@ -371,14 +465,16 @@ impl<'a> FontTextureWriter<'a> {
         &mut self,
         c: char,
     ) -> FontChar {
-        let glyph = self.font.glyph(c)
+        let glyph = self.source.font_for_char(c).glyph(c)
             .scaled(self.font_scale)
             .positioned(rusttype::Point { x: 0.0, y: 0.0 });
 
         // compute the glyph size.  use a minimum size of (1,1) for spaces
-        let y_offset = glyph.pixel_bounding_box().map_or(0.0, |bb| bb.min.y as f32);
-        let bounding_box = glyph.pixel_bounding_box()
+        let spread = self.sdf_spread;
+        let y_offset = glyph.pixel_bounding_box().map_or(0.0, |bb| bb.min.y as f32) - spread as f32;
+        let glyph_size = glyph.pixel_bounding_box()
             .map_or((1, 1), |bb| (bb.width() as u32, bb.height() as u32));
+        let bounding_box = (glyph_size.0 + 2 * spread, glyph_size.1 + 2 * spread);
         
         if self.tex_x + bounding_box.0 >= self.tex_width {
             // move to next row
@@ -392,11 +488,24 @@ impl<'a> FontTextureWriter<'a> {
 
         self.max_row_height = self.max_row_height.max(bounding_box.1);
 
-        glyph.draw(|x, y, val| {
-            let index = (self.tex_x + x) + (self.tex_y + y) * self.tex_width;
-            let value = (val * 255.0).round() as u8;
-            self.data[index as usize] = value;
-        });
+        if spread == 0 {
+            glyph.draw(|x, y, val| {
+                let index = (self.tex_x + x) + (self.tex_y + y) * self.tex_width;
+                let value = (val * 255.0).round() as u8;
+                self.data[index as usize] = value;
+            });
+        } else {
+            let mut coverage = vec![0.0; (glyph_size.0 * glyph_size.1) as usize];
+            glyph.draw(|x, y, val| coverage[(x + y * glyph_size.0) as usize] = val);
+
+            let field = signed_distance_field(&coverage, glyph_size, spread);
+            for y in 0..bounding_box.1 {
+                for x in 0..bounding_box.0 {
+                    let index = (self.tex_x + x) + (self.tex_y + y) * self.tex_width;
+                    self.data[index as usize] = field[(x + y * bounding_box.0) as usize];
+                }
+            }
+        }
 
         let tex_coords = [
             TexCoord::new(
@@ -415,7 +524,50 @@ impl<'a> FontTextureWriter<'a> {
             size: (bounding_box.0 as f32, bounding_box.1 as f32).into(),
             tex_coords,
             x_advance: glyph.unpositioned().h_metrics().advance_width,
+            x_offset: -(spread as f32),
             y_offset,
         }
     }
-}
\ No newline at end of file
+}
+
+/// The distance, in pixels, covered by the signed distance field on either side of a glyph's edge.
+const SDF_SPREAD: u32 = 4;
+
+/// Computes a signed distance field for the glyph `coverage` of the specified `size`, padded by `spread`
+/// pixels on each side.  Each output value maps the distance to the nearest edge, within `spread`,
+/// from 0 (outside) to 255 (inside), with the edge at 128.
+fn signed_distance_field(coverage: &[f32], size: (u32, u32), spread: u32) -> Vec<u8> {
+    let (width, height) = (size.0 as i32, size.1 as i32);
+    let spread = spread as i32;
+    let out_width = width + 2 * spread;
+    let out_height = height + 2 * spread;
+
+    let inside = |x: i32, y: i32| {
+        x >= 0 && y >= 0 && x < width && y < height && coverage[(x + y * width) as usize] >= 0.5
+    };
+
+    let mut output = Vec::with_capacity((out_width * out_height) as usize);
+    for out_y in 0..out_height {
+        for out_x in 0..out_width {
+            let (x, y) = (out_x - spread, out_y - spread);
+            let is_inside = inside(x, y);
+
+            // find the nearest pixel on the other side of the edge
+            let mut min_dist_squared = (spread * spread) as f32;
+            for dy in -spread..=spread {
+                for dx in -spread..=spread {
+                    if inside(x + dx, y + dy) != is_inside {
+                        min_dist_squared = min_dist_squared.min((dx * dx + dy * dy) as f32);
+                    }
+                }
+            }
+
+            let dist = min_dist_squared.sqrt() - 0.5;
+            let signed = if is_inside { dist } else { -dist };
+            let value = 0.5 + signed / (2.0 * spread as f32);
+            output.push((value.clamp(0.0, 1.0) * 255.0).round() as u8);
+        }
+    }
+
+    output
+}
@@ -1,16 +1,20 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::cell::RefCell;
 use std::rc::Rc;
 
 use crate::context::{Context, ContextInternal, InputModifiers};
 use crate::{
-    AnimState, AnimStateKey, Rect, Point, WidgetBuilder, PersistentState, Align,
+    AnimState, AnimStateKey, Rect, Point, WidgetBuilder, PersistentState, Align, Border,
 };
+use crate::font::FontSummary;
 use crate::image::ImageHandle;
 use crate::widget::Widget;
+use crate::recipes::{DRAG_GHOST_ID, SortOrder};
+use crate::winit_io::{CursorIcon, KeyCode};
 
 const MOUSE_NOT_TAKEN: MouseState =
-    MouseState { clicked: false, anim: AnimState::normal(), dragged: Point { x: 0.0, y: 0.0 } };
+    MouseState { clicked: false, right_clicked: false, anim: AnimState::normal(), dragged: Point { x: 0.0, y: 0.0 } };
 
 /// A Frame, holding the widget tree to be drawn on a given frame, and a reference to the
 /// Thyme [`Context`](struct.Context.html)
@@ -34,23 +38,52 @@ pub struct Frame {
 
     parent_index: usize,
     pub(crate) in_modal_tree: bool,
+    pub(crate) in_disabled_tree: bool,
     parent_max_child_bounds: Rect,
     max_child_bounds: Rect,
 
     generated_ids: HashMap<String, u32>,
+    last_widget_bounds: Rect,
+
+    // counters and the prefix stack used by `unique_id` and `scoped_id`.  each scope
+    // stores the parent index it was pushed at
+    unique_ids: HashMap<String, u32>,
+    id_scopes: Vec<(String, usize)>,
 
     mouse_cursor: Option<(ImageHandle, Align)>,
     mouse_anim_state: AnimState,
+    debug_overlay: bool,
+    debug_highlight: Option<String>,
+
+    // whether the widget inspector was shown this frame, in which case the list of
+    // widgets is collected for it to display in the next frame
+    inspector_shown: bool,
+
+    // characters sent to the keyboard focused widget since the previous frame
+    keyboard_input: Option<(String, Vec<char>)>,
+
+    // files dropped onto the window since the previous frame
+    dropped_files: Vec<PathBuf>,
+
+    // the widget that was right clicked in this frame, if any
+    right_clicked_widget: Option<String>,
 }
 
 pub(crate) struct MouseState {
     pub clicked: bool,
+    pub right_clicked: bool,
     pub anim: AnimState,
     pub dragged: Point,
 }
 
 impl Frame {
-    pub(crate) fn new(context: Context, root: Widget, mouse_anim_state: AnimState) -> Frame {
+    pub(crate) fn new(
+        context: Context,
+        root: Widget,
+        mouse_anim_state: AnimState,
+        keyboard_input: Option<(String, Vec<char>)>,
+        dropped_files: Vec<PathBuf>,
+    ) -> Frame {
         let cur_rend_group = RendGroup::default();
         Frame {
             mouse_taken: None,
@@ -64,14 +97,25 @@ impl Frame {
                 start: 0,
                 num: 0,
                 always_top: false,
+                z_order: 0,
             }],
             parent_index: 0,
             in_modal_tree: false,
+            in_disabled_tree: false,
             parent_max_child_bounds: Rect::default(),
             max_child_bounds: Rect::default(),
             generated_ids: HashMap::default(),
+            last_widget_bounds: Rect::default(),
+            unique_ids: HashMap::default(),
+            id_scopes: Vec::new(),
             mouse_cursor: None,
             mouse_anim_state,
+            debug_overlay: false,
+            debug_highlight: None,
+            inspector_shown: false,
+            keyboard_input,
+            dropped_files,
+            right_clicked_widget: None,
         }
     }
 
@@ -79,6 +123,29 @@ impl Frame {
         self.mouse_cursor.map(|(image, align)| (image, align, self.mouse_anim_state))
     }
 
+    pub(crate) fn debug_overlay(&self) -> bool { self.debug_overlay }
+
+    pub(crate) fn right_clicked_widget(&self) -> Option<&str> { self.right_clicked_widget.as_deref() }
+
+    pub(crate) fn set_right_clicked_widget(&mut self, id: String) {
+        self.right_clicked_widget = Some(id);
+    }
+
+    // returns the bounds of the most recently created widget with the specified id in this frame
+    pub(crate) fn widget_bounds(&self, id: &str) -> Option<Rect> {
+        self.widgets.iter().rev().find(|widget| widget.id() == id).map(|widget| Rect::new(widget.pos(), widget.size()))
+    }
+
+    pub(crate) fn debug_highlight(&self) -> Option<&str> { self.debug_highlight.as_deref() }
+
+    pub(crate) fn set_debug_highlight(&mut self, id: Option<String>) {
+        self.debug_highlight = id;
+    }
+
+    pub(crate) fn set_inspector_shown(&mut self) {
+        self.inspector_shown = true;
+    }
+
     pub(crate) fn generate_id(&mut self, id: String) -> String {
         let mut output = id.clone();
         let index = self.generated_ids.entry(id).or_insert(0);
@@ -105,6 +172,10 @@ impl Frame {
 
         let mut context = self.context.internal().borrow_mut();
 
+        if let Some(captured) = context.captured_widget() {
+            return if captured == widget.id() { Some(context.take_mouse_wheel()) } else { None };
+        }
+
         if context.has_modal() && !self.in_modal_tree {
             return None;
         }
@@ -115,8 +186,9 @@ impl Frame {
             }
         }
 
-        let bounds = Rect::new(widget.pos(), widget.size());
-        if !bounds.is_inside(context.mouse_pos()) {
+        // widgets scrolled out of view of a parent do not take the wheel
+        let bounds = widget.hit_bounds();
+        if !bounds.is_inside(context.mouse_pos()) || !widget.clip().is_inside(context.mouse_pos()) {
             return None;
         }
 
@@ -128,6 +200,21 @@ impl Frame {
 
         let mut context = self.context.internal().borrow_mut();
 
+        if let Some(captured) = context.captured_widget() {
+            if captured != widget.id() || self.mouse_taken.is_some() {
+                return MOUSE_NOT_TAKEN;
+            }
+
+            self.mouse_taken = Some((widget.id().to_string(), widget.rend_group()));
+            let anim = if context.mouse_pressed(0) { AnimStateKey::Pressed } else { AnimStateKey::Hover };
+            return MouseState {
+                clicked: context.mouse_clicked(0),
+                right_clicked: context.mouse_clicked(1),
+                anim: AnimState::new(anim),
+                dragged: context.mouse_pos() - context.last_mouse_pos(),
+            };
+        }
+
         if context.has_modal() && !self.in_modal_tree {
             return MOUSE_NOT_TAKEN;
         }
@@ -156,6 +243,7 @@ impl Frame {
                 }
                 return MouseState {
                     clicked: context.mouse_clicked(0),
+                    right_clicked: false,
                     anim: AnimState::new(AnimStateKey::Pressed),
                     dragged
                 };
@@ -164,7 +252,7 @@ impl Frame {
             }
         }
 
-        let bounds = Rect::new(widget.pos(), widget.size());
+        let bounds = widget.hit_bounds();
         if !bounds.is_inside(context.mouse_pos()) {
             return MOUSE_NOT_TAKEN;
         }
@@ -176,6 +264,7 @@ impl Frame {
         self.mouse_taken = Some((widget.id().to_string(), widget.rend_group()));
         MouseState {
             clicked: was_taken_last && context.mouse_clicked(0),
+            right_clicked: was_taken_last && context.mouse_clicked(1),
             anim: AnimState::new(AnimStateKey::Hover),
             dragged: Point::default()
         }
@@ -191,6 +280,12 @@ impl Frame {
         self.parent_max_child_bounds = bounds;
     }
 
+    pub(crate) fn last_widget_bounds(&self) -> Rect { self.last_widget_bounds }
+
+    pub(crate) fn set_last_widget_bounds(&mut self, bounds: Rect) {
+        self.last_widget_bounds = bounds;
+    }
+
     pub(crate) fn parent_index(&self) -> usize { self.parent_index }
 
     pub(crate) fn set_parent_index(&mut self, index: usize) {
@@ -233,14 +328,104 @@ impl Frame {
         WidgetBuilder::new(self, self.parent_index, theme_id, theme)
     }
 
+    /**
+    Returns a new widget ID, formed from `base` and a counter which increments each time this is called
+    with the same `base` in the current frame.  The counters reset each frame, so widgets created in the
+    same order receive the same IDs every frame.  Within a [`scoped_id`](#method.scoped_id), the ID is also
+    prefixed with each of the enclosing scopes.
+
+    # Example
+    ```
+    fn create_ui(ui: &mut Frame, items: &[String]) {
+        for item in items {
+            // IDs "item_0", "item_1", ...
+            let id = ui.unique_id("item");
+            ui.start("button").id(id).text(item).finish();
+        }
+    }
+    ```
+    */
+    pub fn unique_id(&mut self, base: &str) -> String {
+        let base = self.id_scopes.iter()
+            .map(|(scope, _)| scope.as_str())
+            .chain(std::iter::once(base))
+            .collect::<Vec<&str>>()
+            .join("/");
+
+        let index = self.unique_ids.entry(base.to_string()).or_insert(0);
+        let id = format!("{}_{}", base, index);
+        *index += 1;
+        id
+    }
+
+    /**
+    Calls `f`, with `prefix` pushed onto the stack of ID scopes.  Widgets created inside `f` have `prefix/`
+    inserted into their generated IDs, so `ui.start("button")` in the scope is given the ID `prefix/button`,
+    and its children are given IDs such as `prefix/button/label`.  IDs created with
+    [`unique_id`](#method.unique_id) inside `f` are also prefixed.  Scopes may be nested.  This allows a
+    reusable function to create the same widgets several times without their
+    [`PersistentState`](struct.PersistentState.html) colliding.  IDs set explicitly with
+    [`WidgetBuilder::id`](struct.WidgetBuilder.html#method.id) are used as is.
+
+    # Example
+    ```
+    fn create_item(ui: &mut Frame, name: &str) {
+        // "left/name" and "right/name"
+        ui.start("name").text(name).finish();
+    }
+
+    fn create_ui(ui: &mut Frame) {
+        for player in &["left", "right"] {
+            ui.scoped_id(player, |ui| create_item(ui, player));
+        }
+    }
+    ```
+    */
+    pub fn scoped_id<F: FnOnce(&mut Frame)>(&mut self, prefix: &str, f: F) {
+        self.id_scopes.push((prefix.to_string(), self.parent_index));
+        (f)(self);
+        self.id_scopes.pop();
+    }
+
+    // returns the scope prefixes to insert into the IDs of widgets created under the parent at `parent_index`.
+    // only scopes pushed directly under that parent apply, as deeper widgets inherit the prefix from their parent
+    pub(crate) fn id_scope_prefix(&self, parent_index: usize) -> Option<String> {
+        let scopes: Vec<&str> = self.id_scopes.iter()
+            .filter(|(_, index)| *index == parent_index)
+            .map(|(scope, _)| scope.as_str())
+            .collect();
+
+        if scopes.is_empty() {
+            None
+        } else {
+            Some(scopes.join("/"))
+        }
+    }
+
     // ui builder methods
 
+    /// Returns the font used by widgets whose theme does not specify a font, as set with
+    /// [`ContextBuilder::set_default_font`](struct.ContextBuilder.html#method.set_default_font).
+    pub fn default_font(&self) -> Option<FontSummary> {
+        self.context_internal().borrow().themes().default_font()
+    }
+
     /// Returns the current window display size, in logical pixels.
     pub fn display_size(&self) -> Point {
         let context = self.context_internal().borrow();
         context.display_size() / context.scale_factor()
     }
 
+    /// Returns the insets, in logical pixels, from each edge of the display which are obscured by
+    /// hardware.  These are zero unless set with
+    /// [`Context::set_safe_area_insets`](struct.Context.html#method.set_safe_area_insets).
+    /// For example, `ui.start("root").border(ui.safe_area_insets())` keeps the content of a
+    /// full screen widget clear of any obstructions.
+    pub fn safe_area_insets(&self) -> Border {
+        let context = self.context_internal().borrow();
+        context.safe_area_insets()
+    }
+
     /// Returns the current state of the keyboard modifier keys
     pub fn input_modifiers(&self) -> InputModifiers {
         let context = self.context_internal().borrow();
@@ -284,6 +469,22 @@ impl Frame {
         self.mouse_cursor = image.map(|image| (image, align));
     }
 
+    /// Sets the shape of the operating system mouse cursor for this frame.  This overrides any cursor
+    /// set by widgets created earlier in the frame.  See [`Context.set_os_cursor`](struct.Context.html#method.set_os_cursor).
+    pub fn set_os_cursor(&mut self, icon: CursorIcon) {
+        self.context.internal().borrow_mut().set_os_cursor(icon);
+    }
+
+    /// Enables or disables the layout debug overlay for this frame.  When enabled, an outline is drawn
+    /// around the outer and inner rect of every visible widget, colored by the widget's depth in the tree,
+    /// along with the widget's full ID, in the smallest font in the theme.  The overlay is drawn after
+    /// the rest of the frame.  The overlay is drawn by the GL and Glium renderers, which implement
+    /// [`Renderer::draw_rect_outline`](trait.Renderer.html#method.draw_rect_outline).  The wgpu renderer
+    /// does not draw the overlay.
+    pub fn enable_debug_overlay(&mut self, enabled: bool) {
+        self.debug_overlay = enabled;
+    }
+
     /// Manually set the Mouse cursor to the specified `state`.  This is used when
     /// drawing the specified mouse cursor image.  The mouse will automatically inherit
     /// `Normal` and `Pressed` states by default.  This overrides that behavior.
@@ -297,6 +498,71 @@ impl Frame {
         self.widgets[self.parent_index].gap(gap);
     }
 
+    /// Adds a horizontal gap before the next widget to be specified, regardless of the current
+    /// parent's layout direction.  In a `Vertical` layout, this offsets all subsequent widgets.
+    /// Has no effect in `Grid` and `Free` layouts.
+    pub fn hgap(&mut self, gap: f32) {
+        self.widgets[self.parent_index].hgap(gap);
+    }
+
+    /// Adds a vertical gap before the next widget to be specified, regardless of the current
+    /// parent's layout direction.  In a `Horizontal` layout, this offsets all subsequent widgets.
+    /// Has no effect in `Grid` and `Free` layouts.
+    pub fn vgap(&mut self, gap: f32) {
+        self.widgets[self.parent_index].vgap(gap);
+    }
+
+    /**
+    Adds a spring to the current parent's layout, which expands to fill the remaining space in the
+    layout direction.  Widgets specified after the spring are pushed to the far end of the parent.
+    This only has an effect in `Horizontal` and `Vertical` layouts with children aligned to the top
+    left, and only one spring per parent is supported.
+
+    Since widgets are laid out as they are specified, the size of the widgets after the spring is
+    measured and used on the following frame.  This means the layout may be off for a single
+    frame when those widgets first appear or change size.
+
+    # Example
+    ```
+    fn toolbar(ui: &mut Frame) {
+        ui.start("toolbar").layout(Layout::Horizontal).children(|ui| {
+            ui.button("button", "Open");
+            ui.button("button", "Save");
+            ui.spring();
+            ui.button("button", "Quit");
+        });
+    }
+    ```
+    **/
+    pub fn spring(&mut self) {
+        self.widgets[self.parent_index].spring();
+    }
+
+    /// Returns whether the keyboard shortcut with the specified `id` was triggered since the
+    /// previous frame.  Shortcuts are registered with
+    /// [`Context.register_shortcut`](struct.Context.html#method.register_shortcut).
+    pub fn shortcut_triggered(&self, id: &str) -> bool {
+        self.context.internal().borrow().shortcut_triggered(id)
+    }
+
+    /// Returns whether the specified `key` was pressed since the previous frame.  This consumes the
+    /// key press, so subsequent calls for the same `key` in this frame will return false.  Use
+    /// [`key_peek`](#method.key_peek) to check without consuming the press.
+    pub fn key_pressed(&self, key: KeyCode) -> bool {
+        self.context.internal().borrow_mut().take_key_pressed(key)
+    }
+
+    /// Returns whether the specified `key` was pressed since the previous frame, without consuming
+    /// the key press.  See [`key_pressed`](#method.key_pressed).
+    pub fn key_peek(&self, key: KeyCode) -> bool {
+        self.context.internal().borrow().key_pressed(key)
+    }
+
+    /// Returns whether the specified `key` is currently held down.
+    pub fn key_held(&self, key: KeyCode) -> bool {
+        self.context.internal().borrow().key_held(key)
+    }
+
     /// Sets the current cursor position of the current parent widget to the specified value.
     /// Normally, the cursor widget moves after each widget is placed based on the parent's
     /// [`layout`](struct.WidgetBuilder.html#method.layout).
@@ -310,6 +576,21 @@ impl Frame {
     /// This has nothing to do with the mouse cursor.
     pub fn cursor(&self) -> Point { self.widgets[self.parent_index].cursor() }
 
+    /// Returns the number of direct children added to the widget with the specified `id` in the
+    /// previous frame.  Children that were not visible are not counted.  Returns zero if the widget
+    /// was not drawn in the previous frame.  This is useful for deciding what to show, such as a
+    /// "no items" label, based on the content of a widget.
+    pub fn child_count(&self, id: &str) -> usize {
+        self.context.internal().borrow().child_count(id)
+    }
+
+    /// Returns whether the children of the widget with the specified `id` extended outside of the
+    /// widget's bounds in the previous frame.  Unparented children, such as popups, are not considered.
+    /// Returns false if the widget was not drawn in the previous frame.
+    pub fn had_overflow(&self, id: &str) -> bool {
+        self.context.internal().borrow().had_overflow(id)
+    }
+
     /// Causes Thyme to focus the keyboard on the widget with the specified `id`.  Keyboard
     /// events will subsequently be sent to this widget, if it exists.  Only
     /// one widget may have keyboard focus at a time.
@@ -325,6 +606,57 @@ impl Frame {
         context.set_focus_keyboard(id.into());
     }
 
+    /// Directs all mouse input to the widget with the specified `id`, regardless of the mouse position,
+    /// until [`release_capture`](#method.release_capture) is called or the primary mouse button is released.
+    /// This is useful for drag gestures where the mouse may leave the widget.  While captured, no other
+    /// widget will be hovered, pressed, or receive mouse wheel input.  The captured widget must
+    /// [`want the mouse`](struct.WidgetBuilder.html#method.wants_mouse).
+    pub fn capture_mouse<T: Into<String>>(&mut self, id: T) {
+        let mut context = self.context.internal().borrow_mut();
+        context.set_captured_widget(Some(id.into()));
+    }
+
+    /// Releases any mouse capture set with [`capture_mouse`](#method.capture_mouse).
+    pub fn release_capture(&mut self) {
+        let mut context = self.context.internal().borrow_mut();
+        context.set_captured_widget(None);
+    }
+
+    /// Returns the paths of any files dropped onto the window since the previous frame.  See
+    /// [`Context::push_dropped_file`](struct.Context.html#method.push_dropped_file).
+    pub fn dropped_files(&self) -> &[PathBuf] {
+        &self.dropped_files
+    }
+
+    /// Returns the characters sent to the widget with the specified `id` since the previous frame.
+    /// This will be empty unless the widget had keyboard focus at the start of this frame.  Widgets
+    /// obtain keyboard focus when clicked if they [`want the keyboard`](struct.WidgetBuilder.html#method.wants_keyboard),
+    /// or via [`focus_keyboard`](#method.focus_keyboard).
+    pub fn keyboard_input(&self, id: &str) -> &[char] {
+        match &self.keyboard_input {
+            Some((focus_id, input)) if focus_id == id => input,
+            _ => &[],
+        }
+    }
+
+    /// Returns the text currently being composed with an input method editor, and the byte range of
+    /// the cursor within that text, if any.  See [`Context::set_ime_preedit`](struct.Context.html#method.set_ime_preedit).
+    pub fn ime_preedit(&self) -> Option<(String, Option<(usize, usize)>)> {
+        let context = self.context.internal().borrow();
+        context.ime_preedit().cloned()
+    }
+
+    // returns the width of `text` drawn with the font of the theme with the specified `theme_id`,
+    // falling back to the default font in the same way as widget creation
+    pub(crate) fn text_width(&self, theme_id: &str, text: &str) -> f32 {
+        let context = self.context.internal().borrow();
+        let themes = context.themes();
+        match themes.theme(theme_id).and_then(|theme| theme.font).or_else(|| themes.default_font()) {
+            None => 0.0,
+            Some(font) => themes.font(font.handle).measure(text, None).x / context.scale_factor(),
+        }
+    }
+
     /// Returns whether or not the widget with the specified `id` currently has keyboard focus.
     /// See [`focus_keyboard`](#method.focus_keyboard).
     pub fn is_focus_keyboard(&self, id: &str) -> bool {
@@ -364,6 +696,12 @@ impl Frame {
         context.time_millis()
     }
 
+    /// Sets the rate at which the UI time advances relative to real time, starting with the next frame.
+    /// See [`Context::set_time_scale`](struct.Context.html#method.set_time_scale).
+    pub fn set_time_scale(&mut self, scale: f32) {
+        self.context.set_time_scale(scale);
+    }
+
     /// Sets the base time of the [`PersistentState`](struct.PersistentState.html) for the widget with the
     /// specified `id` to the specified `time`.
     /// This time should probably be based on something obtained from [`cur_time_millis`](#method.cur_time_millis)
@@ -392,15 +730,30 @@ impl Frame {
         context.state(id).base_time_millis
     }
     
-    /// Sets the internal `scroll` of the [`PersistentState`](struct.PersistentState.html) for
+    /// Returns the internal `scroll` of the [`PersistentState`](struct.PersistentState.html) for
     /// the widget with the specified `id`.  Useful for [`Scrollpanes`](struct.WidgetBuilder.html#method.scrollpane).
+    /// This is the raw stored offset, which becomes more negative as the content is scrolled towards the bottom
+    /// and right.  It is the negation of the position used by [`set_scroll_pos`](#method.set_scroll_pos) and
+    /// [`ScrollpaneBuilder::get_scroll_pos`](struct.ScrollpaneBuilder.html#method.get_scroll_pos).
     pub fn scroll(&self, id: &str) -> Point {
         let context = self.context.internal().borrow();
         context.state(id).scroll
     }
 
+    /// Sets the scroll position of the scrollpane with the specified content `id` to `pos`, using the same
+    /// convention as [`ScrollpaneBuilder::get_scroll_pos`](struct.ScrollpaneBuilder.html#method.get_scroll_pos).
+    /// This should be called before the scrollpane is built in the current frame.  The position is limited to
+    /// the scrollable area when the scrollpane is built.  Note that `pos` is positive towards the bottom and right,
+    /// the opposite sign of the raw value used by [`scroll`](#method.scroll) and [`change_scroll`](#method.change_scroll).
+    pub fn set_scroll_pos<T: Into<String>>(&mut self, id: T, pos: Point) {
+        let mut context = self.context.internal().borrow_mut();
+        context.state_mut(id).scroll = Point::default() - pos;
+    }
+
     /// Modifies the internal `scroll` of the widget with the specified `id` by the specified `x` and `y` amounts.
-    /// See [`scroll`](#method.scroll)
+    /// The amounts use the same sign as the raw value returned by [`scroll`](#method.scroll), so negative
+    /// values scroll the content towards the bottom and right.  This is the opposite of
+    /// [`set_scroll_pos`](#method.set_scroll_pos).
     pub fn change_scroll<T: Into<String>>(&mut self, id: T, x: f32, y: f32) {
         let mut context = self.context.internal().borrow_mut();
         let state = context.state_mut(id);
@@ -414,6 +767,14 @@ impl Frame {
         context.state(id).text.clone()
     }
 
+    /// Returns the current text of the [`input field`](#method.input_field) or
+    /// [`autocomplete`](#method.autocomplete) widget with the specified `id`, or an empty
+    /// string if it has no text.  This is useful for updating autocomplete suggestions based on
+    /// what the user has typed so far.
+    pub fn current_input(&self, id: &str) -> String {
+        self.text_for(id).unwrap_or_default()
+    }
+
     /// Returns whether the widget with the specified `id` is expanded in its [`PersistentState`](struct.PersistentState.html).
     /// Trees and similar widgets will not show their entire content if not expanded
     pub fn is_expanded(&self, id: &str) -> bool {
@@ -427,6 +788,36 @@ impl Frame {
         context.state_mut(id).expanded = expanded;
     }
 
+    /// Returns the selected index of the widget with the specified `id` in its
+    /// [`PersistentState`](struct.PersistentState.html).  Tab panels and similar widgets use this to
+    /// determine which of their items is active.
+    pub fn selected(&self, id: &str) -> usize {
+        let context = self.context.internal().borrow();
+        context.state(id).selected
+    }
+
+    /// Returns the sort order of the widget with the specified `id` in its
+    /// [`PersistentState`](struct.PersistentState.html).  See [`table`](#method.table).
+    pub fn sort_order(&self, id: &str) -> SortOrder {
+        let context = self.context.internal().borrow();
+        context.state(id).sort_order
+    }
+
+    /// Sets the selected index for the given widget to `selected`.  See [`selected`](#method.selected)
+    pub fn set_selected<T: Into<String>>(&mut self, id: T, selected: usize) {
+        let mut context = self.context.internal().borrow_mut();
+        context.state_mut(id).selected = selected;
+    }
+
+    /// Returns `true` if the [`PersistentState`](struct.PersistentState.html) for the widget with the
+    /// specified `id` changed during the previous frame, and `false` otherwise.  This will return `true`
+    /// for exactly one frame after each change, allowing code to react to state changes without polling
+    /// and comparing the state itself.  Changes to `characters` are not tracked.
+    pub fn state_changed(&self, id: &str) -> bool {
+        let context = self.context.internal().borrow();
+        context.state_changed(id)
+    }
+
     /// Returns whether the widget with the specified `id` is open in its [`PersistentState`](struct.PersistentState.html).
     /// If not open, widgets are not visible.
     pub fn is_open(&self, id: &str) -> bool {
@@ -499,6 +890,22 @@ impl Frame {
         context.clear_state(id);
     }
 
+    /// Completely clears all [`PersistentState`](struct.PersistentState.html) associated with the
+    /// specified `id` and all of its children, resetting them to their default state.  Children
+    /// are all widgets with an ID beginning with `{id}/`.  This includes clearing the modal state
+    /// if the modal is one of the cleared widgets.
+    pub fn reset_widget_state(&mut self, id: &str) {
+        let mut context = self.context.internal().borrow_mut();
+        context.clear_state_recursive(id);
+    }
+
+    /// Completely clears all [`PersistentState`](struct.PersistentState.html) for every widget,
+    /// along with the current modal, if any.
+    pub fn reset_all_state(&mut self) {
+        let mut context = self.context.internal().borrow_mut();
+        context.clear_all_state();
+    }
+
     /// Gets a mutable reference to the [`PersistentState`](struct.PersistentState.html) associated with
     /// the `id`, and calls the passed in closure, `f`, allowing you to modify it in arbitrary ways.  This
     /// is more efficient than calling several individual methods in a row, such as [`open`](#method.open),
@@ -509,6 +916,17 @@ impl Frame {
         (f)(context.state_mut(id))
     }
 
+    /// Calls the passed in closure, `f`, with each of the specified `ids` and a mutable reference to the
+    /// associated [`PersistentState`](struct.PersistentState.html).  This is equivalent to calling
+    /// [`modify`](#method.modify) for each `id`, but only accesses the internal context once.  This is
+    /// useful for actions such as resetting or deselecting a group of widgets.
+    pub fn modify_multiple<F: FnMut(&str, &mut PersistentState)>(&mut self, ids: &[&str], mut f: F) {
+        let mut context = self.context.internal().borrow_mut();
+        for id in ids {
+            (f)(id, context.state_mut(*id));
+        }
+    }
+
     /// Queries the theme for the specified custom float, in the `custom_floats` field for the
     /// theme with the specified `key`.  Returns the `default_value` if the theme or key cannot
     /// be found.
@@ -521,6 +939,26 @@ impl Frame {
         }
     }
 
+    // records changes in anim state for widgets with images that blend between states
+    pub(crate) fn track_anim_transition(&self, widget_index: usize, anim_state: AnimState) {
+        let widget = &self.widgets[widget_index];
+        let mut internal = self.context.internal().borrow_mut();
+
+        let blends = [widget.background(), widget.foreground()].iter().flatten().any(|handle| {
+            internal.themes().image(*handle).transition_ms() > 0
+        });
+        if !blends { return; }
+
+        let now = internal.time_millis();
+        let state = internal.state_mut(widget.id());
+        if let Some(prev) = state.anim_state {
+            if prev != anim_state {
+                state.anim_transition = Some((prev, now));
+            }
+        }
+        state.anim_state = Some(anim_state);
+    }
+
     pub(crate) fn push_widget(&mut self, mut widget: Widget) {
         widget.set_rend_group(self.cur_rend_group);
         self.render_groups[self.cur_rend_group.index as usize].num += 1;
@@ -533,7 +971,7 @@ impl Frame {
         self.cur_rend_group = group;
     }
 
-    pub(crate) fn next_render_group(&mut self, rect: Rect, id: String, always_top: bool) {
+    pub(crate) fn next_render_group(&mut self, rect: Rect, id: String, always_top: bool, z_order: i32) {
         let widgets_len = self.widgets.len();
         let index = self.render_groups.len() as u16;
         let cur_rend_group = RendGroup { index };
@@ -545,6 +983,7 @@ impl Frame {
             start: widgets_len,
             num: 0,
             always_top,
+            z_order,
         });
         self.cur_rend_group = cur_rend_group;
     }
@@ -553,28 +992,58 @@ impl Frame {
         self.render_groups[self.cur_rend_group.index as usize].rect = bounds;
     }
 
-    pub(crate) fn finish_frame(self) -> (Context, Vec<Widget>, Vec<RendGroupDef>) {
+    pub(crate) fn finish_frame(mut self) -> (Context, Vec<Widget>, Vec<RendGroupDef>) {
+        self.parent_index = 0;
+        self.build_toasts();
+        self.build_drag_ghost();
+
+        let widgets = draw_ordered(self.widgets, &mut self.render_groups);
+
+        let last_frame_widgets = if self.inspector_shown {
+            widgets.iter()
+                .filter(|widget| widget.visible())
+                .map(|widget| (widget.id().to_string(), widget.theme_id().to_string()))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let child_counts = widgets.iter()
+            .filter(|widget| widget.visible() && widget.child_count() > 0)
+            .map(|widget| (widget.id().to_string(), widget.child_count()))
+            .collect();
+
+        let overflowed = widgets.iter()
+            .filter(|widget| widget.visible() && widget.overflowed())
+            .map(|widget| widget.id().to_string())
+            .collect();
+
         let (top_rend_group, mouse_pos) = {
             let mut context = self.context.internal().borrow_mut();
 
             context.check_set_rend_group_top(&self.render_groups);
+            context.update_widget_rects(&widgets);
+            context.set_last_frame_widgets(last_frame_widgets);
+            context.set_child_counts(child_counts, overflowed);
+            context.apply_restored_state(widgets.iter().map(|widget| widget.id()));
 
             (context.top_rend_group(), context.mouse_pos())
         };
 
+        // groups are sorted front to back.  The sort is stable, so groups with
+        // equal keys keep their declaration order
         let mut render_groups = self.render_groups;
         render_groups.sort_by_key(|group| {
-            if group.always_top {
-                -1
-            } else if group.group == top_rend_group {
-                0
-            } else {
-                1
-            }
+            let top = if group.group == top_rend_group { 0 } else { 1 };
+
+            (!group.always_top, std::cmp::Reverse(group.z_order), top)
         });
 
         let mut mouse_in_rend_group = None;
         for rend_group in render_groups.iter() {
+            // the drag ghost follows the mouse, and should not block widgets below it
+            if rend_group.id == DRAG_GHOST_ID { continue; }
+
             if rend_group.rect.is_inside(mouse_pos) {
                 mouse_in_rend_group = Some(rend_group.group);
                 break;
@@ -583,7 +1052,58 @@ impl Frame {
 
         self.context.internal().borrow_mut().next_frame(self.mouse_taken, mouse_in_rend_group);
 
-        (self.context, self.widgets, render_groups)
+        (self.context, widgets, render_groups)
+    }
+}
+
+/// Reorders `widgets` for drawing, based on each widget's `z_index` relative to its siblings, and
+/// updates the render `groups` to match.
+fn draw_ordered(mut widgets: Vec<Widget>, groups: &mut [RendGroupDef]) -> Vec<Widget> {
+    if widgets.iter().all(|widget| widget.z_index() == 0) { return widgets; }
+
+    let len = widgets.len();
+    widgets[0].set_subtree_end(len);
+
+    let mut order = Vec::with_capacity(len);
+    push_draw_order(&widgets, 0, &mut order);
+
+    let mut widgets: Vec<Option<Widget>> = widgets.into_iter().map(Some).collect();
+    let widgets: Vec<Widget> = order.into_iter().map(|index| widgets[index].take().unwrap()).collect();
+
+    for group in groups.iter_mut() {
+        group.start = len;
+    }
+
+    for (index, widget) in widgets.iter().enumerate() {
+        let group = &mut groups[widget.rend_group().index as usize];
+        group.start = group.start.min(index);
+    }
+
+    widgets
+}
+
+fn push_draw_order(widgets: &[Widget], index: usize, order: &mut Vec<usize>) {
+    let end = widgets[index].subtree_end().max(index + 1);
+
+    let mut children = Vec::new();
+    let mut child = index + 1;
+    while child < end {
+        children.push(child);
+        child = widgets[child].subtree_end().max(child + 1);
+    }
+
+    // the sort is stable, so siblings with equal z_index keep their declaration order
+    children.sort_by_key(|child| widgets[*child].z_index());
+    let split = children.iter().position(|child| widgets[*child].z_index() >= 0).unwrap_or(children.len());
+
+    for child in &children[..split] {
+        push_draw_order(widgets, *child, order);
+    }
+
+    order.push(index);
+
+    for child in &children[split..] {
+        push_draw_order(widgets, *child, order);
     }
 }
 
@@ -600,6 +1120,7 @@ pub(crate) struct RendGroupDef {
     start: usize,
     num: usize,
     always_top: bool,
+    z_order: i32,
 }
 
 impl RendGroupDef {
@@ -1,10 +1,13 @@
+use std::borrow::Cow;
+
 use crate::font::{Font, FontSource, FontTextureWriter};
 use crate::image::ImageDrawParams;
 use crate::render::{
-    view_matrix, DrawList, DrawMode, FontHandle, Renderer, TexCoord, TextureData, TextureHandle,
+    debug_overlay_color, outline_edges, DEBUG_HIGHLIGHT_COLOR, view_matrix, DrawList, DrawMode, FontHandle, Renderer, TexCoord,
+    TextureData, TextureHandle,
 };
-use crate::theme_definition::CharacterRange;
-use crate::{Color, Frame, Point, Rect};
+use crate::theme_definition::{CharacterRange, GradientDirection};
+use crate::{Align, Color, Frame, Point, Rect};
 
 use gl;
 
@@ -41,7 +44,11 @@ pub struct GLRenderer {
 
     // assets loaded from the context
     textures: Vec<GLTexture>,
-    fonts: Vec<GLTexture>,
+    fonts: Vec<GLFont>,
+    texture_mipmaps: bool,
+
+    // plain white texture used for solid color drawing
+    solid_texture: GLTexture,
 
     // per frame data
     draw_list: GLDrawList,
@@ -61,16 +68,57 @@ impl GLRenderer {
             font_program,
             fonts: Vec::new(),
             textures: Vec::new(),
+            texture_mipmaps: false,
+            solid_texture: GLTexture::new(
+                &[255, 255, 255, 255],
+                (1, 1),
+                gl::NEAREST,
+                gl::CLAMP_TO_EDGE,
+                gl::RGBA,
+                gl::RGBA8,
+                false,
+            ),
             draw_list: GLDrawList::new(),
             groups: Vec::new(),
             matrix: view_matrix(Point::default(), Point { x: 100.0, y: 100.0 }),
         }
     }
 
-    fn font(&self, font: FontHandle) -> &GLTexture {
+    fn font(&self, font: FontHandle) -> &GLFont {
         &self.fonts[font.id()]
     }
 
+    // creates the font texture, with linear filtering for distance field fonts
+    fn add_font(
+        &mut self,
+        handle: FontHandle,
+        writer: FontTextureWriter,
+        ranges: &[CharacterRange],
+        smoothness: f32,
+    ) -> Result<Font, crate::Error> {
+        let writer_out = writer.write(handle, ranges)?;
+
+        let font_texture = GLTexture::new(
+            &writer_out.data,
+            (writer_out.tex_width, writer_out.tex_height),
+            if smoothness > 0.0 { gl::LINEAR } else { gl::NEAREST },
+            gl::CLAMP_TO_BORDER,
+            gl::RED,
+            gl::R8,
+            false,
+        );
+        let font = GLFont { texture: font_texture, smoothness };
+
+        assert!(handle.id() <= self.fonts.len());
+        if handle.id() == self.fonts.len() {
+            self.fonts.push(font);
+        } else {
+            self.fonts[handle.id()] = font;
+        }
+
+        Ok(writer_out.font)
+    }
+
     fn texture(&self, texture: TextureHandle) -> &GLTexture {
         &self.textures[texture.id()]
     }
@@ -86,6 +134,8 @@ impl GLRenderer {
     /// Draws the specified [`Frame`](struct.Frame.html) to the Glium surface, usually the Glium Frame.
     pub fn draw_frame(&mut self, frame: Frame) {
         let mouse_cursor = frame.mouse_cursor();
+        let debug_overlay = frame.debug_overlay();
+        let debug_highlight = frame.debug_highlight().map(|id| id.to_string());
         let (context, widgets, render_groups) = frame.finish_frame();
         let context = context.internal().borrow();
 
@@ -133,6 +183,8 @@ impl GLRenderer {
                         clip: widget.clip(),
                         time_millis,
                         scale,
+                        anim_transition: context.anim_transition_for(widget.id()),
+                        tint: widget.background_tint(),
                     },
                 );
             }
@@ -152,17 +204,41 @@ impl GLRenderer {
                     let image = context.themes().image(image_handle);
                     self.write_group_if_changed(&mut draw_mode, DrawMode::Image(image.texture()));
 
-                    image.draw(
-                        &mut self.draw_list,
-                        ImageDrawParams {
-                            pos: fg_pos.into(),
-                            size: fg_size.into(),
-                            anim_state: widget.anim_state(),
-                            clip: widget.clip(),
-                            time_millis,
-                            scale,
-                        },
-                    );
+                    // widgets with lines only draw the foreground image along those lines
+                    if widget.lines().is_empty() {
+                        image.draw(
+                            &mut self.draw_list,
+                            ImageDrawParams {
+                                pos: fg_pos.into(),
+                                size: fg_size.into(),
+                                anim_state: widget.anim_state(),
+                                clip: widget.clip(),
+                                time_millis,
+                                scale,
+                                anim_transition: context.anim_transition_for(widget.id()),
+                                tint: widget.foreground_tint(),
+                            },
+                        );
+                    }
+
+                    for &(from, to, thickness) in widget.lines() {
+                        image.draw_line(
+                            &mut self.draw_list,
+                            fg_pos + from,
+                            fg_pos + to,
+                            thickness,
+                            ImageDrawParams {
+                                pos: fg_pos.into(),
+                                size: fg_size.into(),
+                                anim_state: widget.anim_state(),
+                                clip: widget.clip(),
+                                time_millis,
+                                scale,
+                                anim_transition: context.anim_transition_for(widget.id()),
+                                tint: widget.foreground_tint(),
+                            },
+                        );
+                    }
                 }
 
                 if let Some(text) = widget.text() {
@@ -172,12 +248,29 @@ impl GLRenderer {
                             DrawMode::Font(font_sum.handle),
                         );
                         let font = context.themes().font(font_sum.handle);
+                        let text = if widget.truncate() {
+                            font.truncate(text, fg_size.x * scale, widget.ellipsis())
+                        } else {
+                            Cow::Borrowed(text)
+                        };
+
+                        if let Some((offset, color)) = widget.text_shadow() {
+                            font.draw(
+                                &mut self.draw_list,
+                                fg_size * scale,
+                                ((fg_pos + offset) * scale).into(),
+                                &text,
+                                widget.text_align(),
+                                color,
+                                widget.clip() * scale,
+                            );
+                        }
 
                         font.draw(
                             &mut self.draw_list,
                             fg_size * scale,
                             (fg_pos * scale).into(),
-                            text,
+                            &text,
                             widget.text_align(),
                             widget.text_color(),
                             widget.clip() * scale,
@@ -206,12 +299,46 @@ impl GLRenderer {
                 clip,
                 time_millis,
                 scale,
+                anim_transition: None,
+                tint: None,
             };
 
             image.draw(&mut self.draw_list, params);
             self.write_group(DrawMode::Image(image.texture()));
         }
 
+        if debug_overlay {
+            for widget in widgets.iter().filter(|widget| widget.visible()) {
+                let color = debug_overlay_color(widget.id());
+                let inner_pos = widget.pos() + widget.border().tl();
+                self.draw_rect_outline(Rect::new(widget.pos(), widget.size()) * scale, scale, color);
+                self.draw_rect_outline(Rect::new(inner_pos, widget.inner_size()) * scale, scale, color);
+            }
+            self.write_group(DrawMode::Solid);
+
+            if let Some(font) = context.themes().debug_font() {
+                let display_clip = Rect::new(Point::default(), context.display_size()) * scale;
+                for widget in widgets.iter().filter(|widget| widget.visible()) {
+                    font.draw(
+                        &mut self.draw_list,
+                        (context.display_size() - widget.pos()) * scale,
+                        (widget.pos() * scale).into(),
+                        widget.id(),
+                        Align::TopLeft,
+                        debug_overlay_color(widget.id()),
+                        display_clip,
+                    );
+                }
+                self.write_group(DrawMode::Font(font.handle()));
+            }
+        }
+
+        if let Some(widget) = debug_highlight.and_then(|id| widgets.iter().find(|widget| widget.id() == id)) {
+            let rect = Rect::new(widget.pos(), widget.size()) * scale;
+            self.draw_rect_outline(rect, 2.0 * scale, DEBUG_HIGHLIGHT_COLOR);
+            self.write_group(DrawMode::Solid);
+        }
+
         unsafe {
             gl::Enable(gl::FRAMEBUFFER_SRGB);
         }
@@ -221,6 +348,7 @@ impl GLRenderer {
 
         let font_uniform_tex = self.font_program.get_uniform_location("tex");
         let font_uniform_matrix = self.font_program.get_uniform_location("matrix");
+        let font_uniform_smoothness = self.font_program.get_uniform_location("smoothness");
 
         let base_uniform_tex = self.base_program.get_uniform_location("tex");
         let base_uniform_matrix = self.base_program.get_uniform_location("matrix");
@@ -230,19 +358,23 @@ impl GLRenderer {
                 DrawMode::Font(font_handle) => {
                     let font = self.font(font_handle);
 
-                    font.bind(0);
+                    font.texture.bind(0);
                     self.font_program.use_program();
 
                     self.font_program
                         .uniform_matrix4fv(font_uniform_matrix, false, &self.matrix);
                     self.font_program.uniform1i(font_uniform_tex, 0);
+                    self.font_program.uniform1f(font_uniform_smoothness, font.smoothness);
 
                     unsafe {
                         gl::DrawArrays(gl::POINTS, group.start as _, (group.end - group.start) as _)
                     };
                 }
-                DrawMode::Image(tex_handle) => {
-                    let texture = self.texture(tex_handle);
+                DrawMode::Image(_) | DrawMode::Solid => {
+                    let texture = match group.mode {
+                        DrawMode::Image(tex_handle) => self.texture(tex_handle),
+                        _ => &self.solid_texture,
+                    };
 
                     texture.bind(0);
                     self.base_program.use_program();
@@ -286,6 +418,33 @@ impl GLRenderer {
 }
 
 impl Renderer for GLRenderer {
+    fn set_texture_mipmaps(&mut self, enabled: bool) {
+        self.texture_mipmaps = enabled;
+    }
+
+    fn update_texture_region(
+        &mut self,
+        handle: TextureHandle,
+        data: &[u8],
+        pos: (u32, u32),
+        size: (u32, u32),
+    ) -> Result<(), crate::Error> {
+        self.texture(handle).update_region(pos, size, data);
+        Ok(())
+    }
+
+    fn draw_rect_outline(&mut self, rect: Rect, thickness: f32, color: Color) {
+        for (pos, size) in outline_edges(rect, thickness).iter() {
+            self.draw_list.push_rect(
+                *pos,
+                *size,
+                [TexCoord::new(0.0, 0.0), TexCoord::new(1.0, 1.0)],
+                color,
+                rect,
+            );
+        }
+    }
+
     fn register_texture(
         &mut self,
         handle: TextureHandle,
@@ -299,6 +458,7 @@ impl Renderer for GLRenderer {
             gl::CLAMP_TO_EDGE,
             gl::RGBA,
             gl::RGBA8,
+            self.texture_mipmaps,
         );
 
         assert!(handle.id() <= self.textures.len());
@@ -319,32 +479,30 @@ impl Renderer for GLRenderer {
         size: f32,
         scale: f32,
     ) -> Result<Font, crate::Error> {
-        let font = &source.font;
-
-        let writer = FontTextureWriter::new(font, ranges, size, scale);
-
-        let writer_out = writer.write(handle, ranges)?;
-
-        let font_texture = GLTexture::new(
-            &writer_out.data,
-            (writer_out.tex_width, writer_out.tex_height),
-            gl::NEAREST,
-            gl::CLAMP_TO_BORDER,
-            gl::RED,
-            gl::R8,
-        );
-
-        assert!(handle.id() <= self.fonts.len());
-        if handle.id() == self.fonts.len() {
-            self.fonts.push(font_texture);
-        } else {
-            self.fonts[handle.id()] = font_texture;
-        }
+        let writer = FontTextureWriter::new(source, ranges, size, scale);
+        self.add_font(handle, writer, ranges, 0.0)
+    }
 
-        Ok(writer_out.font)
+    fn register_sdf_font(
+        &mut self,
+        handle: FontHandle,
+        source: &FontSource,
+        ranges: &[CharacterRange],
+        size: f32,
+        scale: f32,
+        smoothness: f32,
+    ) -> Result<Font, crate::Error> {
+        let writer = FontTextureWriter::new_sdf(source, ranges, size, scale);
+        self.add_font(handle, writer, ranges, smoothness.max(f32::EPSILON))
     }
 }
 
+// a font texture, with the smoothness for distance field fonts, or zero for regular fonts
+struct GLFont {
+    texture: GLTexture,
+    smoothness: f32,
+}
+
 struct DrawGroup {
     start: usize,
     end: usize,
@@ -362,6 +520,9 @@ const VERT_SHADER_SRC: &str = r#"
   layout(location = 4) in vec3 color;
   layout(location = 5) in vec2 clip_pos;
   layout(location = 6) in vec2 clip_size;
+  layout(location = 7) in vec3 color_end;
+  layout(location = 8) in vec2 gradient;
+  layout(location = 9) in float alpha;
 
   out vec2 g_size;
   out vec2 g_tex0;
@@ -369,6 +530,9 @@ const VERT_SHADER_SRC: &str = r#"
   out vec3 g_color;
   out vec2 g_clip_pos;
   out vec2 g_clip_size;
+  out vec3 g_color_end;
+  out vec2 g_gradient;
+  out float g_alpha;
 
   void main() {
     gl_Position = vec4(position, 0.0, 1.0);
@@ -379,6 +543,9 @@ const VERT_SHADER_SRC: &str = r#"
 	g_color = color;
 	g_clip_pos = clip_pos;
 	g_clip_size = clip_size;
+	g_color_end = color_end;
+	g_gradient = gradient;
+	g_alpha = alpha;
   }
 "#;
 
@@ -394,12 +561,22 @@ const GEOM_SHADER_SRC: &str = r#"
   in vec3 g_color[];
   in vec2 g_clip_pos[];
   in vec2 g_clip_size[];
+  in vec3 g_color_end[];
+  in vec2 g_gradient[];
+  in float g_alpha[];
 
   out vec2 v_tex_coords;
   out vec3 v_color;
+  out float v_alpha;
 
   uniform mat4 matrix;
 
+  // blend between the start and end colors based on the corner's position along the gradient direction
+  vec3 corner_color(vec2 corner) {
+    float t = dot(g_gradient[0], corner) / max(g_gradient[0].x + g_gradient[0].y, 1.0);
+    return mix(g_color[0], g_color_end[0], t);
+  }
+
   void main() {
 	vec4 base = gl_in[0].gl_Position;
     
@@ -416,7 +593,8 @@ const GEOM_SHADER_SRC: &str = r#"
     gl_ClipDistance[3] = clip_pos.y + clip_size.y - position.y;
 	gl_Position = matrix * position;
 	v_tex_coords = g_tex0[0];
-	v_color = g_color[0];
+	v_color = corner_color(vec2(0.0, 0.0));
+	v_alpha = g_alpha[0];
 	EmitVertex();
     
     // [0, 1] vertex
@@ -427,7 +605,8 @@ const GEOM_SHADER_SRC: &str = r#"
     gl_ClipDistance[3] = clip_pos.y + clip_size.y - position.y;
 	gl_Position = matrix * position;
 	v_tex_coords = vec2(g_tex0[0].x, g_tex1[0].y);
-	v_color = g_color[0];
+	v_color = corner_color(vec2(0.0, 1.0));
+	v_alpha = g_alpha[0];
     EmitVertex();
     
     // [1, 0] vertex
@@ -438,7 +617,8 @@ const GEOM_SHADER_SRC: &str = r#"
     gl_ClipDistance[3] = clip_pos.y + clip_size.y - position.y;
 	gl_Position = matrix * position;
 	v_tex_coords = vec2(g_tex1[0].x, g_tex0[0].y);
-	v_color = g_color[0];
+	v_color = corner_color(vec2(1.0, 0.0));
+	v_alpha = g_alpha[0];
     EmitVertex();
     
     // [1, 1] vertex
@@ -449,7 +629,8 @@ const GEOM_SHADER_SRC: &str = r#"
     gl_ClipDistance[3] = clip_pos.y + clip_size.y - position.y;
     gl_Position = matrix * position;
     v_tex_coords = g_tex1[0];
-    v_color = g_color[0];
+    v_color = corner_color(vec2(1.0, 1.0));
+    v_alpha = g_alpha[0];
     EmitVertex();
 
     EndPrimitive();
@@ -461,13 +642,14 @@ const FRAGMENT_SHADER_SRC: &str = r#"
 
   in vec2 v_tex_coords;
   in vec3 v_color;
+  in float v_alpha;
 
   out vec4 color;
 
   uniform sampler2D tex;
 
   void main() {
-    color = vec4(v_color, 1.0) * texture(tex, v_tex_coords);
+    color = vec4(v_color, v_alpha) * texture(tex, v_tex_coords);
   }
 "#;
 
@@ -476,13 +658,22 @@ const FONT_FRAGMENT_SHADER_SRC: &str = r#"
 
     in vec2 v_tex_coords;
     in vec3 v_color;
+    in float v_alpha;
 
     out vec4 color;
 
     uniform sampler2D tex;
+    uniform float smoothness;
     
     void main() {
-        color = vec4(v_color, texture(tex, v_tex_coords).r);
+        float value = texture(tex, v_tex_coords).r;
+
+        // distance field fonts have a positive smoothness
+        if (smoothness > 0.0) {
+            value = smoothstep(0.5 - smoothness, 0.5 + smoothness, value);
+        }
+
+        color = vec4(v_color, v_alpha * value);
     }
 "#;
 
@@ -530,6 +721,66 @@ impl DrawList for GLDrawList {
             color: color.into(),
             clip_pos: clip.pos.into(),
             clip_size: clip.size.into(),
+            color_end: color.into(),
+            gradient: [0.0, 0.0],
+            alpha: 1.0,
+        };
+
+        self.vertices.push(vert);
+    }
+
+    fn push_rect_alpha(
+        &mut self,
+        pos: [f32; 2],
+        size: [f32; 2],
+        tex: [TexCoord; 2],
+        color: Color,
+        alpha: f32,
+        clip: Rect,
+    ) {
+        let vert = GLVertex {
+            position: pos,
+            size,
+            tex0: [tex[0].x(), tex[0].y()],
+            tex1: [tex[1].x(), tex[1].y()],
+            color: color.into(),
+            clip_pos: clip.pos.into(),
+            clip_size: clip.size.into(),
+            color_end: color.into(),
+            gradient: [0.0, 0.0],
+            alpha,
+        };
+
+        self.vertices.push(vert);
+    }
+
+    fn push_gradient_rect(
+        &mut self,
+        pos: [f32; 2],
+        size: [f32; 2],
+        tex: [TexCoord; 2],
+        colors: [Color; 2],
+        direction: GradientDirection,
+        alpha: f32,
+        clip: Rect,
+    ) {
+        let gradient = match direction {
+            GradientDirection::Horizontal => [1.0, 0.0],
+            GradientDirection::Vertical => [0.0, 1.0],
+            GradientDirection::Diagonal => [1.0, 1.0],
+        };
+
+        let vert = GLVertex {
+            position: pos,
+            size,
+            tex0: [tex[0].x(), tex[0].y()],
+            tex1: [tex[1].x(), tex[1].y()],
+            color: colors[0].into(),
+            clip_pos: clip.pos.into(),
+            clip_size: clip.size.into(),
+            color_end: colors[1].into(),
+            gradient,
+            alpha,
         };
 
         self.vertices.push(vert);
@@ -546,4 +797,7 @@ pub(crate) struct GLVertex {
     pub color: [f32; 3],
     pub clip_pos: [f32; 2],
     pub clip_size: [f32; 2],
+    pub color_end: [f32; 3],
+    pub gradient: [f32; 2],
+    pub alpha: f32,
 }
@@ -44,6 +44,12 @@ impl Program {
         }
     }
 
+    pub fn uniform1f(&self, uniform_location: i32, value: f32) {
+        unsafe {
+            gl::Uniform1f(uniform_location, value);
+        }
+    }
+
     pub fn get_uniform_location(&self, name: &str) -> i32 {
         let name = std::ffi::CString::new(name).unwrap();
         unsafe { gl::GetUniformLocation(self.program_handle, name.as_ptr() as _) }
@@ -1,6 +1,8 @@
 pub struct GLTexture {
     texture_handle: u32,
     data: Vec<u8>,
+    format: u32,
+    mipmaps: bool,
 }
 
 impl GLTexture {
@@ -11,12 +13,27 @@ impl GLTexture {
         wrap: u32,
         format: u32,
         internal_format: u32,
+        mipmaps: bool,
     ) -> GLTexture {
         let mut texture = GLTexture {
             texture_handle: 0,
             data: image_data.to_vec(),
+            format,
+            mipmaps,
         };
         
+        let levels = if mipmaps {
+            32 - dimensions.0.max(dimensions.1).max(1).leading_zeros()
+        } else {
+            1
+        };
+
+        let min_filter = match (mipmaps, filter) {
+            (true, gl::NEAREST) => gl::NEAREST_MIPMAP_NEAREST,
+            (true, _) => gl::LINEAR_MIPMAP_LINEAR,
+            (false, _) => filter,
+        };
+
         unsafe {
             gl::GenTextures(1, &mut texture.texture_handle);
             gl::BindTexture(gl::TEXTURE_2D, texture.texture_handle);
@@ -24,16 +41,16 @@ impl GLTexture {
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, wrap as _);
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, wrap as _);
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_R, wrap as _);
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, filter as _);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, min_filter as _);
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, filter as _);
 
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_BASE_LEVEL, 0);
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAX_LEVEL, 0);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAX_LEVEL, (levels - 1) as _);
             gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
             
             gl::TexStorage2D(
                 gl::TEXTURE_2D,
-                1,
+                levels as _,
                 internal_format as _,
                 dimensions.0 as _,
                 dimensions.1 as _,
@@ -51,7 +68,9 @@ impl GLTexture {
                 texture.data.as_ptr() as _,
             );
 
-            gl::GenerateMipmap(gl::TEXTURE_2D);
+            if mipmaps {
+                gl::GenerateMipmap(gl::TEXTURE_2D);
+            }
         }
 
         texture
@@ -74,6 +93,30 @@ impl GLTexture {
             gl::BindTexture(gl::TEXTURE_2D, self.texture_handle);
         }
     }
+
+    /// Replaces the texels in the region at `pos` of `size` with `data`
+    pub fn update_region(&self, pos: (u32, u32), size: (u32, u32), data: &[u8]) {
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, self.texture_handle);
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+
+            gl::TexSubImage2D(
+                gl::TEXTURE_2D,
+                0,
+                pos.0 as _,
+                pos.1 as _,
+                size.0 as _,
+                size.1 as _,
+                self.format,
+                gl::UNSIGNED_BYTE,
+                data.as_ptr() as _,
+            );
+
+            if self.mipmaps {
+                gl::GenerateMipmap(gl::TEXTURE_2D);
+            }
+        }
+    }
 }
 
 impl Drop for GLTexture {
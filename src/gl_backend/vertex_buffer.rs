@@ -26,7 +26,7 @@ impl VAO {
                 gl::STATIC_DRAW,
             );
 
-            for idx in 0..=6 {
+            for idx in 0..=9 {
                 gl::EnableVertexAttribArray(idx);    
             }
             
@@ -92,6 +92,33 @@ impl VAO {
                 std::mem::size_of::<GLVertex>() as _,
                 offset_of!(GLVertex, clip_size) as _,
             );
+
+            gl::VertexAttribPointer(
+                7,
+                3,
+                gl::FLOAT,
+                gl::FALSE,
+                std::mem::size_of::<GLVertex>() as _,
+                offset_of!(GLVertex, color_end) as _,
+            );
+
+            gl::VertexAttribPointer(
+                8,
+                2,
+                gl::FLOAT,
+                gl::FALSE,
+                std::mem::size_of::<GLVertex>() as _,
+                offset_of!(GLVertex, gradient) as _,
+            );
+
+            gl::VertexAttribPointer(
+                9,
+                1,
+                gl::FLOAT,
+                gl::FALSE,
+                std::mem::size_of::<GLVertex>() as _,
+                offset_of!(GLVertex, alpha) as _,
+            );
             
 
             gl::BindBuffer(gl::ARRAY_BUFFER, 0);
@@ -10,10 +10,12 @@ use glium::texture::{Texture2d, RawImage2d};
 use glium::index::PrimitiveType;
 
 use crate::{image::ImageDrawParams};
-use crate::render::{view_matrix, TexCoord, DrawList, DrawMode, Renderer, TextureHandle, TextureData, FontHandle};
+use crate::render::{
+    debug_overlay_color, outline_edges, DEBUG_HIGHLIGHT_COLOR, view_matrix, TexCoord, DrawList, DrawMode, Renderer, TextureHandle, TextureData, FontHandle
+};
 use crate::font::{Font, FontSource, FontTextureWriter};
-use crate::theme_definition::CharacterRange;
-use crate::{Frame, Point, Color, Rect};
+use crate::theme_definition::{CharacterRange, GradientDirection};
+use crate::{Align, Frame, Point, Color, Rect};
 
 /// A Thyme [`Renderer`](trait.Renderer.html) for [`Glium`](https://github.com/glium/glium).
 ///
@@ -40,7 +42,10 @@ pub struct GliumRenderer {
 
     // assets loaded from the context
     textures: Vec<GliumTexture>,
-    fonts: Vec<GliumTexture>,
+    fonts: Vec<GliumFont>,
+
+    // plain white texture used for solid color drawing
+    solid_texture: GliumTexture,
 
     // per frame data
     draw_list: GliumDrawList,
@@ -82,8 +87,18 @@ impl GliumRenderer {
             },
         )?;
 
+        let solid_texture = GliumTexture {
+            texture: Texture2d::new(facade, RawImage2d::from_raw_rgba(vec![255u8; 4], (1, 1))).unwrap(),
+            sampler: SamplerBehavior {
+                minify_filter: MinifySamplerFilter::Nearest,
+                magnify_filter: MagnifySamplerFilter::Nearest,
+                ..Default::default()
+            },
+        };
+
         Ok(GliumRenderer {
             context,
+            solid_texture,
             base_program,
             font_program,
             fonts: Vec::new(),
@@ -99,10 +114,61 @@ impl GliumRenderer {
         })
     }
 
-    fn font(&self, font: FontHandle) -> &GliumTexture {
+    fn font(&self, font: FontHandle) -> &GliumFont {
         &self.fonts[font.id()]
     }
 
+    // creates the font texture, with linear filtering for distance field fonts
+    fn add_font(
+        &mut self,
+        handle: FontHandle,
+        writer: FontTextureWriter,
+        ranges: &[CharacterRange],
+        smoothness: f32,
+    ) -> Result<Font, crate::Error> {
+        let writer_out = writer.write(handle, ranges)?;
+
+        let font_tex = Texture2d::with_format(
+            &self.context,
+            RawImage2d {
+                data: Cow::Owned(writer_out.data),
+                width: writer_out.tex_width,
+                height: writer_out.tex_height,
+                format: glium::texture::ClientFormat::U8,
+            },
+            glium::texture::UncompressedFloatFormat::U8,
+            glium::texture::MipmapsOption::NoMipmap,
+        ).unwrap();
+
+        let (minify_filter, magnify_filter) = if smoothness > 0.0 {
+            (MinifySamplerFilter::Linear, MagnifySamplerFilter::Linear)
+        } else {
+            (MinifySamplerFilter::Nearest, MagnifySamplerFilter::Nearest)
+        };
+
+        let sampler = SamplerBehavior {
+            minify_filter,
+            magnify_filter,
+            wrap_function: (
+                SamplerWrapFunction::BorderClamp,
+                SamplerWrapFunction::BorderClamp,
+                SamplerWrapFunction::BorderClamp,
+            ),
+            ..Default::default()
+        };
+
+        let font = GliumFont { texture: GliumTexture { texture: font_tex, sampler }, smoothness };
+
+        assert!(handle.id() <= self.fonts.len());
+        if handle.id() == self.fonts.len() {
+            self.fonts.push(font);
+        } else {
+            self.fonts[handle.id()] = font;
+        }
+
+        Ok(writer_out.font)
+    }
+
     fn texture(&self, texture: TextureHandle) -> &GliumTexture {
         &self.textures[texture.id()]
     }
@@ -110,6 +176,8 @@ impl GliumRenderer {
     /// Draws the specified [`Frame`](struct.Frame.html) to the Glium surface, usually the Glium Frame.
     pub fn draw_frame<T: Surface>(&mut self, target: &mut T, frame: Frame) -> Result<(), GliumError> {
         let mouse_cursor = frame.mouse_cursor();
+        let debug_overlay = frame.debug_overlay();
+        let debug_highlight = frame.debug_highlight().map(|id| id.to_string());
         let (context, widgets, render_groups) = frame.finish_frame();
         let context = context.internal().borrow();
 
@@ -146,6 +214,8 @@ impl GliumRenderer {
                         clip: widget.clip(),
                         time_millis,
                         scale,
+                        anim_transition: context.anim_transition_for(widget.id()),
+                        tint: widget.background_tint(),
                     }
                 );
             }
@@ -163,29 +233,70 @@ impl GliumRenderer {
                     let image = context.themes().image(image_handle);
                     self.write_group_if_changed(&mut draw_mode, DrawMode::Image(image.texture()));
     
-                    image.draw(
-                        &mut self.draw_list,
-                        ImageDrawParams {
-                            pos: fg_pos.into(),
-                            size: fg_size.into(),
-                            anim_state: widget.anim_state(),
-                            clip: widget.clip(),
-                            time_millis,
-                            scale,
-                        }
-                    );
+                    // widgets with lines only draw the foreground image along those lines
+                    if widget.lines().is_empty() {
+                        image.draw(
+                            &mut self.draw_list,
+                            ImageDrawParams {
+                                pos: fg_pos.into(),
+                                size: fg_size.into(),
+                                anim_state: widget.anim_state(),
+                                clip: widget.clip(),
+                                time_millis,
+                                scale,
+                                anim_transition: context.anim_transition_for(widget.id()),
+                                tint: widget.foreground_tint(),
+                            }
+                        );
+                    }
+
+                    for &(from, to, thickness) in widget.lines() {
+                        image.draw_line(
+                            &mut self.draw_list,
+                            fg_pos + from,
+                            fg_pos + to,
+                            thickness,
+                            ImageDrawParams {
+                                pos: fg_pos.into(),
+                                size: fg_size.into(),
+                                anim_state: widget.anim_state(),
+                                clip: widget.clip(),
+                                time_millis,
+                                scale,
+                                anim_transition: context.anim_transition_for(widget.id()),
+                                tint: widget.foreground_tint(),
+                            },
+                        );
+                    }
                 }
     
                 if let Some(text) = widget.text() {
                     if let Some(font_sum) = widget.font() {
                         self.write_group_if_changed(&mut draw_mode, DrawMode::Font(font_sum.handle));
                         let font = context.themes().font(font_sum.handle);
+                        let text = if widget.truncate() {
+                            font.truncate(text, fg_size.x * scale, widget.ellipsis())
+                        } else {
+                            Cow::Borrowed(text)
+                        };
     
+                        if let Some((offset, color)) = widget.text_shadow() {
+                            font.draw(
+                                &mut self.draw_list,
+                                fg_size * scale,
+                                ((fg_pos + offset) * scale).into(),
+                                &text,
+                                widget.text_align(),
+                                color,
+                                widget.clip() * scale,
+                            );
+                        }
+
                         font.draw(
                             &mut self.draw_list,
                             fg_size * scale,
                             (fg_pos * scale).into(),
-                            text,
+                            &text,
                             widget.text_align(),
                             widget.text_color(),
                             widget.clip() * scale,
@@ -213,13 +324,47 @@ impl GliumRenderer {
                 anim_state,
                 clip,
                 time_millis,
-                scale
+                scale,
+                anim_transition: None,
+                tint: None,
             };
 
             image.draw(&mut self.draw_list, params);
             self.write_group(DrawMode::Image(image.texture()));
         }
 
+        if debug_overlay {
+            for widget in widgets.iter().filter(|widget| widget.visible()) {
+                let color = debug_overlay_color(widget.id());
+                let inner_pos = widget.pos() + widget.border().tl();
+                self.draw_rect_outline(Rect::new(widget.pos(), widget.size()) * scale, scale, color);
+                self.draw_rect_outline(Rect::new(inner_pos, widget.inner_size()) * scale, scale, color);
+            }
+            self.write_group(DrawMode::Solid);
+
+            if let Some(font) = context.themes().debug_font() {
+                let display_clip = Rect::new(Point::default(), context.display_size()) * scale;
+                for widget in widgets.iter().filter(|widget| widget.visible()) {
+                    font.draw(
+                        &mut self.draw_list,
+                        (context.display_size() - widget.pos()) * scale,
+                        (widget.pos() * scale).into(),
+                        widget.id(),
+                        Align::TopLeft,
+                        debug_overlay_color(widget.id()),
+                        display_clip,
+                    );
+                }
+                self.write_group(DrawMode::Font(font.handle()));
+            }
+        }
+
+        if let Some(widget) = debug_highlight.and_then(|id| widgets.iter().find(|widget| widget.id() == id)) {
+            let rect = Rect::new(widget.pos(), widget.size()) * scale;
+            self.draw_rect_outline(rect, 2.0 * scale, DEBUG_HIGHLIGHT_COLOR);
+            self.write_group(DrawMode::Solid);
+        }
+
         // create the vertex buffer and draw all groups
         let vertices = glium::VertexBuffer::immutable(
             &self.context, &self.draw_list.vertices
@@ -230,8 +375,9 @@ impl GliumRenderer {
                 DrawMode::Font(font_handle) => {
                     let font = self.font(font_handle);
                     let uniforms = uniform! {
-                        tex: Sampler(&font.texture, font.sampler),
+                        tex: Sampler(&font.texture.texture, font.texture.sampler),
                         matrix: self.matrix,
+                        smoothness: font.smoothness,
                     };
                     target.draw(
                         vertices.slice(group.start..group.end).unwrap(),
@@ -241,8 +387,11 @@ impl GliumRenderer {
                         &self.params
                     )?;
                 },
-                DrawMode::Image(tex_handle) => {
-                    let texture = self.texture(tex_handle);
+                DrawMode::Image(_) | DrawMode::Solid => {
+                    let texture = match group.mode {
+                        DrawMode::Image(tex_handle) => self.texture(tex_handle),
+                        _ => &self.solid_texture,
+                    };
                     let uniforms = uniform! {
                         tex: Sampler(&texture.texture, texture.sampler),
                         matrix: self.matrix,
@@ -290,6 +439,37 @@ impl GliumRenderer {
 }
 
 impl Renderer for GliumRenderer {
+    fn update_texture_region(
+        &mut self,
+        handle: TextureHandle,
+        data: &[u8],
+        pos: (u32, u32),
+        size: (u32, u32),
+    ) -> Result<(), crate::Error> {
+        let region = glium::Rect { left: pos.0, bottom: pos.1, width: size.0, height: size.1 };
+        let image = RawImage2d::from_raw_rgba(data.to_vec(), size);
+        let texture = &self.texture(handle).texture;
+        texture.write(region, image);
+
+        // writing only updates the main level, so regenerate the rest to match
+        if texture.get_mipmap_levels() > 1 {
+            unsafe { texture.generate_mipmaps(); }
+        }
+        Ok(())
+    }
+
+    fn draw_rect_outline(&mut self, rect: Rect, thickness: f32, color: Color) {
+        for (pos, size) in outline_edges(rect, thickness).iter() {
+            self.draw_list.push_rect(
+                *pos,
+                *size,
+                [TexCoord::new(0.0, 0.0), TexCoord::new(1.0, 1.0)],
+                color,
+                rect,
+            );
+        }
+    }
+
     fn register_texture(
         &mut self,
         handle: TextureHandle,
@@ -328,44 +508,21 @@ impl Renderer for GliumRenderer {
         size: f32,
         scale: f32,
     ) -> Result<Font, crate::Error> {
-        let font = &source.font;
-
-        let writer = FontTextureWriter::new(font, ranges, size, scale);
-
-        let writer_out = writer.write(handle, ranges)?;
-
-        let font_tex = Texture2d::with_format(
-            &self.context,
-            RawImage2d {
-                data: Cow::Owned(writer_out.data),
-                width: writer_out.tex_width,
-                height: writer_out.tex_height,
-                format: glium::texture::ClientFormat::U8,
-            },
-            glium::texture::UncompressedFloatFormat::U8,
-            glium::texture::MipmapsOption::NoMipmap,
-        ).unwrap();
-
-        let sampler = SamplerBehavior {
-            minify_filter: MinifySamplerFilter::Nearest,
-            magnify_filter: MagnifySamplerFilter::Nearest,
-            wrap_function: (
-                SamplerWrapFunction::BorderClamp,
-                SamplerWrapFunction::BorderClamp,
-                SamplerWrapFunction::BorderClamp,
-            ),
-            ..Default::default()
-        };
-
-        assert!(handle.id() <= self.fonts.len());
-        if handle.id() == self.fonts.len() {
-            self.fonts.push(GliumTexture { texture: font_tex, sampler });
-        } else {
-            self.fonts[handle.id()] = GliumTexture { texture: font_tex, sampler };
-        }
-        
+        let writer = FontTextureWriter::new(source, ranges, size, scale);
+        self.add_font(handle, writer, ranges, 0.0)
+    }
 
-        Ok(writer_out.font)
+    fn register_sdf_font(
+        &mut self,
+        handle: FontHandle,
+        source: &FontSource,
+        ranges: &[CharacterRange],
+        size: f32,
+        scale: f32,
+        smoothness: f32,
+    ) -> Result<Font, crate::Error> {
+        let writer = FontTextureWriter::new_sdf(source, ranges, size, scale);
+        self.add_font(handle, writer, ranges, smoothness.max(f32::EPSILON))
     }
 }
 
@@ -380,6 +537,12 @@ struct GliumTexture {
     sampler: SamplerBehavior,
 }
 
+// a font texture, with the smoothness for distance field fonts, or zero for regular fonts
+struct GliumFont {
+    texture: GliumTexture,
+    smoothness: f32,
+}
+
 #[derive(Debug)]
 pub enum GliumError {
     DisplayCreation(glium::backend::glutin::DisplayCreationError),
@@ -459,6 +622,9 @@ const VERT_SHADER_SRC: &str = r#"
   in vec3 color;
   in vec2 clip_pos;
   in vec2 clip_size;
+  in vec3 color_end;
+  in vec2 gradient;
+  in float alpha;
 
   out vec2 g_size;
   out vec2 g_tex0;
@@ -466,6 +632,9 @@ const VERT_SHADER_SRC: &str = r#"
   out vec3 g_color;
   out vec2 g_clip_pos;
   out vec2 g_clip_size;
+  out vec3 g_color_end;
+  out vec2 g_gradient;
+  out float g_alpha;
 
   void main() {
     gl_Position = vec4(position, 0.0, 1.0);
@@ -476,6 +645,9 @@ const VERT_SHADER_SRC: &str = r#"
 	g_color = color;
 	g_clip_pos = clip_pos;
 	g_clip_size = clip_size;
+	g_color_end = color_end;
+	g_gradient = gradient;
+	g_alpha = alpha;
   }
 "#;
 
@@ -491,12 +663,22 @@ const GEOM_SHADER_SRC: &str = r#"
   in vec3 g_color[];
   in vec2 g_clip_pos[];
   in vec2 g_clip_size[];
+  in vec3 g_color_end[];
+  in vec2 g_gradient[];
+  in float g_alpha[];
 
   out vec2 v_tex_coords;
   out vec3 v_color;
+  out float v_alpha;
 
   uniform mat4 matrix;
 
+  // blend between the start and end colors based on the corner's position along the gradient direction
+  vec3 corner_color(vec2 corner) {
+    float t = dot(g_gradient[0], corner) / max(g_gradient[0].x + g_gradient[0].y, 1.0);
+    return mix(g_color[0], g_color_end[0], t);
+  }
+
   void main() {
 	vec4 base = gl_in[0].gl_Position;
     
@@ -513,7 +695,8 @@ const GEOM_SHADER_SRC: &str = r#"
     gl_ClipDistance[3] = clip_pos.y + clip_size.y - position.y;
 	gl_Position = matrix * position;
 	v_tex_coords = g_tex0[0];
-	v_color = g_color[0];
+	v_color = corner_color(vec2(0.0, 0.0));
+	v_alpha = g_alpha[0];
 	EmitVertex();
     
     // [0, 1] vertex
@@ -524,7 +707,8 @@ const GEOM_SHADER_SRC: &str = r#"
     gl_ClipDistance[3] = clip_pos.y + clip_size.y - position.y;
 	gl_Position = matrix * position;
 	v_tex_coords = vec2(g_tex0[0].x, g_tex1[0].y);
-	v_color = g_color[0];
+	v_color = corner_color(vec2(0.0, 1.0));
+	v_alpha = g_alpha[0];
     EmitVertex();
     
     // [1, 0] vertex
@@ -535,7 +719,8 @@ const GEOM_SHADER_SRC: &str = r#"
     gl_ClipDistance[3] = clip_pos.y + clip_size.y - position.y;
 	gl_Position = matrix * position;
 	v_tex_coords = vec2(g_tex1[0].x, g_tex0[0].y);
-	v_color = g_color[0];
+	v_color = corner_color(vec2(1.0, 0.0));
+	v_alpha = g_alpha[0];
     EmitVertex();
     
     // [1, 1] vertex
@@ -546,7 +731,8 @@ const GEOM_SHADER_SRC: &str = r#"
     gl_ClipDistance[3] = clip_pos.y + clip_size.y - position.y;
     gl_Position = matrix * position;
     v_tex_coords = g_tex1[0];
-    v_color = g_color[0];
+    v_color = corner_color(vec2(1.0, 1.0));
+    v_alpha = g_alpha[0];
     EmitVertex();
 
     EndPrimitive();
@@ -558,13 +744,14 @@ const FRAGMENT_SHADER_SRC: &str = r#"
 
   in vec2 v_tex_coords;
   in vec3 v_color;
+  in float v_alpha;
 
   out vec4 color;
 
   uniform sampler2D tex;
 
   void main() {
-    color = vec4(v_color, 1.0) * texture(tex, v_tex_coords);
+    color = vec4(v_color, v_alpha) * texture(tex, v_tex_coords);
   }
 "#;
 
@@ -573,13 +760,22 @@ const FONT_FRAGMENT_SHADER_SRC: &str = r#"
 
     in vec2 v_tex_coords;
     in vec3 v_color;
+    in float v_alpha;
 
     out vec4 color;
 
     uniform sampler2D tex;
+    uniform float smoothness;
     
     void main() {
-        color = vec4(v_color, texture(tex, v_tex_coords).r);
+        float value = texture(tex, v_tex_coords).r;
+
+        // distance field fonts have a positive smoothness
+        if (smoothness > 0.0) {
+            value = smoothstep(0.5 - smoothness, 0.5 + smoothness, value);
+        }
+
+        color = vec4(v_color, v_alpha * value);
     }
 "#;
 
@@ -625,6 +821,66 @@ impl DrawList for GliumDrawList {
             color: color.into(),
             clip_pos: clip.pos.into(),
             clip_size: clip.size.into(),
+            color_end: color.into(),
+            gradient: [0.0, 0.0],
+            alpha: 1.0,
+        };
+
+        self.vertices.push(vert);
+    }
+
+    fn push_rect_alpha(
+        &mut self,
+        pos: [f32; 2],
+        size: [f32; 2],
+        tex: [TexCoord; 2],
+        color: Color,
+        alpha: f32,
+        clip: Rect,
+    ) {
+        let vert = GliumVertex {
+            position: pos,
+            size,
+            tex0: [tex[0].x(), tex[0].y()],
+            tex1: [tex[1].x(), tex[1].y()],
+            color: color.into(),
+            clip_pos: clip.pos.into(),
+            clip_size: clip.size.into(),
+            color_end: color.into(),
+            gradient: [0.0, 0.0],
+            alpha,
+        };
+
+        self.vertices.push(vert);
+    }
+
+    fn push_gradient_rect(
+        &mut self,
+        pos: [f32; 2],
+        size: [f32; 2],
+        tex: [TexCoord; 2],
+        colors: [Color; 2],
+        direction: GradientDirection,
+        alpha: f32,
+        clip: Rect,
+    ) {
+        let gradient = match direction {
+            GradientDirection::Horizontal => [1.0, 0.0],
+            GradientDirection::Vertical => [0.0, 1.0],
+            GradientDirection::Diagonal => [1.0, 1.0],
+        };
+
+        let vert = GliumVertex {
+            position: pos,
+            size,
+            tex0: [tex[0].x(), tex[0].y()],
+            tex1: [tex[1].x(), tex[1].y()],
+            color: colors[0].into(),
+            clip_pos: clip.pos.into(),
+            clip_size: clip.size.into(),
+            color_end: colors[1].into(),
+            gradient,
+            alpha,
         };
 
         self.vertices.push(vert);
@@ -640,6 +896,9 @@ struct GliumVertex {
     pub color: [f32; 3],
     pub clip_pos: [f32; 2],
     pub clip_size: [f32; 2],
+    pub color_end: [f32; 3],
+    pub gradient: [f32; 2],
+    pub alpha: f32,
 }
 
-implement_vertex!(GliumVertex, position, size, tex0, tex1, color, clip_pos, clip_size);
+implement_vertex!(GliumVertex, position, size, tex0, tex1, color, clip_pos, clip_size, color_end, gradient, alpha);
@@ -1,9 +1,15 @@
 use std::collections::HashMap;
 
 use crate::{Error};
-use crate::render::{TexCoord, DrawList, TextureHandle, TextureData};
+use crate::render::{TexCoord, DrawList, ModulatedDrawList, TextureHandle, TextureData};
 use crate::{Rect, Color, AnimState, Point};
-use crate::theme_definition::{ImageFill, ImageDefinition, ImageDefinitionKind};
+use crate::theme_definition::{ImageFill, ImageDefinition, ImageDefinitionKind, GradientDirection};
+
+// a position or size within a texture, in texels
+pub(crate) type Texels = (u32, u32);
+
+// the maximum number of rects used to approximate a single line
+const MAX_LINE_STEPS: f32 = 1024.0;
 
 #[derive(Copy, Clone)]
 pub struct ImageHandle {
@@ -35,21 +41,35 @@ enum ImageKind {
         tex_coords: [[TexCoord; 2]; 4],
         grid_size: [f32; 2],
     },
+    ComposedAsymmetric {
+        tex_coords: [[TexCoord; 4]; 4],
+
+        // left, right, top, and bottom border widths
+        borders: [f32; 4],
+    },
     Simple {
         tex_coords: [TexCoord; 2],
         base_size: [f32; 2],
         fill: ImageFill,
     },
+    Gradient {
+        tex_coords: [TexCoord; 2],
+        colors: [Color; 2],
+        direction: GradientDirection,
+    },
     Timed {
         frame_time_millis: u32,
         frames: Vec<Image>,
-        once: bool,
+        repeat_count: Option<u32>,
+        reverse: bool,
     },
     Animated {
-        states: Vec<(AnimState, Image)>
+        states: Vec<(AnimState, Image)>,
+        transition_ms: u32,
     }
 }
 
+#[derive(Copy, Clone)]
 pub(crate) struct ImageDrawParams {
     pub pos: [f32; 2],
     pub size: [f32; 2],
@@ -57,6 +77,12 @@ pub(crate) struct ImageDrawParams {
     pub clip: Rect,
     pub time_millis: u32,
     pub scale: f32,
+
+    // the previous anim state and the millis since the change, for blending animated images
+    pub anim_transition: Option<(AnimState, u32)>,
+
+    // a color multiplied with the image's color
+    pub tint: Option<Color>,
 }
 
 #[derive(Clone)]
@@ -81,11 +107,86 @@ impl Image {
 
     pub fn base_size(&self) -> Point { self.base_size }
 
+    /// Returns the position and size in texels of this image within `texture`, along with its
+    /// base size, if this is a `Simple` image.
+    pub(crate) fn texel_region(&self, texture: &TextureData) -> Option<(Texels, Texels, Point)> {
+        match &self.kind {
+            ImageKind::Simple { tex_coords, base_size, .. } => {
+                // the corners are swapped in flipped images
+                let (a, b) = (texture.texel(tex_coords[0]), texture.texel(tex_coords[1]));
+                let start = (a.0.min(b.0), a.1.min(b.1));
+                let end = (a.0.max(b.0), a.1.max(b.1));
+                Some((start, (end.0 - start.0, end.1 - start.1), (*base_size).into()))
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the time to blend between states, if this is an `Animated` image, or zero otherwise.
+    pub(crate) fn transition_ms(&self) -> u32 {
+        match &self.kind {
+            ImageKind::Animated { transition_ms, .. } => *transition_ms,
+            _ => 0,
+        }
+    }
+
+    /// Returns the frame time and number of frames, if this is a `Timed` image.
+    pub(crate) fn timed_frames(&self) -> Option<(u32, usize)> {
+        match &self.kind {
+            ImageKind::Timed { frame_time_millis, frames, .. } => Some((*frame_time_millis, frames.len())),
+            _ => None,
+        }
+    }
+
+    /// Draws this image along the line from `from` to `to`, in logical pixels, as a series of
+    /// axis aligned rects of the specified `thickness`.  The `pos` and `size` of `params` are ignored.
+    /// Nothing is drawn for a non positive `thickness` or non finite coordinates.
+    pub(crate) fn draw_line<D: DrawList>(
+        &self,
+        draw_list: &mut D,
+        from: Point,
+        to: Point,
+        thickness: f32,
+        params: ImageDrawParams,
+    ) {
+        let finite = [from.x, from.y, to.x, to.y, thickness].iter().all(|value| value.is_finite());
+        if !finite || thickness <= 0.0 { return; }
+
+        let delta = to - from;
+        let half = thickness / 2.0;
+
+        // step along the major axis, with one rect per `thickness` along the minor axis
+        let horizontal = delta.x.abs() >= delta.y.abs();
+        let (major, minor) = if horizontal { (delta.x, delta.y) } else { (delta.y, delta.x) };
+        let steps = (minor.abs() / thickness).ceil().clamp(1.0, MAX_LINE_STEPS);
+
+        for step in 0..(steps as usize) {
+            let start = step as f32 / steps;
+            let end = (step + 1) as f32 / steps;
+            let major_pos = (major * start).min(major * end) - half;
+            let major_size = (major * (end - start)).abs() + thickness;
+            let minor_pos = minor * (start + end) / 2.0 - half;
+
+            let (pos, size) = if horizontal {
+                (Point::new(from.x + major_pos, from.y + minor_pos), Point::new(major_size, thickness))
+            } else {
+                (Point::new(from.x + minor_pos, from.y + major_pos), Point::new(thickness, major_size))
+            };
+
+            self.draw(draw_list, ImageDrawParams { pos: pos.into(), size: size.into(), ..params });
+        }
+    }
+
     pub(crate) fn draw<D: DrawList>(
         &self,
         draw_list: &mut D,
         params: ImageDrawParams,
     ) {
+        if let Some(tint) = params.tint {
+            self.draw(&mut ModulatedDrawList::tint(draw_list, tint), ImageDrawParams { tint: None, ..params });
+            return;
+        }
+
         match &self.kind {
             ImageKind::Empty => (),
             ImageKind::Collected { sub_images } => {
@@ -124,6 +225,8 @@ impl Image {
                         clip,
                         time_millis: params.time_millis,
                         scale: params.scale,
+                        anim_transition: params.anim_transition,
+                        tint: None,
                     };
 
                     image.draw(draw_list, sub_params);
@@ -159,6 +262,18 @@ impl Image {
                     params.clip * params.scale
                 )
             },
+            ImageKind::ComposedAsymmetric { tex_coords, borders } => {
+                let mut borders = *borders;
+                borders.iter_mut().for_each(|border| *border *= params.scale);
+                self.draw_composed_asymmetric(
+                    draw_list,
+                    tex_coords,
+                    borders,
+                    [params.pos[0] * params.scale, params.pos[1] * params.scale],
+                    [params.size[0] * params.scale, params.size[1] * params.scale],
+                    params.clip * params.scale
+                )
+            },
             ImageKind::Simple { tex_coords, base_size, fill } => {
                 let clip = params.clip * params.scale;
                 match fill {
@@ -201,17 +316,29 @@ impl Image {
                     }
                 }
             },
-            ImageKind::Timed { frame_time_millis, frames, once } => {
-                let total_time_millis = frame_time_millis * frames.len() as u32;
-                if *once && params.time_millis > total_time_millis {
-                    frames[frames.len() - 1].draw(draw_list, params);
-                } else {
-                    let frame_index = ((params.time_millis % total_time_millis) / frame_time_millis) as usize;
-                    frames[frame_index].draw(draw_list, params);
-                }
+            ImageKind::Gradient { tex_coords, colors, direction } => {
+                draw_list.push_gradient_rect(
+                    [params.pos[0] * params.scale, params.pos[1] * params.scale],
+                    [params.size[0] * params.scale, params.size[1] * params.scale],
+                    *tex_coords,
+                    *colors,
+                    *direction,
+                    1.0,
+                    params.clip * params.scale,
+                );
+            },
+            ImageKind::Timed { frame_time_millis, frames, repeat_count, reverse } => {
+                let frame_index = timed_frame_index(
+                    *frame_time_millis,
+                    frames.len(),
+                    *repeat_count,
+                    *reverse,
+                    params.time_millis,
+                );
+                frames[frame_index].draw(draw_list, params);
             },
-            ImageKind::Animated { states } => {
-                self.draw_animated(draw_list, states, params);
+            ImageKind::Animated { states, transition_ms } => {
+                self.draw_animated(draw_list, states, *transition_ms, params);
             }
         }
     }
@@ -226,7 +353,7 @@ impl Image {
         let base_size;
         let kind = match &def.kind {
             ImageDefinitionKind::Alias { .. } | ImageDefinitionKind::Group { .. } => unreachable!(),
-            ImageDefinitionKind::Composed { grid_size, position} => {
+            ImageDefinitionKind::Composed { grid_size, position, flip_horizontal, flip_vertical } => {
                 let mut tex_coords = [[TexCoord::default(); 4]; 4];
                 for y in 0..4 {
                     #[allow(clippy::needless_range_loop)]
@@ -237,11 +364,12 @@ impl Image {
                     }
                 }
 
+                flip_grid(&mut tex_coords, *flip_horizontal, *flip_vertical);
                 let grid_size = [grid_size[0] as f32 * scale, grid_size[1] as f32 * scale];
                 base_size = Point::new(grid_size[0] * 3.0, grid_size[1] * 3.0);
                 ImageKind::Composed { tex_coords, grid_size }
             },
-            ImageDefinitionKind::ComposedHorizontal { grid_size_horiz, position } => {
+            ImageDefinitionKind::ComposedHorizontal { grid_size_horiz, position, flip_horizontal, flip_vertical } => {
                 let mut tex_coords = [[TexCoord::default(); 2]; 4];
                 for y in 0..2 {
                     #[allow(clippy::needless_range_loop)]
@@ -252,11 +380,12 @@ impl Image {
                     }
                 }
                 
+                flip_grid(&mut tex_coords, *flip_horizontal, *flip_vertical);
                 let grid_size = [grid_size_horiz[0] as f32 * scale, grid_size_horiz[1] as f32 * scale];
                 base_size = Point::new(grid_size[0] * 3.0, grid_size[1]);
                 ImageKind::ComposedHorizontal { tex_coords, grid_size }
             },
-            ImageDefinitionKind::ComposedVertical { grid_size_vert, position } => {
+            ImageDefinitionKind::ComposedVertical { grid_size_vert, position, flip_horizontal, flip_vertical } => {
                 let mut tex_coords = [[TexCoord::default(); 4]; 2];
                 for y in 0..4 {
                     #[allow(clippy::needless_range_loop)]
@@ -267,16 +396,46 @@ impl Image {
                     }
                 }
                 
+                flip_grid(&mut tex_coords, *flip_horizontal, *flip_vertical);
                 let grid_size = [grid_size_vert[0] as f32 * scale, grid_size_vert[1] as f32 * scale];
                 base_size = Point::new(grid_size[0] * 3.0, grid_size[1]);
                 ImageKind::ComposedVertical { tex_coords, grid_size }
             },
-            ImageDefinitionKind::Simple { size, position, fill } => {
-                let tex1 = texture.tex_coord(position[0], position[1]);
-                let tex2 = texture.tex_coord(position[0] + size[0], position[1] + size[1]);
+            ImageDefinitionKind::ComposedAsymmetric { position, size, left, right, top, bottom } => {
+                if left + right > size[0] || top + bottom > size[1] {
+                    return Err(Error::Theme(format!("Borders are larger than the size of image: {}", image_id)));
+                }
+
+                let xs = [position[0], position[0] + left, position[0] + size[0] - right, position[0] + size[0]];
+                let ys = [position[1], position[1] + top, position[1] + size[1] - bottom, position[1] + size[1]];
+                let mut tex_coords = [[TexCoord::default(); 4]; 4];
+                for (x, x_val) in xs.iter().enumerate() {
+                    for (y, y_val) in ys.iter().enumerate() {
+                        tex_coords[x][y] = texture.tex_coord(*x_val, *y_val);
+                    }
+                }
+
+                let borders = [*left as f32 * scale, *right as f32 * scale, *top as f32 * scale, *bottom as f32 * scale];
+                base_size = Point::new(size[0] as f32 * scale, size[1] as f32 * scale);
+                ImageKind::ComposedAsymmetric { tex_coords, borders }
+            },
+            ImageDefinitionKind::Simple { size, position, fill, flip_horizontal, flip_vertical } => {
+                let (mut x1, mut x2) = (position[0], position[0] + size[0]);
+                let (mut y1, mut y2) = (position[1], position[1] + size[1]);
+                if *flip_horizontal { std::mem::swap(&mut x1, &mut x2); }
+                if *flip_vertical { std::mem::swap(&mut y1, &mut y2); }
+
+                let tex1 = texture.tex_coord(x1, y1);
+                let tex2 = texture.tex_coord(x2, y2);
                 base_size = Point::new(size[0] as f32 * scale, size[1] as f32 * scale);
                 ImageKind::Simple { tex_coords: [tex1, tex2], base_size: base_size.into(), fill: *fill }
             },
+            ImageDefinitionKind::Gradient { start_color, end_color, direction } => {
+                let tex1 = texture.tex_coord(0, 0);
+                let tex2 = texture.tex_coord(1, 1);
+                base_size = Point::default();
+                ImageKind::Gradient { tex_coords: [tex1, tex2], colors: [*start_color, *end_color], direction: *direction }
+            },
             ImageDefinitionKind::Collected { sub_images } => {
                 let mut size = Point::default();
                 let mut images_out = Vec::new();
@@ -294,7 +453,7 @@ impl Image {
                 base_size = size;
                 ImageKind::Collected { sub_images: images_out }
             },
-            ImageDefinitionKind::Timed { frame_time_millis, frames, once } => {
+            ImageDefinitionKind::Timed { frame_time_millis, frames, once, repeat_count, reverse } => {
                 let mut size = Point::default();
                 let mut frames_out = Vec::new();
                 for id in frames {
@@ -310,9 +469,10 @@ impl Image {
                 }
 
                 base_size = size;
-                ImageKind::Timed { frame_time_millis: *frame_time_millis, frames: frames_out, once: *once }
+                let repeat_count = if *once { Some(1) } else { *repeat_count };
+                ImageKind::Timed { frame_time_millis: *frame_time_millis, frames: frames_out, repeat_count, reverse: *reverse }
             },
-            ImageDefinitionKind::Animated { states } => {
+            ImageDefinitionKind::Animated { states, transition_ms } => {
                 let mut size = Point::default();
                 let mut states_out: Vec<(AnimState, Image)> = Vec::new();
                 for (state, id) in states {
@@ -322,7 +482,7 @@ impl Image {
                 }
 
                 base_size = size;
-                ImageKind::Animated { states: states_out }
+                ImageKind::Animated { states: states_out, transition_ms: *transition_ms }
             }
         };
 
@@ -338,12 +498,29 @@ impl Image {
         &self,
         draw_list: &mut D,
         states: &[(AnimState, Image)],
+        transition_ms: u32,
         params: ImageDrawParams,
     ) {
-        for (state, image) in states {
-            if state == &params.anim_state {
+        let find = |anim_state: AnimState| {
+            states.iter().find(|(state, _)| *state == anim_state).map(|(_, image)| image)
+        };
+
+        let previous = match params.anim_transition {
+            Some((prev, elapsed)) if elapsed < transition_ms && prev != params.anim_state => {
+                find(prev).map(|image| (image, elapsed as f32 / transition_ms as f32))
+            },
+            _ => None,
+        };
+
+        match previous {
+            None => if let Some(image) = find(params.anim_state) {
                 image.draw(draw_list, params);
-                break;
+            },
+            Some((prev_image, t)) => {
+                prev_image.draw(&mut ModulatedDrawList::alpha(draw_list, 1.0 - t), params);
+                if let Some(image) = find(params.anim_state) {
+                    image.draw(&mut ModulatedDrawList::alpha(draw_list, t), params);
+                }
             }
         }
     }
@@ -437,6 +614,38 @@ impl Image {
         );
     }
 
+    /// Draws a 3 by 3 grid, where the border `widths` (left, right, top, bottom) may all differ.
+    /// The middle row and column are skipped when there is no room for them.
+    fn draw_composed_asymmetric<D: DrawList>(
+        &self,
+        draw_list: &mut D,
+        tex: &[[TexCoord; 4]; 4],
+        widths: [f32; 4],
+        pos: [f32; 2],
+        size: [f32; 2],
+        clip: Rect,
+    ) {
+        let [left, right, top, bot] = widths;
+        let xs = [pos[0], pos[0] + left, pos[0] + size[0] - right, pos[0] + size[0]];
+        let ys = [pos[1], pos[1] + top, pos[1] + size[1] - bot, pos[1] + size[1]];
+
+        for y in 0..3 {
+            if y == 1 && ys[2] <= ys[1] { continue; }
+
+            for x in 0..3 {
+                if x == 1 && xs[2] <= xs[1] { continue; }
+
+                draw_list.push_rect(
+                    [xs[x], ys[y]],
+                    [xs[x + 1] - xs[x], ys[y + 1] - ys[y]],
+                    [tex[x][y], tex[x + 1][y + 1]],
+                    self.color,
+                    clip,
+                );
+            }
+        }
+    }
+
     fn draw_composed<D: DrawList>(
         &self,
         draw_list: &mut D,
@@ -528,6 +737,41 @@ impl Image {
     }
 }
 
+/// Mirrors the grid of texture coordinates `tex`, indexed by column and then row, so that
+/// the image built from it is drawn flipped.
+fn flip_grid<T: AsMut<[TexCoord]>>(tex: &mut [T], horizontal: bool, vertical: bool) {
+    if horizontal {
+        tex.reverse();
+    }
+
+    if vertical {
+        tex.iter_mut().for_each(|column| column.as_mut().reverse());
+    }
+}
+
+/// Returns the index of the frame of a `Timed` image with `frames` frames to show at `time_millis`.
+/// When `reverse` is set, each cycle plays the frames forwards and then backwards.  After
+/// `repeat_count` cycles, the final frame of the cycle is held.
+fn timed_frame_index(
+    frame_time_millis: u32,
+    frames: usize,
+    repeat_count: Option<u32>,
+    reverse: bool,
+    time_millis: u32,
+) -> usize {
+    let cycle_frames = if reverse && frames > 1 { 2 * frames - 2 } else { frames };
+    let total_time_millis = frame_time_millis.saturating_mul(cycle_frames as u32);
+
+    if let Some(count) = repeat_count {
+        if time_millis >= total_time_millis.saturating_mul(count) {
+            return if reverse { 0 } else { frames - 1 };
+        }
+    }
+
+    let step = ((time_millis % total_time_millis) / frame_time_millis) as usize;
+    if step < frames { step } else { cycle_frames - step }
+}
+
 fn find_image_in_set(parent_id: &str, set: &HashMap<String, Image>, id: &str) -> Result<Image, Error> {
     match set.get(id) {
         None => {
@@ -76,7 +76,22 @@ theme fairly small with just a base set of widgets, defining most things in code
 
 The theme can be defined from any [`serde`](https://serde.rs/)
 compatible source, with the examples in this project using [`YAML`](https://yaml.org/).
-The theme has several sections: `fonts`, `image_sets`, and `widgets`.
+The theme has several sections: `palette`, `fonts`, `image_sets`, and `widgets`.
+
+## Palette
+The optional `palette` section maps names to colors.  The widget color attributes `text_color`, `text_shadow_color`,
+`background_tint`, and `foreground_tint` may then reference a palette color by its name, prefixed with `$`.  Palette colors
+may be changed at runtime with [`Context::set_palette_color`](struct.Context.html#method.set_palette_color), which updates
+every widget referencing them.  This is useful for switching between light and dark color schemes.
+```yaml
+palette:
+  primary: "#FFAA00"
+  background: "#333"
+widgets:
+  label:
+    text_color: "$primary"
+    background_tint: "$background"
+```
 
 ## Fonts
 The `fonts` section consists of a mapping, with `IDs` mapped
@@ -103,6 +118,18 @@ fonts:
     size: 16
 ```
 
+Fonts may also set `sdf: true`, in which case the font atlas stores a signed distance field for each glyph rather than its
+coverage.  This keeps glyph edges crisp when drawn at scales other than their native size.  The optional `smoothness`
+controls how soft the glyph edges are, in units of the distance field, with smaller values producing sharper edges.  The
+`wgpu` backend does not support distance field fonts, and draws them as regular fonts.
+```yaml
+  large:
+    source: roboto
+    size: 32
+    sdf: true
+    smoothness: 0.06
+```
+
 ## Image Sets
 Images are defined as a series of `image_sets`.  Each image_set has an `id`, used as the first
 part of the ID of each image in the set.  The complete image ID is equal to `image_set_id/image_id`.
@@ -146,6 +173,18 @@ over an area.
     fill: Stretch
 ```
 
+#### Gradient Images
+Gradient images do not reference the image source, and instead blend between a `start_color` and `end_color` over the area
+they are drawn to.  The `direction` field is optional, with valid values of `Horizontal` (default) - left to right, `Vertical` - top
+to bottom, and `Diagonal` - top left to bottom right.  Gradients are currently only blended by the Glium and GL renderers; the
+wgpu renderer draws them using the `start_color`.
+```yaml
+  header_background:
+    start_color: "#246"
+    end_color: "#000"
+    direction: Vertical
+```
+
 #### Image Groups
 You can create an image group as a shorthand for multiple simple images.  You specify an overall scale factor and fill, then for each image,
 x, y, width, and height.  These four values are multipled by the scale factor.  All simple images in a group are immediately expanded
@@ -203,9 +242,39 @@ There are also composed horizontal and composed vertical images, that consist of
 are defined and used in the same manner as regular composed images, but use `grid_size_horiz` and `grid_size_vert` to
 differentiate the different types.
 
+#### Flipped Images
+Simple, composed, composed horizontal, and composed vertical images may set the optional `flip_horizontal` and `flip_vertical`
+fields, which both default to `false`.  These mirror the image when it is drawn, allowing one sub-image to be used for both
+a left and right arrow, for example.
+```yaml
+  arrow_left:
+    position: [0, 0]
+    size: [16, 16]
+  arrow_right:
+    position: [0, 0]
+    size: [16, 16]
+    flip_horizontal: true
+```
+
+#### Composed Asymmetric
+Composed asymmetric images are composed images where the borders have different widths, such as a window frame with a title bar
+that is taller than the other edges.  The `size` is the size of the entire image, while `left`, `right`, `top`, and `bottom` give
+the width of each border.  The corners are drawn at a fixed size, while the edges and center stretch as with regular composed images.
+```yaml
+  window_frame:
+    position: [0, 64]
+    size: [64, 64]
+    left: 8
+    right: 8
+    top: 24
+    bottom: 8
+```
+
 #### Timed Images
-Timed images display one out of several frames, on a timer.  Timed images can repeat continuously (the default), or only display once,
-based on the value of the optional `once` parameter.  `frame_time_millis` is how long each frame is shown for, in milliseconds.  Each
+Timed images display one out of several frames, on a timer.  Timed images can repeat continuously (the default), or only play
+a fixed number of times, based on the value of the optional `repeat_count` parameter, and then hold the final frame.  `once: true`
+is equivalent to `repeat_count: 1`.  If the optional `reverse` parameter is set, each cycle plays the frames forwards and then
+backwards, for a ping-pong animation.  `frame_time_millis` is how long each frame is shown for, in milliseconds.  Each
 `frame` is the `id` of an image within the current image set.  It can be any of the other types of images in the current set.
 
 In this example, each frame is displayed for 500 milliseconds in an endless cycle.
@@ -218,6 +287,18 @@ In this example, each frame is displayed for 500 milliseconds in an endless cycl
       - button_bright
 ```
 
+In this example, the frames play forwards and backwards three times, and then the first frame is held.
+```yaml
+  button_pulse:
+    frame_time_millis: 100
+    repeat_count: 3
+    reverse: true
+    frames:
+      - button_normal
+      - button_bright
+      - button_brightest
+```
+
 #### Animated Images
 Animated images display one of several sub images based on the [`AnimState`](struct.AnimState.html). of the parent widget.
 The referenced images are specified by `id`, and can include Simple, Composed, or Collected images.
@@ -232,6 +313,18 @@ The referenced images are specified by `id`, and can include Simple, Composed, o
       Active + Pressed: button_pressed_active
 ```
 
+By default, the image switches between states instantly.  The optional `transition_ms` parameter instead cross-fades
+from the previous state's image to the new one over the specified number of milliseconds.  Blending requires renderer
+support for transparency, which the `wgpu` backend does not have; it switches halfway through the transition instead.
+```yaml
+  button_smooth:
+    transition_ms: 150
+    states:
+      Normal: button_normal
+      Hover: button_hover
+      Pressed: button_pressed
+```
+
 Images which contain references to other images are parsed in a particular order - `Collected`, then `Animated`, then
 `Timed`.  This means an `Animated` image may reference a `Collected` image, but not the other way around.  All of these
 image types may contain references to the basic image types - `Simple`, `Composed`, `ComposedHorizontal`, and
@@ -317,29 +410,72 @@ Each widget theme has many optional attributes that may be defined in the theme
 methods on [`WidgetBuilder`](struct.WidgetBuilder.html) will take precedence over items defined in the theme file.  The
 [`child_align`](struct.WidgetBuilder.html#method.child_align), [`layout`](struct.WidgetBuilder.html#method.layout), and
 [`layout_spacing`](struct.WidgetBuilder.html#method.layout_spacing) fields deal specifically with how
-the widget will layout its children.
+the widget will layout its children.  A `tooltip` (or `tooltip_text`) is shown whenever the widget is hovered, using the
+optional `tooltip_theme`, or the `tooltip` theme by default.  A `cursor`, such as `EwResize`, sets the shape of the operating
+system mouse cursor while the widget is hovered.  See [`CursorIcon`](enum.CursorIcon.html).
 
 ```yaml
    complicated_button:
      text: Hello
      text_color: "#FFAA00"
+     text_shadow_offset: [1, 1]
+     text_shadow_color: "#000"
      text_align: Center
+     truncate: true
+     ellipsis: "..."
      font: medium
      background: gui/button
      foreground: gui/button_icon
+     background_tint: "#CCC"
+     foreground_tint: "#FFAA00"
      tooltip: "This is a button!"
+     tooltip_theme: small_tooltip
+     cursor: Hand
      wants_mouse: true
      wants_scroll: false
+     wants_keyboard: false
+     enabled: true
      pos: [10, 10]
      size: [100, 0]
      width_from: Normal
      height_from: FontLine
      # OR size_from: [Normal, FontLine]
+     aspect_ratio: 1.5
+     split_divider_width: 6
      border: { all: 5 }
      align: TopLeft
      child_align: Top
      layout: Vertical
      layout_spacing: 5
+     layout_free_relative: true
+     z_order: 0
+     z_index: 0
+     size_active: [120, 0]
+     size_hover: [110, 0]
+```
+
+The `z_order` attribute only has an effect on widgets that start a new render group, such as
+[`windows`](struct.WindowBuilder.html#method.z_order).  Render groups with a higher `z_order` are drawn on top.
+The `z_index` attribute instead controls the draw order of a widget relative to its siblings within the same parent.
+
+### State overrides
+The `size_active` and `size_hover` attributes replace the widget's `size` while it is
+[`active`](struct.WidgetBuilder.html#method.active), or hovered or pressed, respectively.  More generally,
+the `state_overrides` mapping replaces the `pos`, `size`, or `border` of the widget while it is in exactly the
+specified [`AnimState`](struct.AnimState.html).  An exact match in `state_overrides` takes precedence over
+`size_hover`, which in turn takes precedence over `size_active`.  The state from the previous frame is used,
+as the widget's state is not known until after it has been laid out.  The mouse is always tested against the
+widget's bounds without any override, so a changed `pos` or `size` does not itself change the widget's state.
+
+```yaml
+  expand_button:
+    from: button
+    size: [20, 20]
+    size_active: [40, 20]
+    state_overrides:
+      Active + Hover:
+        size: [40, 24]
+        border: { all: 6 }
 ```
 
 ### Custom fields
@@ -372,6 +508,7 @@ mod resource;
 mod theme_definition;
 mod point;
 mod scrollpane;
+mod split_pane;
 mod widget;
 mod window;
 mod winit_io;
@@ -401,14 +538,19 @@ pub use gl_backend::GLRenderer;
 
 
 pub use frame::Frame;
+pub use font::FontSummary;
 pub use point::{Rect, Point, Border};
 pub use widget::{WidgetBuilder, WidgetState};
 pub use context_builder::{BuildOptions, ContextBuilder};
 pub use context::{Context, PersistentState, InputModifiers};
-pub use scrollpane::{ScrollpaneBuilder, ShowElement};
-pub use theme_definition::{AnimStateKey, AnimState, Align, Color, Layout, WidthRelative, HeightRelative};
+pub use scrollpane::{ScrollpaneBuilder, ScrollRequest, ShowElement};
+pub use split_pane::{SplitPaneBuilder, SplitDirection};
+pub use recipes::{DragSourceResult, SortOrder, TableColumn, TooltipPlacement};
+pub use theme_definition::{AnimStateKey, AnimState, Align, Color, FontStyle, Layout, WidthRelative, HeightRelative};
+pub use theme_definition::{CollectedSubImage, GradientDirection, ImageDefinition, ImageDefinitionKind, ImageFill};
+pub use theme_definition::{CharacterRange, FontDefinition, ImageSet, ThemeColor, ThemeDefinition, ThemeDefinitionBuilder, WidgetThemeDefinition, WidgetThemeOverride};
 pub use window::WindowBuilder;
-pub use winit_io::WinitIo;
+pub use winit_io::{WinitIo, KeyCode, CursorIcon};
 
 pub use render::{IO, Renderer};
 
@@ -424,6 +566,12 @@ pub enum Error {
     /// An error originating from an invalid font source
     FontSource(String),
 
+    /// An error loading the image file at the specified path, along with the underlying error
+    ImageLoad(String, Box<dyn std::error::Error + Send + Sync>),
+
+    /// An error parsing the font data for the specified font source, along with the underlying error
+    FontParse(String, Box<dyn std::error::Error + Send + Sync>),
+
     /// An error that occurred attempting to use the filesystem
     IO(std::io::Error),
 
@@ -446,9 +594,11 @@ impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         use self::Error::*;
         match self {
-          Serde(e) => write!(f, "Error deserializing theme: {}", e),
+          Serde(e) => write!(f, "Error deserializing data: {}", e),
             Theme(msg) => write!(f, "Error creating theme from theme definition: {}", msg),
             FontSource(msg) => write!(f, "Error reading font source: {}", msg),
+            ImageLoad(path, _) => write!(f, "Error loading image '{}'", path),
+            FontParse(id, _) => write!(f, "Error parsing font source '{}'", id),
             IO(error) => write!(f, "IO Error: {}", error),
             DisplayCreation(msg) => write!(f, "Error creating display: {}", msg),
             Winit(error) => write!(f, "Winit error: {}", error),
@@ -469,6 +619,8 @@ impl std::error::Error for Error {
             Serde(..) => None,
             Theme(..) => None,
             FontSource(..) => None,
+            ImageLoad(_, error) => Some(error.as_ref()),
+            FontParse(_, error) => Some(error.as_ref()),
             IO(error) => Some(error),
             DisplayCreation(..) => None,
             Winit(error) => Some(error),
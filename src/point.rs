@@ -24,6 +24,24 @@ pub struct Border {
 }
 
 impl Border {
+    /// Creates a new border with all four edges set to `value`.  This is equivalent
+    /// to `all: {value}` in the theme file.
+    pub fn uniform(value: f32) -> Border {
+        Border { top: value, bot: value, left: value, right: value }
+    }
+
+    /// Creates a new border with the specified `left` and `right` edges, and
+    /// zero top and bottom edges.
+    pub fn left_right(left: f32, right: f32) -> Border {
+        Border { top: 0.0, bot: 0.0, left, right }
+    }
+
+    /// Creates a new border with the specified `top` and `bot` edges, and
+    /// zero left and right edges.
+    pub fn top_bot(top: f32, bot: f32) -> Border {
+        Border { top, bot, left: 0.0, right: 0.0 }
+    }
+
     /// The vertical border, top plus bottom
     pub fn vertical(&self) -> f32 {
         self.top + self.bot
@@ -228,6 +246,29 @@ impl Rect {
             size: max - min,
         }
     }
+
+    /// Returns true if the specified point is inside (or on the edge of)
+    /// this rectangle; false otherwise.  This is equivalent to [`is_inside`](#method.is_inside).
+    pub fn contains(&self, pos: Point) -> bool {
+        self.is_inside(pos)
+    }
+
+    /// Returns the area covered by both this and `other`, or `None` if they
+    /// do not overlap.  Rectangles which only share an edge do not overlap.
+    pub fn intersection(self, other: Rect) -> Option<Rect> {
+        let rect = self.min(other);
+        if rect.size.x > 0.0 && rect.size.y > 0.0 {
+            Some(rect)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the smallest `Rect` containing both this and `other`.  This is
+    /// equivalent to [`max`](#method.max).
+    pub fn union(self, other: Rect) -> Rect {
+        self.max(other)
+    }
 }
 
 impl Mul<Rect> for f32 {
@@ -274,6 +315,54 @@ impl Point {
         }
     }
 
+    /// Returns a point with both components rounded down to the nearest integer
+    pub fn floor(self) -> Point {
+        Point {
+            x: self.x.floor(),
+            y: self.y.floor(),
+        }
+    }
+
+    /// Returns a point with both components rounded up to the nearest integer
+    pub fn ceil(self) -> Point {
+        Point {
+            x: self.x.ceil(),
+            y: self.y.ceil(),
+        }
+    }
+
+    /// Returns a point with each component clamped between the corresponding
+    /// components of `min` and `max`
+    pub fn clamp(self, min: Point, max: Point) -> Point {
+        self.max(min).min(max)
+    }
+
+    /// Linearly interpolates between this point, at `t = 0.0`, and `other`, at `t = 1.0`
+    pub fn lerp(self, other: Point, t: f32) -> Point {
+        self + (other - self) * t
+    }
+
+    /// Returns the length of this point, treated as a vector from the origin
+    pub fn length(self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    /// Returns this point scaled to have a [`length`](#method.length) of one.  If
+    /// the length is zero, the zero point is returned.
+    pub fn normalize(self) -> Point {
+        let length = self.length();
+        if length == 0.0 {
+            Point::default()
+        } else {
+            self / length
+        }
+    }
+
+    /// Returns the dot product of this point and `other`
+    pub fn dot(self, other: Point) -> f32 {
+        self.x * other.x + self.y * other.y
+    }
+
     /// Returns a per-component maximum of this and `other`
     pub fn max(self, other: Point) -> Point {
         Point {
@@ -1,6 +1,94 @@
+use std::any::Any;
 use std::fmt::Display;
 
-use crate::{Align, Frame, Point, Rect, WidgetState};
+use serde::{Serialize, Deserialize};
+
+use crate::{Align, Color, Frame, Layout, Point, Rect, SplitDirection, WidgetState, WidthRelative, HeightRelative};
+use crate::context::DragState;
+use crate::winit_io::KeyCode;
+
+/// The ID used for the persistent state of the toast container.  Each
+/// individual toast uses `{TOASTS_ID}/{toast_id}`, where `toast_id` is unique to that toast.
+pub(crate) const TOASTS_ID: &str = "__thyme_toasts";
+
+/// The ID used for the widget and render group of the drag and drop preview.
+pub(crate) const DRAG_GHOST_ID: &str = "__thyme_drag_ghost";
+
+/// The theme used for context menus.
+const CONTEXT_MENU_THEME: &str = "context_menu";
+
+/// The ID and theme used for the widget inspector window.
+const WIDGET_INSPECTOR_ID: &str = "widget_inspector";
+
+/// The result of a [`begin_drag_source`](struct.Frame.html#method.begin_drag_source) widget.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DragSourceResult {
+    /// Whether the mouse clicked on the drag source on the current frame, without dragging it.
+    pub clicked: bool,
+
+    /// Whether this drag source is currently being dragged.
+    pub dragging: bool,
+}
+
+/// Where a tooltip created with [`tooltip_placed`](struct.Frame.html#method.tooltip_placed)
+/// is positioned.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TooltipPlacement {
+    /// Centered horizontally above the widget.
+    Above,
+
+    /// Centered horizontally below the widget.
+    Below,
+
+    /// Centered vertically to the left of the widget.
+    Left,
+
+    /// Centered vertically to the right of the widget.
+    Right,
+
+    /// At the bottom right of the mouse cursor.
+    Cursor,
+}
+
+/// A column of a [`table`](struct.Frame.html#method.table).
+#[derive(Debug, Copy, Clone)]
+pub struct TableColumn<'a> {
+    /// The ID of this column, returned when the user changes the sort order.
+    pub id: &'a str,
+
+    /// The text shown in this column's header.
+    pub label: &'a str,
+
+    /// Whether clicking on this column's header changes the sort order.
+    pub sortable: bool,
+
+    /// The width of this column, in logical pixels.
+    pub width: f32,
+}
+
+/// The sort order of a [`table`](struct.Frame.html#method.table) column.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum SortOrder {
+    /// Sorted from smallest to largest.
+    Ascending,
+
+    /// Sorted from largest to smallest.
+    Descending,
+
+    /// Not sorted.
+    #[default]
+    None,
+}
+
+impl SortOrder {
+    fn next(self) -> SortOrder {
+        match self {
+            SortOrder::Ascending => SortOrder::Descending,
+            SortOrder::Descending => SortOrder::None,
+            SortOrder::None => SortOrder::Ascending,
+        }
+    }
+}
 
 // Specific widget builders and convenience methods
 impl Frame {
@@ -37,6 +125,121 @@ impl Frame {
         self.start(theme).text(text).finish();
     }
 
+    /**
+    A simple [`label`](#method.label) displaying the specified `text`, using the specified `align`
+    for the text instead of the theme's `text_align`.
+
+    # Example
+    ```
+    fn create_stats(ui: &mut Frame, score: u32) {
+        ui.label_aligned("label", format!("Score: {}", score), Align::Right);
+    }
+    ```
+    **/
+    pub fn label_aligned<T: Into<String>>(&mut self, theme: &str, text: T, align: Align) {
+        self.start(theme).text(text).text_align(align).finish();
+    }
+
+    /**
+    A simple [`label`](#method.label) displaying the specified `text`, using the specified `color`
+    for the text instead of the theme's `text_color`.
+
+    # Example
+    ```
+    fn create_status(ui: &mut Frame, error: &str) {
+        ui.label_colored("label", error, Color::red());
+    }
+    ```
+    **/
+    pub fn label_colored<T: Into<String>>(&mut self, theme: &str, text: T, color: Color) {
+        self.start(theme).text(text).text_color(color).finish();
+    }
+
+    /**
+    Displays the image with the specified `image_id` at the specified `size`, without requiring a theme
+    entry for the widget.  The image ID consists of "{image_set_id}/{image_id}".  The widget is given the
+    specified `id`.  Simple images with `fill: Stretch` are drawn at `size`, while those with `fill: None`
+    are drawn at their own size.  Other image types, such as composed images, are drawn to fill `size`.
+
+    # Example
+    ```
+    fn create_portrait(ui: &mut Frame) {
+        ui.image("portrait", "gui/portrait", Point::new(64.0, 64.0));
+    }
+    ```
+    **/
+    pub fn image(&mut self, id: &str, image_id: &str, size: Point) -> WidgetState {
+        self.start("default")
+        .id(id)
+        .background(image_id)
+        .size(size.x, size.y)
+        .finish()
+    }
+
+    /**
+    Displays the image with the specified `image_id`, scaled so that it fits within `max_size` while
+    preserving the aspect ratio of the image's base size.  If the image has no base size, it is
+    drawn at `max_size`.  See [`image`](#method.image).
+    **/
+    pub fn image_scaled_to_fit(&mut self, id: &str, image_id: &str, max_size: Point) -> WidgetState {
+        let size = match self.context().image_base_size(image_id) {
+            Some(base) if base.x > 0.0 && base.y > 0.0 => {
+                let scale = (max_size.x / base.x).min(max_size.y / base.y);
+                base * scale
+            },
+            _ => max_size,
+        };
+
+        self.image(id, image_id, size)
+    }
+
+    /**
+    Adds an invisible widget of the specified `width` and `height`, which takes up space in the parent's
+    layout just as a visible widget of that size would.  No theme entry is needed, and nothing is drawn.
+
+    # Example
+    ```
+    fn create_toolbar(ui: &mut Frame) {
+        ui.button("button", "Open");
+        ui.spacer(20.0, 0.0);
+        ui.button("button", "Quit");
+    }
+    ```
+    **/
+    pub fn spacer(&mut self, width: f32, height: f32) {
+        self.start("default").size(width, height).finish();
+    }
+
+    /**
+    A separator line, drawn using the widget's `background`.  In a `Horizontal` layout, the separator
+    fills the parent's inner height and uses the `size.x` from the theme as its thickness.  Otherwise,
+    it fills the parent's inner width and uses the `size.y` from the theme as its thickness.
+
+    An example theme definition:
+    ```yaml
+    separator:
+      background: gui/greyed_out
+      size: [2, 2]
+    ```
+
+    # Example
+    ```
+    fn create_menu(ui: &mut Frame) {
+        ui.button("button", "Open");
+        ui.separator("separator");
+        ui.button("button", "Quit");
+    }
+    ```
+    **/
+    pub fn separator(&mut self, theme: &str) {
+        let align = match self.widget(self.parent_index()).layout() {
+            Layout::Horizontal => Align::StretchVertical,
+            _ => Align::StretchHorizontal,
+        };
+
+        self.start(theme).align(align).finish();
+    }
+
     /**
     A simple button with a text `label`.
 
@@ -181,6 +384,264 @@ impl Frame {
         new_value
     }
 
+    /**
+    A rotary knob, used to select a value between `min` and `max`.  The user changes the value by pressing
+    the knob and dragging the mouse vertically, with upward movement increasing the value.  Dragging over
+    `drag_distance` logical pixels, specified in the theme's `custom_floats` and defaulting to 200, covers
+    the full range.  The specified `id` must be unique.  Returns the new value if the user moved the knob
+    on this frame, None, otherwise.  Will always return a value within [`min`, `max`] inclusive.  `max` must
+    be greater than `min`.
+
+    Thyme does not rotate images, so the angle of the knob is shown by the foreground of the `dial` child.
+    This should be a [`Timed`](index.html#timed-images) image, with one frame per angular position, from the
+    position at `min` to the position at `max`.  The frame closest to the current value is always shown,
+    regardless of the frame time.  Any other image type is simply drawn as is.
+
+    An example theme definition:
+    ```yaml
+    knob:
+      wants_mouse: true
+      size: [48, 48]
+      custom_floats:
+        drag_distance: 200
+      children:
+        dial:
+          size_from: [Parent, Parent]
+          foreground: gui/knob_dial
+    ```
+
+    And the matching image definition, in the `gui` image set:
+    ```yaml
+    knob_dial:
+      frame_time_millis: 10
+      frames:
+        - knob_0
+        - knob_1
+        - knob_2
+        - knob_3
+    ```
+
+    # Example
+    ```
+    fn volume(ui: &mut Frame, volume: &mut f32) {
+        if let Some(new_volume) = ui.knob("knob", "volume", 0.0, 1.0, *volume) {
+            *volume = new_volume;
+        }
+    }
+    ```
+    */
+    pub fn knob(&mut self, theme: &str, id: &str, min: f32, max: f32, value: f32) -> Option<f32> {
+        let distance = match self.custom_float(theme, "drag_distance", 0.0) {
+            distance if distance > 0.0 => distance,
+            _ => 200.0,
+        };
+        let fraction = (value - min) / (max - min);
+
+        let result = self.start(theme)
+        .id(id)
+        .wants_mouse(true)
+        .children(|ui| {
+            ui.start("dial").foreground_frame(fraction).finish();
+        });
+
+        if result.pressed && result.moved.y != 0.0 {
+            let new_val = value - (max - min) * result.moved.y / distance;
+            Some(new_val.min(max).max(min))
+        } else {
+            None
+        }
+    }
+
+    /**
+    A line chart with the specified `theme` and `id`, plotting the values in `data` as a connected line.  Each value is placed at its index along
+    the x axis, and the visible area of the `plot` covers `x_range` horizontally and `y_range` vertically.
+    The line is drawn in the `line` child using its foreground image, with a thickness of `line_thickness`,
+    specified in the theme's `custom_floats` and defaulting to 1.  `grid_lines` evenly spaced horizontal
+    `grid_line` children are drawn behind the line.  The range endpoints are shown in the `x_min`, `x_max`,
+    `y_min`, and `y_max` labels.
+
+    An example theme definition:
+    ```yaml
+    line_chart:
+      size: [300, 200]
+      background: gui/window_bg_base
+      border: { all: 5 }
+      custom_floats:
+        grid_lines: 3
+        line_thickness: 2
+      children:
+        plot:
+          pos: [30, 0]
+          size_from: [Parent, Parent]
+          size: [-30, -20]
+          background: gui/frame
+          children:
+            grid_line:
+              width_from: Parent
+              size: [0, 1]
+              background: gui/greyed_out
+            line:
+              size_from: [Parent, Parent]
+              foreground: gui/caret_on
+        y_max:
+          from: label
+          align: TopLeft
+          size: [30, 20]
+        y_min:
+          from: label
+          align: BotLeft
+          pos: [0, 20]
+          size: [30, 20]
+        x_min:
+          from: label
+          align: BotLeft
+          pos: [30, 0]
+          size: [30, 20]
+        x_max:
+          from: label
+          align: BotRight
+          size: [30, 20]
+    ```
+
+    # Example
+    ```
+    fn frame_times(ui: &mut Frame, times: &[f32]) {
+        ui.line_chart("line_chart", "frame_times", times, (0.0, times.len() as f32 - 1.0), (0.0, 33.0));
+    }
+    ```
+    */
+    pub fn line_chart(&mut self, theme: &str, id: &str, data: &[f32], x_range: (f32, f32), y_range: (f32, f32)) {
+        let grid_lines = self.custom_float(theme, "grid_lines", 0.0) as usize;
+        let thickness = match self.custom_float(theme, "line_thickness", 0.0) {
+            thickness if thickness > 0.0 => thickness,
+            _ => 1.0,
+        };
+
+        self.start(theme)
+        .id(id)
+        .children(|ui| {
+            let mut plot = Rect::default();
+            ui.start("plot")
+            .trigger_layout_inner(&mut plot)
+            .clip(plot)
+            .children(|ui| {
+                chart_grid(ui, grid_lines, plot.size);
+
+                let mut inner = Rect::default();
+                let mut builder = ui.start("line").trigger_layout_inner(&mut inner);
+
+                let to_point = |index: usize, value: f32| Point::new(
+                    inner.size.x * (index as f32 - x_range.0) / (x_range.1 - x_range.0),
+                    inner.size.y * (1.0 - (value - y_range.0) / (y_range.1 - y_range.0)),
+                );
+
+                for (index, pair) in data.windows(2).enumerate() {
+                    builder = builder.line(to_point(index, pair[0]), to_point(index + 1, pair[1]), thickness);
+                }
+                builder.finish();
+            });
+
+            ui.label("y_max", y_range.1.to_string());
+            ui.label("y_min", y_range.0.to_string());
+            ui.label("x_min", x_range.0.to_string());
+            ui.label("x_max", x_range.1.to_string());
+        });
+    }
+
+    /**
+    A bar chart with the specified `theme` and `id`, with one bar per entry in `data`.  The `plot` is divided evenly between the entries, and each
+    `bar` is sized relative to the largest value, leaving `bar_spacing` logical pixels between bars, specified in
+    the theme's `custom_floats`.  Each entry's label is shown in a `bar_label` below its bar, and the largest
+    value is shown in the `y_max` label.  `grid_lines` evenly spaced horizontal `grid_line` children are drawn
+    behind the bars.  Negative values are drawn as empty bars.
+
+    An example theme definition:
+    ```yaml
+    bar_chart:
+      size: [300, 200]
+      background: gui/window_bg_base
+      border: { all: 5 }
+      custom_floats:
+        grid_lines: 3
+        bar_spacing: 6
+      children:
+        plot:
+          pos: [30, 0]
+          size_from: [Parent, Parent]
+          size: [-30, -20]
+          background: gui/frame
+          children:
+            grid_line:
+              width_from: Parent
+              size: [0, 1]
+              background: gui/greyed_out
+            bar:
+              background: gui/small_button_normal
+        y_max:
+          from: label
+          align: TopLeft
+          size: [30, 20]
+        bar_label:
+          from: label
+          size: [0, 20]
+    ```
+
+    # Example
+    ```
+    fn scores(ui: &mut Frame) {
+        let data = vec![("Red".to_string(), 3.0), ("Blue".to_string(), 5.0)];
+        ui.bar_chart("bar_chart", "scores", &data);
+    }
+    ```
+    */
+    pub fn bar_chart(&mut self, theme: &str, id: &str, data: &[(String, f32)]) {
+        let grid_lines = self.custom_float(theme, "grid_lines", 0.0) as usize;
+        let spacing = self.custom_float(theme, "bar_spacing", 0.0);
+        let max = data.iter().map(|(_, value)| *value).fold(0.0, f32::max);
+
+        let mut chart = Rect::default();
+        self.start(theme)
+        .id(id)
+        .trigger_layout_inner(&mut chart)
+        .children(|ui| {
+            let slot = |plot: Rect| if data.is_empty() { 0.0 } else { plot.size.x / data.len() as f32 };
+
+            let mut plot = Rect::default();
+            ui.start("plot")
+            .trigger_layout_inner(&mut plot)
+            .clip(plot)
+            .children(|ui| {
+                chart_grid(ui, grid_lines, plot.size);
+
+                let slot = slot(plot);
+                for (index, (_, value)) in data.iter().enumerate() {
+                    let height = if max > 0.0 { plot.size.y * value.max(0.0) / max } else { 0.0 };
+
+                    ui.start("bar")
+                    .align(Align::BotLeft)
+                    .pos(index as f32 * slot + spacing / 2.0, 0.0)
+                    .size((slot - spacing).max(0.0), height)
+                    .finish();
+                }
+            });
+
+            ui.label("y_max", max.to_string());
+
+            let slot = slot(plot);
+            let offset = plot.pos.x - chart.pos.x;
+            for (index, (label, _)) in data.iter().enumerate() {
+                let mut rect = Rect::default();
+                ui.start("bar_label")
+                .text(label)
+                .trigger_layout(&mut rect)
+                .align(Align::BotLeft)
+                .pos(offset + index as f32 * slot, 0.0)
+                .size(slot, rect.size.y)
+                .finish();
+            }
+        });
+    }
+
     /**
     A spinner, used to select a numeric value.  The spinner includes a label, a button to increase the value,
     and a button to decrease the value.  If the decrease button is clicked, returns -1, while if
@@ -301,97 +762,653 @@ impl Frame {
         });
     }
 
-    // TODO menubar
-
     /**
-    A drop down box. It displays its currently active selection (`current`), and opens a modal popup to select a new
-    choice from the list of `values` when the user clicks on it.  The specified `id` must be unique.
-    The method will return a selected choice on the frame the user clicks on it, otherwise returning `None`.
+    A tab panel.  A bar of `tabs` is shown, with the currently selected tab drawn in the `Active` state.  Clicking
+    on a tab selects it.  The `children` closure is called with the index of the selected tab to fill the content
+    area, which is clipped to its bounds.  The selected index is stored in the [`PersistentState`](struct.PersistentState.html)
+    of the specified `id` (see [`Frame.selected`](struct.Frame.html#method.selected)) and is also returned.
 
-    An example theme definition;  See [`ScrollpaneBuilder`](struct.ScrollpaneBuilder.html) for the scrollpane example.
     ```yaml
-    combo_box:
-      from: button
+    tab_panel:
+      size_from: [Parent, Parent]
+      layout: Vertical
+      layout_spacing: [0, 2]
       children:
-        expand:
-          size: [12, 12]
-          align: Right
-          foreground: gui/arrow_down
-        combo_box_popup:
-          from: scrollpane
-          width_from: Parent
-          height_from: Normal
-          size: [10, 75]
-          background: gui/small_button_normal
+        tab_bar:
+          size_from: [Parent, Normal]
+          size: [0, 24]
+          layout: Horizontal
+          layout_spacing: [2, 0]
           children:
-            content:
-              size: [-18, -10]
-              children:
-                entry:
-                  from: button
-                  width_from: Parent
-                  size: [0, 25]
-            scrollbar_vertical:
-              size: [20, 20]
+            tab:
+              from: button
+              size: [80, 24]
+        content:
+          size_from: [Parent, Parent]
+          size: [0, -26]
+          border: { all: 5 }
+          background: gui/frame
     ```
-    */
-    pub fn combo_box<'a, T: Display>(&mut self, theme: &str, id: &str, current: &T, values: &'a [T]) -> Option<&'a T> {
-        let popup_id = format!("{}_popup", id);
 
-        let mut result = None;
-        let open_result = self.start(theme)
-        .text(current.to_string())
-        .wants_mouse(true)
-        .children(|ui| {
-            ui.child("expand");
+    # Example
+    ```
+    fn create_tabs(ui: &mut Frame) {
+        ui.tab_panel("tab_panel", "unique_id", &["Stats", "Inventory"], |ui, tab| {
+            match tab {
+                0 => ui.label("label", "Stats"),
+                _ => ui.label("label", "Inventory"),
+            }
+        });
+    }
+    ```
+    */
+    pub fn tab_panel<F: FnOnce(&mut Frame, usize)>(
+        &mut self,
+        theme: &str,
+        id: &str,
+        tabs: &[&str],
+        children: F,
+    ) -> usize {
+        let mut selected = self.selected(id).min(tabs.len().saturating_sub(1));
 
-            ui.start("combo_box_popup")
-            .id(&popup_id)
-            .initially_open(false)
-            .unclip()
-            .unparent()
-            .new_render_group()
-            .scrollpane(&format!("{}_content", popup_id))
-            .children(|ui| {
-                for value in values {
-                    if ui.button("entry", value.to_string()).clicked {
-                        result = Some(value);
-                        ui.close(&popup_id);
+        self.start(theme).children(|ui| {
+            ui.start("tab_bar").children(|ui| {
+                for (index, tab) in tabs.iter().enumerate() {
+                    if ui.toggle_button("tab", *tab, index == selected).clicked {
+                        selected = index;
                     }
                 }
             });
 
+            let mut rect = Rect::default();
+            ui.start("content")
+            .trigger_layout(&mut rect)
+            .clip(rect)
+            .children(|ui| {
+                (children)(ui, selected);
+            });
         });
-        if open_result.clicked {
-            self.open_modal(&popup_id);
-            self.close_modal_on_click_outside();
-        }
 
-        result
-    }
+        if selected != self.selected(id) {
+            self.set_selected(id, selected);
+        }
 
-    /// A simple toggle button that can be toggle on or off, based on the passed in `active` state.
-    ///
-    /// See [`button`](#method.button) for a YAML example.
-    pub fn toggle_button<T: Into<String>>(&mut self, theme: &str, label: T, active: bool) -> WidgetState {
-        self.start(theme).text(label).active(active).wants_mouse(true).finish()
+        selected
     }
 
     /**
-    Creates a simple text input field.  The `id` that is passed in must be unique.
-    The text input will grab keyboard focus when the user clicks on it, allowing
-    the user to type text.  The return value will be `None` if the text didn't change
-    this frame, or will contain the current text displayed by the textbox if it did
-    change.  Optionally, pass an initial_value which will set the field's text if it
-    is not already set.
+    A table with a header row of `columns` and the specified number of `rows`.  The `cell_builder` closure is
+    called for each cell with the row index and column index, with the cell clipped to its column width.
+    Clicking on the header of a [`sortable`](struct.TableColumn.html#structfield.sortable) column cycles its
+    sort order between `Ascending`, `Descending`, and `None`.  The sort column index and order are stored in
+    the [`PersistentState`](struct.PersistentState.html) of the specified `id` (see
+    [`Frame.selected`](struct.Frame.html#method.selected) and [`Frame.sort_order`](struct.Frame.html#method.sort_order)).
+    On the frame the user changes the sort order, the column ID and new order are returned.  The table does not
+    sort any data itself; the `cell_builder` should display rows in the desired order.
 
-    An example YAML theme definition:
     ```yaml
-    input_field:
-      font: small
-      border: { height: 4, width: 5 }
-      background: gui/input_field
-      text_align: Left
+    table:
+      size_from: [Parent, Children]
+      layout: Vertical
+      children:
+        header:
+          size_from: [Parent, Normal]
+          size: [0, 24]
+          layout: Horizontal
+          children:
+            column:
+              from: button
+              children:
+                ascending:
+                  size: [12, 12]
+                  align: Right
+                  foreground: gui/arrow_up
+                descending:
+                  size: [12, 12]
+                  align: Right
+                  foreground: gui/arrow_down
+        row:
+          size_from: [Parent, Normal]
+          size: [0, 24]
+          layout: Horizontal
+          children:
+            cell:
+              layout: Horizontal
+              child_align: Left
+    ```
+
+    # Example
+    ```
+    fn create_table(ui: &mut Frame, names: &[String], scores: &[u32]) {
+        let columns = [
+            TableColumn { id: "name", label: "Name", sortable: true, width: 150.0 },
+            TableColumn { id: "score", label: "Score", sortable: false, width: 50.0 },
+        ];
+
+        ui.table("table", "unique_id", &columns, names.len(), |ui, row, column| {
+            match column {
+                0 => ui.label("label", &names[row]),
+                _ => ui.label("label", scores[row].to_string()),
+            }
+        });
+    }
+    ```
+    */
+    pub fn table<'b, F: FnMut(&mut Frame, usize, usize)>(
+        &mut self,
+        theme: &str,
+        id: &str,
+        columns: &[TableColumn<'b>],
+        rows: usize,
+        mut cell_builder: F,
+    ) -> Option<(&'b str, SortOrder)> {
+        let sort_column = self.selected(id);
+        let sort_order = self.sort_order(id);
+        let mut changed = None;
+
+        self.start(theme).children(|ui| {
+            ui.start("header").children(|ui| {
+                for (index, column) in columns.iter().enumerate() {
+                    let order = if index == sort_column { sort_order } else { SortOrder::None };
+
+                    let result = ui.start("column")
+                    .text(column.label)
+                    .size(column.width, 0.0)
+                    .width_from(WidthRelative::Normal)
+                    .height_from(HeightRelative::Parent)
+                    .wants_mouse(column.sortable)
+                    .children(|ui| {
+                        match order {
+                            SortOrder::Ascending => { ui.child("ascending"); },
+                            SortOrder::Descending => { ui.child("descending"); },
+                            SortOrder::None => (),
+                        }
+                    });
+
+                    if column.sortable && result.clicked {
+                        changed = Some((index, order.next()));
+                    }
+                }
+            });
+
+            for row in 0..rows {
+                ui.start("row").children(|ui| {
+                    for (index, column) in columns.iter().enumerate() {
+                        let mut rect = Rect::default();
+                        ui.start("cell")
+                        .size(column.width, 0.0)
+                        .width_from(WidthRelative::Normal)
+                        .height_from(HeightRelative::Parent)
+                        .trigger_layout(&mut rect)
+                        .clip(rect)
+                        .children(|ui| {
+                            (cell_builder)(ui, row, index);
+                        });
+                    }
+                });
+            }
+        });
+
+        let (index, order) = changed?;
+        self.modify(id, |state| {
+            state.selected = index;
+            state.sort_order = order;
+        });
+
+        Some((columns[index].id, order))
+    }
+
+    /**
+    A list of `item_count` items that the user may reorder by dragging.  The `item_builder` closure is called
+    to add children to each `item`, with the item index and whether that item is currently being dragged.
+    While dragging, the dragged item follows the mouse cursor and a `placeholder` is shown where it will be
+    inserted.  The drag state is stored in the [`PersistentState`](struct.PersistentState.html) of the
+    specified `id`.  When the user releases a dragged item at a new position, returns the item's original
+    index and its new index.  Your code is responsible for actually moving the item in your data.
+
+    ```yaml
+    reorderable_list:
+      size_from: [Parent, Children]
+      layout: Vertical
+      layout_spacing: [2, 2]
+      children:
+        item:
+          wants_mouse: true
+          background: gui/small_button
+          size_from: [Parent, Normal]
+          size: [0, 24]
+          border: { all: 5 }
+          layout: Horizontal
+        placeholder:
+          background: gui/frame
+          size_from: [Parent, Normal]
+          size: [0, 24]
+    ```
+
+    # Example
+    ```
+    fn reorder(ui: &mut Frame, names: &mut Vec<String>) {
+        let result = ui.reorderable_list("reorderable_list", "unique_id", names.len(), |ui, index, _| {
+            ui.label("label", &names[index]);
+        });
+
+        if let Some((from, to)) = result {
+            let name = names.remove(from);
+            names.insert(to, name);
+        }
+    }
+    ```
+    */
+    pub fn reorderable_list<F: FnMut(&mut Frame, usize, bool)>(
+        &mut self,
+        theme: &str,
+        id: &str,
+        item_count: usize,
+        mut item_builder: F,
+    ) -> Option<(usize, usize)> {
+        let (mouse_pos, mouse_pressed) = {
+            let context = self.context_internal().borrow();
+            (context.mouse_pos(), context.mouse_pressed(0))
+        };
+
+        let mut output = None;
+        let reorder = self.modify(id, |state| {
+            match state.reorder {
+                Some((from, _)) if from >= item_count => state.reorder = None,
+                Some((from, to)) if !mouse_pressed => {
+                    // the drag was released since the previous frame
+                    if from != to { output = Some((from, to)); }
+                    state.reorder = None;
+                }
+                _ => (),
+            }
+            state.reorder
+        });
+
+        let mut drag_start = None;
+        let mut insert_index = 0;
+
+        let mut list_rect = Rect::default();
+        self.start(theme)
+        .trigger_layout_inner(&mut list_rect)
+        .children(|ui| {
+            let mut slot = 0;
+            let mut placeholder_rect = Rect::default();
+
+            for index in 0..item_count {
+                if let Some((from, to)) = reorder {
+                    if from == index { continue; }
+
+                    if slot == to {
+                        ui.start("placeholder").trigger_layout(&mut placeholder_rect).finish();
+                    }
+                }
+
+                let mut rect = Rect::default();
+                let result = ui.start("item")
+                .trigger_layout(&mut rect)
+                .children(|ui| {
+                    (item_builder)(ui, index, false);
+                });
+
+                if reorder.is_none() && result.pressed && result.moved != Point::default() {
+                    drag_start = Some(index);
+                }
+
+                if rect.pos.y + rect.size.y / 2.0 < mouse_pos.y {
+                    insert_index = slot + 1;
+                }
+
+                slot += 1;
+            }
+
+            let (from, to) = match reorder {
+                None => return,
+                Some(reorder) => reorder,
+            };
+
+            // the placeholder goes at the end of the list
+            if slot == to {
+                ui.start("placeholder").trigger_layout(&mut placeholder_rect).finish();
+            }
+
+            ui.start("item")
+            .screen_pos(list_rect.pos.x, mouse_pos.y - placeholder_rect.size.y / 2.0)
+            .unparent()
+            .unclip()
+            .always_top()
+            .active(true)
+            .children(|ui| {
+                (item_builder)(ui, from, true);
+            });
+        });
+
+        if let Some((from, _)) = reorder {
+            self.modify(id, |state| state.reorder = Some((from, insert_index)));
+        } else if let Some(index) = drag_start {
+            self.modify(id, |state| state.reorder = Some((index, index)));
+        }
+
+        output
+    }
+
+    // TODO menubar
+
+    /**
+    A drop down box. It displays its currently active selection (`current`), and opens a modal popup to select a new
+    choice from the list of `values` when the user clicks on it.  The specified `id` must be unique.
+    The method will return a selected choice on the frame the user clicks on it, otherwise returning `None`.
+
+    An example theme definition;  See [`ScrollpaneBuilder`](struct.ScrollpaneBuilder.html) for the scrollpane example.
+    ```yaml
+    combo_box:
+      from: button
+      children:
+        expand:
+          size: [12, 12]
+          align: Right
+          foreground: gui/arrow_down
+        combo_box_popup:
+          from: scrollpane
+          width_from: Parent
+          height_from: Normal
+          size: [10, 75]
+          background: gui/small_button_normal
+          children:
+            content:
+              size: [-18, -10]
+              children:
+                entry:
+                  from: button
+                  width_from: Parent
+                  size: [0, 25]
+            scrollbar_vertical:
+              size: [20, 20]
+    ```
+    */
+    pub fn combo_box<'a, T: Display>(&mut self, theme: &str, id: &str, current: &T, values: &'a [T]) -> Option<&'a T> {
+        let popup_id = format!("{}_popup", id);
+
+        let mut result = None;
+        let open_result = self.start(theme)
+        .text(current.to_string())
+        .wants_mouse(true)
+        .children(|ui| {
+            ui.child("expand");
+
+            ui.start("combo_box_popup")
+            .id(&popup_id)
+            .initially_open(false)
+            .unclip()
+            .unparent()
+            .new_render_group()
+            .scrollpane(&format!("{}_content", popup_id))
+            .children(|ui| {
+                for value in values {
+                    if ui.button("entry", value.to_string()).clicked {
+                        result = Some(value);
+                        ui.close(&popup_id);
+                    }
+                }
+            });
+
+        });
+        if open_result.clicked {
+            self.open_modal(&popup_id);
+            self.close_modal_on_click_outside();
+        }
+
+        result
+    }
+
+    /**
+    Shows a context menu when the widget with the specified `trigger_id` is right clicked.  This should be
+    called after the trigger widget is created, every frame.  The menu is a popup placed below the trigger
+    widget, with children added by the `builder` closure, typically [`buttons`](#method.button).  The menu
+    closes when the user clicks outside of it, or when `builder` returns `true`, such as when an item is clicked.
+    The trigger widget must [`want the mouse`](struct.WidgetBuilder.html#method.wants_mouse).  The menu uses the
+    `context_menu` theme and the ID `{trigger_id}_context_menu`.
+
+    An example theme definition:
+    ```yaml
+    context_menu:
+      background: gui/small_button_normal
+      border: { all: 5 }
+      layout: Vertical
+      size: [120, 10]
+      height_from: Children
+      children:
+        item:
+          from: button
+          width_from: Parent
+          size: [0, 25]
+    ```
+
+    # Example
+    ```
+    fn file_entry(ui: &mut Frame, name: &str) {
+        ui.start("file").id(name).text(name).wants_mouse(true).finish();
+        ui.context_menu(name, |ui| {
+            if ui.button("item", "Rename").clicked {
+                // rename the file
+                return true;
+            }
+            ui.button("item", "Delete").clicked
+        });
+    }
+    ```
+    */
+    pub fn context_menu<F: FnOnce(&mut Frame) -> bool>(&mut self, trigger_id: &str, builder: F) {
+        let menu_id = format!("{}_context_menu", trigger_id);
+
+        if self.right_clicked_widget() == Some(trigger_id) {
+            self.open_modal(&menu_id);
+            self.close_modal_on_click_outside();
+        } else if !self.is_open(&menu_id) {
+            return;
+        }
+
+        let anchor = match self.widget_bounds(trigger_id) {
+            None => return,
+            Some(anchor) => anchor,
+        };
+
+        let mut close = false;
+        self.start(CONTEXT_MENU_THEME)
+        .id(&menu_id)
+        .initially_open(false)
+        .unclip()
+        .unparent()
+        .new_render_group()
+        .screen_pos(anchor.pos.x, anchor.bot())
+        .children(|ui| {
+            close = builder(ui);
+        });
+
+        if close {
+            self.close(&menu_id);
+        }
+    }
+
+    /**
+    A drop down box with a search field, otherwise similar to [`combo_box`](#method.combo_box).  When the popup
+    is opened, the `search` input field in the popup's sticky `header` gains keyboard focus.  Only `values`
+    whose text contains the search text, ignoring case, are shown.  The search text is stored in the
+    [`PersistentState`](struct.PersistentState.html) of `{id}_search` and is cleared when the popup closes.
+
+    An example theme definition;  See [`ScrollpaneBuilder`](struct.ScrollpaneBuilder.html) for the scrollpane example.
+    ```yaml
+    searchable_combo_box:
+      from: combo_box
+      children:
+        combo_box_popup:
+          size: [10, 105]
+          children:
+            header:
+              size: [-18, 30]
+              pos: [0, 5]
+              children:
+                search:
+                  from: input_field
+                  width_from: Parent
+    ```
+    */
+    pub fn searchable_combo_box<'a, T: Display>(
+        &mut self,
+        theme: &str,
+        id: &str,
+        current: &T,
+        values: &'a [T],
+    ) -> Option<&'a T> {
+        let popup_id = format!("{}_popup", id);
+        let search_id = format!("{}_search", id);
+
+        if !self.is_open(&popup_id) {
+            self.modify(&search_id, |state| {
+                state.text = None;
+                state.characters.clear();
+            });
+        }
+
+        let mut result = None;
+        let open_result = self.start(theme)
+        .text(current.to_string())
+        .wants_mouse(true)
+        .children(|ui| {
+            ui.child("expand");
+
+            ui.start("combo_box_popup")
+            .id(&popup_id)
+            .initially_open(false)
+            .unclip()
+            .unparent()
+            .new_render_group()
+            .scrollpane(&format!("{}_content", popup_id))
+            .sticky_header(|ui| {
+                ui.input_field("search", &search_id, None);
+            })
+            .children(|ui| {
+                let filter = ui.text_for(&search_id).unwrap_or_default().to_lowercase();
+
+                for value in values {
+                    let text = value.to_string();
+                    if !text.to_lowercase().contains(&filter) { continue; }
+
+                    if ui.button("entry", text).clicked {
+                        result = Some(value);
+                        ui.close(&popup_id);
+                    }
+                }
+            });
+
+        });
+        if open_result.clicked {
+            self.open_modal(&popup_id);
+            self.close_modal_on_click_outside();
+            self.focus_keyboard(search_id);
+        }
+
+        result
+    }
+
+    /// A simple toggle button that can be toggle on or off, based on the passed in `active` state.
+    ///
+    /// See [`button`](#method.button) for a YAML example.
+    pub fn toggle_button<T: Into<String>>(&mut self, theme: &str, label: T, active: bool) -> WidgetState {
+        self.start(theme).text(label).active(active).wants_mouse(true).finish()
+    }
+
+    /**
+    A toggle switch with the specified `label`, showing whether `value` is on or off.  The `thumb` slides from
+    the left of the `track` when off to the right when on, and the track and thumb have the `Active`
+    [`AnimState`](struct.AnimState.html) when on.  The slide takes `transition_ms` milliseconds, specified in
+    the theme's `custom_floats`, defaulting to zero.  The thumb is square, sized to the track's inner height.
+    The specified `id` must be unique.  Returns the new value, which differs from `value` on the frame the
+    user clicks the toggle.
+
+    ```yaml
+    toggle:
+      wants_mouse: true
+      size_from: [Parent, Normal]
+      size: [0, 24]
+      layout: Horizontal
+      layout_spacing: [5, 5]
+      child_align: Left
+      custom_floats:
+        transition_ms: 150
+      children:
+        track:
+          background: gui/small_button_black
+          size: [44, 24]
+          border: { all: 2 }
+          children:
+            thumb:
+              background: gui/small_button
+        label:
+          from: label
+          size_from: [Normal, Parent]
+          size: [100, 0]
+    ```
+
+    # Example
+    ```
+    fn options(ui: &mut Frame, fullscreen: &mut bool) {
+        *fullscreen = ui.toggle("toggle", "fullscreen", "Fullscreen", *fullscreen);
+    }
+    ```
+    */
+    pub fn toggle<T: Into<String>>(&mut self, theme: &str, id: &str, label: T, value: bool) -> bool {
+        let transition_ms = self.custom_float(theme, "transition_ms", 0.0);
+        let elapsed = self.cur_time_millis().saturating_sub(self.base_time_millis(id)) as f32;
+        let progress = if transition_ms > 0.0 { (elapsed / transition_ms).min(1.0) } else { 1.0 };
+        let frac = if value { progress } else { 1.0 - progress };
+
+        let result = self.start(theme)
+        .id(id)
+        .wants_mouse(true)
+        .active(value)
+        .children(|ui| {
+            let mut track = Rect::default();
+            ui.start("track")
+            .active(value)
+            .trigger_layout_inner(&mut track)
+            .children(|ui| {
+                let size = track.size.y;
+                ui.start("thumb")
+                .align(Align::TopLeft)
+                .pos(frac * (track.size.x - size), 0.0)
+                .size(size, size)
+                .active(value)
+                .finish();
+            });
+
+            ui.label("label", label);
+        });
+
+        if result.clicked {
+            let time = self.cur_time_millis();
+            self.set_base_time_millis(id, time);
+            !value
+        } else {
+            value
+        }
+    }
+
+    /**
+    Creates a simple text input field.  The `id` that is passed in must be unique.
+    The text input will grab keyboard focus when the user clicks on it, allowing
+    the user to type text.  The return value will be `None` if the text didn't change
+    this frame, or will contain the current text displayed by the textbox if it did
+    change.  Optionally, pass an initial_value which will set the field's text if it
+    is not already set.
+
+    An example YAML theme definition:
+    ```yaml
+    input_field:
+      font: small
+      border: { height: 4, width: 5 }
+      background: gui/input_field
+      text_align: Left
       wants_mouse: true
       size: [150, 24]
       child_align: TopLeft
@@ -400,8 +1417,22 @@ impl Frame {
           size: [2, -2]
           height_from: Parent
           background: gui/caret
+        preedit:
+          font: small
+          height_from: Parent
+          text_align: Left
+          children:
+            underline:
+              align: BotLeft
+              width_from: Parent
+              size: [0, 1]
+              background: gui/caret_on
     ```
 
+    While text is being composed with an input method editor (see
+    [`Context::set_ime_preedit`](struct.Context.html#method.set_ime_preedit)), the composition text is
+    shown after the field's text using the `preedit` child, with the caret placed at the composition cursor.
+
     # Example
     ```
     fn select_name(ui: &mut Frame, name: &mut String) {
@@ -415,29 +1446,112 @@ impl Frame {
         let mut text_out = None;
 
         self.modify(id, |state| {
-            if state.text.is_none() {
-                state.text = Some(initial_value.unwrap_or_default());
-            }
+            if state.text.is_none() {
+                state.text = Some(initial_value.unwrap_or_default());
+            }
+
+            let mut text_changed = false;
+            for c in state.characters.drain(..) {
+                if c as u32 == 8 { //backspace
+                    state.text.as_mut().unwrap().pop();
+                    text_changed = true;
+                } else {
+                    state.text.as_mut().unwrap().push(c);
+                    text_changed = true;
+                }
+            }
+
+            if text_changed {
+                text_out = state.text.clone();
+            }
+        });
+        let mut text_pos = Point::default();
+
+        let preedit = self.ime_preedit();
+
+        self.start(theme)
+        .id(id)
+        .wants_keyboard(true)
+        .trigger_text_layout(&mut text_pos)
+        .children(|ui| {
+            if !ui.is_focus_keyboard(id) { return; }
+
+            match preedit {
+                None => {
+                    ui.start("caret").pos(text_pos.x, text_pos.y).finish();
+                },
+                Some((text, cursor)) => {
+                    // measure with the font the preedit text is actually drawn with
+                    let preedit_theme = format!("{}/preedit", theme);
+                    let width = ui.text_width(&preedit_theme, &text);
+                    let caret_x = cursor.and_then(|(start, _)| text.get(..start))
+                        .map(|before| ui.text_width(&preedit_theme, before));
+
+                    ui.start("preedit")
+                    .text(text)
+                    .pos(text_pos.x, text_pos.y)
+                    .size(width, 0.0)
+                    .children(|ui| {
+                        ui.start("underline").finish();
+                    });
+
+                    if let Some(caret_x) = caret_x {
+                        ui.start("caret").pos(text_pos.x + caret_x, text_pos.y).finish();
+                    }
+                }
+            }
+        });
+
+        text_out
+    }
+
+    /**
+    Creates a text input field that only accepts digits, formatted according to the specified `mask`.  Each
+    `#` character in the `mask` is a digit slot, while all other characters are literals that are displayed
+    and automatically skipped over as the user types.  For example, `"##/##/####"` could be used for a date.
+    Non digit characters are ignored.  The displayed text is stored in the [`PersistentState`](struct.PersistentState.html)
+    of the specified `id`, which must be unique.  Returns the raw digits, without any literals, on any
+    frame they change.
+
+    The theme is the same as for [`input_field`](#method.input_field).
+
+    # Example
+    ```
+    fn phone_number(ui: &mut Frame, number: &mut String) {
+        if let Some(digits) = ui.masked_input("input_field", "phone_number", "(###) ###-####") {
+            *number = digits;
+        }
+    }
+    ```
+    */
+    pub fn masked_input(&mut self, theme: &str, id: &str, mask: &str) -> Option<String> {
+        let slots = mask.chars().filter(|c| *c == '#').count();
+        let mut raw_out = None;
+
+        self.modify(id, |state| {
+            let mut raw = unmask(mask, state.text.as_deref().unwrap_or_default());
 
-            let mut text_changed = false;
+            let mut raw_changed = false;
             for c in state.characters.drain(..) {
                 if c as u32 == 8 { //backspace
-                    state.text.as_mut().unwrap().pop();
-                    text_changed = true;
-                } else {
-                    state.text.as_mut().unwrap().push(c);
-                    text_changed = true;
+                    raw_changed |= raw.pop().is_some();
+                } else if c.is_ascii_digit() && raw.len() < slots {
+                    raw.push(c);
+                    raw_changed = true;
                 }
             }
 
-            if text_changed {
-                text_out = state.text.clone();
+            state.text = Some(apply_mask(mask, &raw));
+
+            if raw_changed {
+                raw_out = Some(raw);
             }
         });
         let mut text_pos = Point::default();
 
-        let result = self.start(theme)
+        self.start(theme)
         .id(id)
+        .wants_keyboard(true)
         .trigger_text_layout(&mut text_pos)
         .children(|ui| {
             if ui.is_focus_keyboard(id) {
@@ -445,9 +1559,122 @@ impl Frame {
             }
         });
 
-        if result.clicked {
-            self.focus_keyboard(id);
+        raw_out
+    }
+
+    /**
+    Creates a text input field with a drop down list of `suggestions`.  This otherwise behaves like
+    [`input_field`](#method.input_field), with the text stored in the [`PersistentState`](struct.PersistentState.html)
+    of the specified `id`.  While the field has keyboard focus, `suggestions` containing the current text, ignoring
+    case, are shown below it.  The highlighted suggestion is moved with the `Up` and `Down` keys, and is completed
+    by pressing `Enter`.  Clicking on a suggestion also completes it.  If specified, the `hint` text is shown when the
+    field is empty.  Returns the new text on any frame it changes.  Since the suggestions are passed in each frame,
+    they may be updated based on [`current_input`](#method.current_input).
+
+    ```yaml
+    autocomplete:
+      size: [150, 24]
+      children:
+        input:
+          from: input_field
+          align: TopLeft
+        hint:
+          from: label
+          align: TopLeft
+          text_color: "#888"
+          border: { height: 4, width: 5 }
+          size_from: [Parent, Parent]
+        suggestions:
+          background: gui/small_button_normal
+          pos: [0, 24]
+          align: TopLeft
+          width_from: Parent
+          height_from: Children
+          layout: Vertical
+          children:
+            suggestion:
+              from: button
+              width_from: Parent
+    ```
+
+    # Example
+    ```
+    fn pick_city(ui: &mut Frame, cities: &[&str]) {
+        if let Some(city) = ui.autocomplete("autocomplete", "city", Some("City"), cities) {
+            println!("City is now {}", city);
         }
+    }
+    ```
+    */
+    pub fn autocomplete(&mut self, theme: &str, id: &str, hint: Option<&str>, suggestions: &[&str]) -> Option<String> {
+        let list_id = format!("{}_suggestions", id);
+
+        // the enter key completes a suggestion rather than being added to the text
+        self.modify(id, |state| {
+            state.characters.retain(|c| *c as u32 == 8 || !c.is_control());
+        });
+
+        let mut text_out = None;
+        self.start(theme).children(|ui| {
+            if let Some(text) = ui.input_field("input", id, None) {
+                text_out = Some(text);
+            }
+
+            let input = ui.current_input(id);
+            if let Some(hint) = hint {
+                if input.is_empty() {
+                    ui.label("hint", hint);
+                }
+            }
+
+            let input = input.to_lowercase();
+            let matches: Vec<&str> = suggestions.iter().copied().filter(|suggestion| {
+                let suggestion = suggestion.to_lowercase();
+                !input.is_empty() && suggestion != input && suggestion.contains(&input)
+            }).collect();
+
+            // the list stays visible on the frame the mouse is released, which also clears keyboard focus
+            let focused = ui.is_focus_keyboard(id);
+            let show = !matches.is_empty() && (focused || ui.is_open(&list_id));
+            if focused != ui.is_open(&list_id) {
+                ui.modify(&list_id, |state| state.is_open = focused);
+            }
+            if !show { return; }
+
+            let mut selected = ui.selected(id).min(matches.len() - 1);
+            if ui.key_pressed(KeyCode::Down) {
+                selected = (selected + 1).min(matches.len() - 1);
+            }
+            if ui.key_pressed(KeyCode::Up) {
+                selected = selected.saturating_sub(1);
+            }
+
+            let mut completed = None;
+            if ui.key_pressed(KeyCode::Return) || ui.key_pressed(KeyCode::NumpadEnter) {
+                completed = Some(matches[selected]);
+            }
+
+            ui.start("suggestions")
+            .unclip()
+            .unparent()
+            .z_index(i32::MAX)
+            .children(|ui| {
+                for (index, suggestion) in matches.iter().enumerate() {
+                    if ui.toggle_button("suggestion", *suggestion, index == selected).clicked {
+                        completed = Some(suggestion);
+                    }
+                }
+            });
+
+            ui.set_selected(id, selected);
+
+            if let Some(completed) = completed {
+                ui.modify(id, |state| state.text = Some(completed.to_string()));
+                ui.set_selected(id, 0);
+                ui.focus_keyboard(id);
+                text_out = Some(completed.to_string());
+            }
+        });
 
         text_out
     }
@@ -495,7 +1722,50 @@ impl Frame {
     ```
     **/
     pub fn tooltip<T: Into<String>>(&mut self, theme: &str, label: T) {
+        self.tooltip_placed(theme, label, TooltipPlacement::Cursor);
+    }
+
+    /**
+    Creates a tooltip with the specified text, but only once the mouse has been stationary
+    for at least `delay_ms` milliseconds.  This is intended to be called each frame while the
+    associated widget is hovered.  Moving the mouse, or not calling this method for a frame,
+    restarts the delay.  The tooltip is placed based on the position of the mouse, as in
+    [`tooltip`](#method.tooltip).
+
+    # Example
+    ```
+    fn create_tooltip(ui: &mut Frame) {
+        if ui.button("button", "Save").hovered {
+            ui.tooltip_delayed("tooltip", "Saves the current file", 500);
+        }
+    }
+    ```
+    **/
+    pub fn tooltip_delayed<T: Into<String>>(&mut self, theme: &str, label: T, delay_ms: u32) {
+        let elapsed = self.context_internal().borrow_mut().tooltip_hover_millis();
+        if elapsed < delay_ms { return; }
+
+        self.tooltip_placed(theme, label, TooltipPlacement::Cursor);
+    }
+
+    /**
+    Creates a tooltip with the specified text, positioned according to `placement`.  Other than
+    [`TooltipPlacement::Cursor`](enum.TooltipPlacement.html), placements are relative to the
+    most recently finished widget, which is typically the widget that is being hovered.  The
+    tooltip is kept within the bounds of the display.
+
+    # Example
+    ```
+    fn create_tooltip(ui: &mut Frame) {
+        if ui.button("button", "Open").hovered {
+            ui.tooltip_placed("tooltip", "Opens a file", TooltipPlacement::Below);
+        }
+    }
+    ```
+    **/
+    pub fn tooltip_placed<T: Into<String>>(&mut self, theme: &str, label: T, placement: TooltipPlacement) {
         let mouse = self.mouse_rect();
+        let anchor = self.last_widget_bounds();
         let display_size = self.display_size();
 
         let mut rect = Rect::default();
@@ -507,10 +1777,284 @@ impl Frame {
         .always_top()
         .trigger_layout(&mut rect);
 
-        let x = mouse.right().min(display_size.x - rect.size.x);
-        let y = mouse.bot().min(display_size.y - rect.size.y);
+        let pos = tooltip_pos(placement, rect.size, mouse, anchor, display_size);
+        builder.screen_pos(pos.x, pos.y).finish();
+    }
+
+    /**
+    Creates a tooltip showing only the image with the specified `image_id`, such as a large preview of an
+    item icon.  The image is used as the tooltip's background, and the tooltip is sized to the image's
+    base size.  The tooltip is placed based on the position of the mouse, as in [`tooltip`](#method.tooltip).
+
+    An example YAML theme definition:
+    ```yaml
+    tooltip_image:
+      size_from: [Normal, Normal]
+    ```
+
+    # Example
+    ```
+    fn create_item(ui: &mut Frame) {
+        if ui.button("item_button", "Sword").hovered {
+            ui.tooltip_image("tooltip_image", "items/sword_large");
+        }
+    }
+    ```
+    **/
+    pub fn tooltip_image(&mut self, theme: &str, image_id: &str) {
+        let size = self.context().image_base_size(image_id).unwrap_or_default();
+        let mouse = self.mouse_rect();
+        let anchor = self.last_widget_bounds();
+        let display_size = self.display_size();
+
+        let pos = tooltip_pos(TooltipPlacement::Cursor, size, mouse, anchor, display_size);
+
+        self.start(theme)
+        .unclip()
+        .unparent()
+        .always_top()
+        .background(image_id)
+        .width_from(WidthRelative::Normal)
+        .height_from(HeightRelative::Normal)
+        .size(size.x, size.y)
+        .screen_pos(pos.x, pos.y)
+        .finish();
+    }
+
+    /**
+    Creates a tooltip with arbitrary content, added by the `children` closure.  The tooltip is placed
+    based on the position of the mouse, as in [`tooltip`](#method.tooltip), using the size of the tooltip
+    as specified in the theme.
+
+    # Example
+    ```
+    fn create_item(ui: &mut Frame) {
+        if ui.button("item_button", "Sword").hovered {
+            ui.tooltip_custom("tooltip", |ui| {
+                ui.label("label", "Sword");
+                ui.label("label", "Damage: 5");
+            });
+        }
+    }
+    ```
+    **/
+    pub fn tooltip_custom<F: FnOnce(&mut Frame)>(&mut self, theme: &str, children: F) {
+        let mouse = self.mouse_rect();
+        let anchor = self.last_widget_bounds();
+        let display_size = self.display_size();
+
+        let mut rect = Rect::default();
+
+        let builder = self.start(theme)
+        .unclip()
+        .unparent()
+        .always_top()
+        .trigger_layout(&mut rect);
+
+        let pos = tooltip_pos(TooltipPlacement::Cursor, rect.size, mouse, anchor, display_size);
+        builder.screen_pos(pos.x, pos.y).children(children);
+    }
+
+    /**
+    Queues a toast notification showing the specified `message`.  The toast is shown
+    for `duration_ms` milliseconds, starting from the current frame, and then automatically
+    dismissed.  Toasts are drawn on top of all other widgets at the end of the frame, so this
+    may be called from anywhere in your UI code.  Multiple toasts stack vertically.
+
+    Toasts are created as `toast` children of the `toast_anchor` widget, which spans the entire
+    screen.  The `child_align` of `toast_anchor` determines which corner the toasts appear in.
+    Each toast's base time (see [`set_base_time_millis`](#method.set_base_time_millis)) is set to the time
+    it was shown, so `Timed` images in the toast theme may be used for a slide in effect.  For the last
+    `fade_out_millis` (a custom float on `toast_anchor`) of its life, a toast has the `Active`
+    [`AnimState`](struct.AnimState.html), which can be used for a fade out effect.
+
+    An example YAML theme definition:
+    ```yaml
+    toast_anchor:
+      size_from: [Parent, Parent]
+      border: { all: 10 }
+      layout: Vertical
+      layout_spacing: [5, 5]
+      child_align: TopRight
+      custom_floats:
+        fade_out_millis: 500
+      children:
+        toast:
+          background: gui/toast
+          font: small
+          text_align: Center
+          size: [200, 30]
+          border: { all: 5 }
+    ```
+
+    # Example
+    ```
+    fn save_game(ui: &mut Frame) {
+        // save the game here
+        ui.show_toast("Game saved", 3000);
+    }
+    ```
+    */
+    pub fn show_toast<T: Into<String>>(&mut self, message: T, duration_ms: u32) {
+        let mut context = self.context_internal().borrow_mut();
+        context.push_toast(message.into(), duration_ms);
+    }
+
+    pub(crate) fn build_toasts(&mut self) {
+        let (toasts, time) = {
+            let mut context = self.context_internal().borrow_mut();
+            let time = context.time_millis();
+            let toasts: Vec<_> = context.active_toasts().iter().map(|toast| {
+                (toast.id, toast.message.clone(), toast.start_millis, toast.start_millis.saturating_add(toast.duration_millis))
+            }).collect();
+
+            if toasts.is_empty() { return; }
+
+            if context.themes().theme("toast_anchor").is_none() {
+                context.log(log::Level::Error, "Unable to show toasts without a 'toast_anchor' theme".to_string());
+                return;
+            }
+
+            (toasts, time)
+        };
+
+        let fade_out = self.custom_float("toast_anchor", "fade_out_millis", 0.0) as u32;
+
+        self.start("toast_anchor")
+        .id(TOASTS_ID)
+        .screen_pos(0.0, 0.0)
+        .unclip()
+        .unparent()
+        .children(|ui| {
+            for (toast_id, message, start, end) in toasts {
+                let id = format!("{}/{}", TOASTS_ID, toast_id);
+                ui.set_base_time_millis(&id, start);
+
+                ui.start("toast")
+                .id(id)
+                .text(message)
+                .active(end - time <= fade_out)
+                .always_top()
+                .finish();
+            }
+        });
+    }
+
+    /**
+    Creates a widget with the specified `theme` and `id` that may be dragged and dropped onto a
+    [`drop_target`](#method.drop_target).  When the user presses the mouse on this widget and moves it, a
+    drag begins, carrying the specified `payload`.  The `payload` is only used on the frame the drag
+    begins.  While dragging, a copy of this widget's theme follows the mouse cursor, and the source widget
+    has the `Active` [`AnimState`](struct.AnimState.html).  If the mouse is released anywhere other than a
+    drop target, the drag is cancelled.
+
+    An example YAML theme definition:
+    ```yaml
+    inventory_item:
+      background: gui/button
+      wants_mouse: true
+      size: [32, 32]
+    ```
+
+    # Example
+    ```
+    fn inventory(ui: &mut Frame, items: &[u32]) {
+        for item in items {
+            ui.begin_drag_source("inventory_item", &format!("item_{}", item), *item);
+        }
+
+        if let Some(payload) = ui.drop_target("drop_slot", "trash_can") {
+            if let Ok(item) = payload.downcast::<u32>() {
+                println!("Discarded item {}", item);
+            }
+        }
+    }
+    ```
+    */
+    pub fn begin_drag_source<T: Any>(&mut self, theme: &str, id: &str, payload: T) -> DragSourceResult {
+        let mut rect = Rect::default();
+        let builder = self.start(theme).id(id).wants_mouse(true).trigger_layout(&mut rect);
+        let id = builder.widget.id().to_string();
+        let theme_id = builder.widget.theme_id().to_string();
+
+        let mut dragging = {
+            let context = builder.frame.context_internal().borrow();
+            match context.drag() {
+                None => false,
+                Some(drag) => drag.source_id == id,
+            }
+        };
+
+        let state = builder.active(dragging).finish();
+
+        if !dragging && state.pressed && state.moved != Point::default() {
+            let mut context = self.context_internal().borrow_mut();
+            let offset = context.mouse_pos() - rect.pos;
+            context.begin_drag(DragState {
+                source_id: id,
+                theme_id,
+                payload: Box::new(payload),
+                size: rect.size,
+                offset,
+            });
+            dragging = true;
+        }
+
+        DragSourceResult { clicked: state.clicked && !dragging, dragging }
+    }
+
+    /**
+    Creates a widget with the specified `theme` and `id` that accepts payloads from
+    [`begin_drag_source`](#method.begin_drag_source).  When a drag is released over this widget, returns
+    the dragged payload, which may be downcast to its original type.  Returns `None` otherwise.  While a
+    drag is hovering over it, this widget has the `Active` [`AnimState`](struct.AnimState.html).
+
+    An example YAML theme definition:
+    ```yaml
+    drop_slot:
+      background: gui/frame
+      size: [48, 48]
+    ```
+    */
+    pub fn drop_target(&mut self, theme: &str, id: &str) -> Option<Box<dyn Any>> {
+        let mut rect = Rect::default();
+        let builder = self.start(theme).id(id).trigger_layout(&mut rect);
+
+        let (drag_over, released) = {
+            let context = builder.frame.context_internal().borrow();
+            let drag_over = context.drag().is_some() && rect.is_inside(context.mouse_pos());
+            (drag_over, !context.mouse_pressed(0))
+        };
 
-        builder.screen_pos(x, y).finish();
+        builder.active(drag_over).finish();
+
+        if drag_over && released {
+            let mut context = self.context_internal().borrow_mut();
+            context.take_drag().map(|drag| drag.payload)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn build_drag_ghost(&mut self) {
+        let (theme_id, size, pos) = {
+            let context = self.context_internal().borrow();
+            match context.drag() {
+                None => return,
+                Some(drag) => (drag.theme_id.to_string(), drag.size, context.mouse_pos() - drag.offset),
+            }
+        };
+
+        self.start(&theme_id)
+        .id(DRAG_GHOST_ID)
+        .size_from(WidthRelative::Normal, HeightRelative::Normal)
+        .size(size.x, size.y)
+        .screen_pos(pos.x, pos.y)
+        .wants_mouse(false)
+        .unclip()
+        .unparent()
+        .always_top()
+        .finish();
     }
 
     /**
@@ -552,4 +2096,201 @@ impl Frame {
     pub fn scrollpane<F: FnOnce(&mut Frame)>(&mut self, theme: &str, content_id: &str, children: F) {
         self.start(theme).scrollpane(content_id).children(children);
     }
-}
\ No newline at end of file
+
+    /**
+    Shows a floating window listing every visible widget drawn in the previous frame, if `enabled`
+    is true.  Clicking on a widget in the list shows the fields of its resolved theme, and
+    outlines the widget on screen using the same mechanism as the
+    [`debug overlay`](#method.enable_debug_overlay).  The selection is kept between frames.
+    This should be called each frame, after the rest of your UI, so that the inspector draws on top.
+
+    The inspector uses the `widget_inspector` theme, which must be present.  An example theme definition:
+    ```yaml
+    widget_inspector:
+      from: window
+      size: [400, 500]
+      children:
+        titlebar:
+          children:
+            title:
+              text: "Widget Inspector"
+        widget_list:
+          from: scrollpane_vertical
+          height_from: Normal
+          size: [0, 200]
+          children:
+            content:
+              children:
+                entry:
+                  from: button
+                  width_from: Parent
+                  size: [0, 20]
+                  text_align: Left
+                  truncate: true
+        theme_fields:
+          from: scrollpane_vertical
+          size: [0, -210]
+          children:
+            content:
+              children:
+                field:
+                  from: label
+                  text_align: Left
+    ```
+
+    # Example
+    ```
+    fn build_ui(ui: &mut Frame, show_inspector: bool) {
+        ui.label("label", "Hello");
+        ui.show_widget_inspector(show_inspector);
+    }
+    ```
+    */
+    pub fn show_widget_inspector(&mut self, enabled: bool) {
+        if !enabled { return; }
+        self.set_inspector_shown();
+
+        let (widgets, mut selected) = {
+            let context = self.context_internal().borrow();
+            if context.themes().theme(WIDGET_INSPECTOR_ID).is_none() {
+                drop(context);
+                self.context_internal().borrow_mut().log(
+                    log::Level::Error,
+                    format!("Unable to show the widget inspector without a '{}' theme", WIDGET_INSPECTOR_ID),
+                );
+                return;
+            }
+
+            // don't list the inspector's own widgets
+            let prefix = format!("{}/", WIDGET_INSPECTOR_ID);
+            let widgets: Vec<(String, String)> = context.last_frame_widgets().iter()
+                .filter(|(id, _)| id != WIDGET_INSPECTOR_ID && !id.starts_with(&prefix))
+                .cloned()
+                .collect();
+            (widgets, context.inspected_widget().map(|id| id.to_string()))
+        };
+
+        let fields: Vec<String> = {
+            let context = self.context_internal().borrow();
+            let theme_id = selected.as_ref()
+                .and_then(|selected| widgets.iter().find(|(id, _)| id == selected))
+                .map(|(_, theme_id)| theme_id.as_str());
+
+            match theme_id.and_then(|id| context.themes().theme_definition(id).map(|def| (id, def))) {
+                None => Vec::new(),
+                Some((id, definition)) => {
+                    let yaml = serde_yaml::to_string(&definition).unwrap_or_default();
+                    std::iter::once(format!("theme: {}", id))
+                        .chain(yaml.lines()
+                            .filter(|line| *line != "---" && !line.ends_with(": ~") && !line.ends_with(": {}"))
+                            .map(|line| line.to_string()))
+                        .collect()
+                }
+            }
+        };
+
+        self.window(WIDGET_INSPECTOR_ID, |ui| {
+            ui.scrollpane("widget_list", &format!("{}/list_content", WIDGET_INSPECTOR_ID), |ui| {
+                for (id, _) in &widgets {
+                    let is_selected = selected.as_deref() == Some(id.as_str());
+                    if ui.start("entry").text(id).active(is_selected).finish().clicked {
+                        selected = if is_selected { None } else { Some(id.to_string()) };
+                    }
+                }
+            });
+
+            ui.scrollpane("theme_fields", &format!("{}/fields_content", WIDGET_INSPECTOR_ID), |ui| {
+                for field in fields {
+                    ui.label("field", field);
+                }
+            });
+        });
+
+        self.context_internal().borrow_mut().set_inspected_widget(selected.clone());
+        self.set_debug_highlight(selected);
+    }
+
+    /// A convenience method to create a split pane with the specified `theme` and `id`, which must be unique.
+    /// The two panes are placed side by side, with the `left` pane initially given `fraction` of the available
+    /// width.  See [`SplitPaneBuilder`](struct.SplitPaneBuilder.html) for more details and more flexible
+    /// split pane creation.
+    pub fn hsplit<F: FnOnce(&mut Frame), G: FnOnce(&mut Frame)>(
+        &mut self,
+        theme: &str,
+        id: &str,
+        fraction: f32,
+        left: F,
+        right: G,
+    ) {
+        self.start(theme).split_pane(id, SplitDirection::Horizontal, fraction).children(left, right);
+    }
+
+    /// A convenience method to create a split pane with the specified `theme` and `id`, which must be unique.
+    /// The two panes are placed one above the other, with the `top` pane initially given `fraction` of the
+    /// available height.  See [`SplitPaneBuilder`](struct.SplitPaneBuilder.html) for more details and more
+    /// flexible split pane creation.
+    pub fn vsplit<F: FnOnce(&mut Frame), G: FnOnce(&mut Frame)>(
+        &mut self,
+        theme: &str,
+        id: &str,
+        fraction: f32,
+        top: F,
+        bot: G,
+    ) {
+        self.start(theme).split_pane(id, SplitDirection::Vertical, fraction).children(top, bot);
+    }
+}
+
+/// Computes the position of a tooltip of the specified `size`, relative to the `mouse` or the `anchor`
+/// widget depending on `placement`, and kept within the display.
+fn tooltip_pos(placement: TooltipPlacement, size: Point, mouse: Rect, anchor: Rect, display_size: Point) -> Point {
+    let center_x = anchor.pos.x + (anchor.size.x - size.x) / 2.0;
+    let center_y = anchor.pos.y + (anchor.size.y - size.y) / 2.0;
+
+    use TooltipPlacement::*;
+    let (x, y) = match placement {
+        Above => (center_x, anchor.pos.y - size.y),
+        Below => (center_x, anchor.bot()),
+        Left => (anchor.pos.x - size.x, center_y),
+        Right => (anchor.right(), center_y),
+        Cursor => (mouse.right(), mouse.bot()),
+    };
+
+    Point {
+        x: x.min(display_size.x - size.x).max(0.0),
+        y: y.min(display_size.y - size.y).max(0.0),
+    }
+}
+
+/// Adds `count` evenly spaced horizontal `grid_line` children to a chart plot of the specified `size`.
+fn chart_grid(ui: &mut Frame, count: usize, size: Point) {
+    for index in 0..count {
+        let y = size.y * (index + 1) as f32 / (count + 1) as f32;
+        ui.start("grid_line").align(Align::TopLeft).pos(0.0, y).finish();
+    }
+}
+
+/// Formats the `raw` digits according to `mask`, where each `#` is a digit slot.  Literals
+/// are included up until the first unfilled digit slot.
+fn apply_mask(mask: &str, raw: &str) -> String {
+    let mut digits = raw.chars();
+    let mut output = String::new();
+
+    for c in mask.chars() {
+        if c == '#' {
+            match digits.next() {
+                None => break,
+                Some(digit) => output.push(digit),
+            }
+        } else {
+            output.push(c);
+        }
+    }
+
+    output
+}
+
+/// Extracts the digits in the slots of `mask` from the formatted `text`.
+fn unmask(mask: &str, text: &str) -> String {
+    mask.chars().zip(text.chars()).filter(|(m, _)| *m == '#').map(|(_, c)| c).collect()
+}
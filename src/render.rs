@@ -2,7 +2,7 @@ use std::num::NonZeroU16;
 
 use crate::{Color, Rect, Point, Error};
 use crate::font::{FontSource, Font};
-use crate::theme_definition::CharacterRange;
+use crate::theme_definition::{CharacterRange, GradientDirection};
 
 /// A trait to be implemented on the type to be used for Event handling.  See [`WinitIO`](struct.WinitIO.html)
 /// for an example implementation.  The IO handles events from an external source and passes them to the Thyme
@@ -19,6 +19,8 @@ pub trait IO {
 /// for an example implementation.  The `Renderer` takes a completed frame and renders the widget tree stored within it.
 pub trait Renderer {
     /// Register a font with Thyme.  This method is called via the [`ContextBuilder`](struct.ContextBuilder.html).
+    /// Each style of a font source is registered separately, with its [`FontStyle`](enum.FontStyle.html)
+    /// available via `source.style()`, so renderers produce one glyph atlas per style.
     fn register_font(
         &mut self,
         handle: FontHandle,
@@ -28,6 +30,24 @@ pub trait Renderer {
         scale: f32,
     ) -> Result<Font, Error>;
 
+    /// Register a font with Thyme, rendered as a signed distance field rather than glyph coverage.  This method is
+    /// called via the [`ContextBuilder`](struct.ContextBuilder.html) for fonts with `sdf` set in the theme.
+    /// Glyphs should be drawn using `smoothness` as the half width of the transition around the glyph edge,
+    /// in units of the distance field.  Renderers that do not support distance field fonts may register a regular
+    /// font instead, which is the default.
+    #[allow(clippy::too_many_arguments)]
+    fn register_sdf_font(
+        &mut self,
+        handle: FontHandle,
+        source: &FontSource,
+        ranges: &[CharacterRange],
+        size: f32,
+        scale: f32,
+        _smoothness: f32,
+    ) -> Result<Font, Error> {
+        self.register_font(handle, source, ranges, size, scale)
+    }
+
     /// Register a texture with Thyme.  This method is called via the [`ContextBuilder`](struct.ContextBuilder.html).
     fn register_texture(
         &mut self,
@@ -35,6 +55,60 @@ pub trait Renderer {
         image_data: &[u8],
         dimensions: (u32, u32),
     ) -> Result<TextureData, Error>;
+
+    /// Sets whether textures registered after this call should have mipmaps generated.  This method is called
+    /// via the [`ContextBuilder`](struct.ContextBuilder.html).  Renderers that do not support mipmapping
+    /// may ignore it, which is the default.
+    fn set_texture_mipmaps(&mut self, _enabled: bool) {}
+
+    /// Replaces a region of a texture previously registered with [`register_texture`](#method.register_texture).
+    /// `data` is in the same RGBA format, covering the region of `size` in texels starting at `pos`.
+    /// This method is called via [`Context::update_image_region`](struct.Context.html#method.update_image_region),
+    /// which validates the region.  Renderers that do not support updating textures return an error, which is
+    /// the default.
+    fn update_texture_region(
+        &mut self,
+        _handle: TextureHandle,
+        _data: &[u8],
+        _pos: (u32, u32),
+        _size: (u32, u32),
+    ) -> Result<(), Error> {
+        Err(Error::Theme("This renderer does not support updating textures".to_string()))
+    }
+
+    /// Draws the outline of `rect`, in physical pixels, with lines of the specified `thickness` and a solid `color`.
+    /// This is used to draw the debug overlay, see [`Frame::enable_debug_overlay`](struct.Frame.html#method.enable_debug_overlay),
+    /// after the normal draw pass.  Renderers that cannot draw solid colors may ignore it, which is the default.
+    fn draw_rect_outline(&mut self, _rect: Rect, _thickness: f32, _color: Color) {}
+}
+
+/// The outline colors used by the debug overlay, cycling with widget depth
+const DEBUG_OVERLAY_COLORS: [Color; 5] = [
+    Color { r: 255, g: 0, b: 0 },
+    Color { r: 255, g: 255, b: 0 },
+    Color { r: 0, g: 255, b: 0 },
+    Color { r: 0, g: 255, b: 255 },
+    Color { r: 255, g: 0, b: 255 },
+];
+
+/// The outline color of the widget selected in the widget inspector
+pub(crate) const DEBUG_HIGHLIGHT_COLOR: Color = Color { r: 255, g: 128, b: 0 };
+
+/// Returns the debug overlay color for a widget with the specified full `id`, based on its depth in the tree
+pub(crate) fn debug_overlay_color(id: &str) -> Color {
+    let depth = id.matches('/').count();
+    DEBUG_OVERLAY_COLORS[depth % DEBUG_OVERLAY_COLORS.len()]
+}
+
+/// Returns the position and size of the four edges of the outline of `rect`
+pub(crate) fn outline_edges(rect: Rect, thickness: f32) -> [([f32; 2], [f32; 2]); 4] {
+    let (pos, size) = (rect.pos, rect.size);
+    [
+        ([pos.x, pos.y], [size.x, thickness]),
+        ([pos.x, pos.y + size.y - thickness], [size.x, thickness]),
+        ([pos.x, pos.y], [thickness, size.y]),
+        ([pos.x + size.x - thickness, pos.y], [thickness, size.y]),
+    ]
 }
 
 pub(crate) fn view_matrix(display_pos: Point, display_size: Point) -> [[f32; 4]; 4] {
@@ -52,9 +126,12 @@ pub(crate) fn view_matrix(display_pos: Point, display_size: Point) -> [[f32; 4];
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub enum DrawMode {
+pub(crate) enum DrawMode {
     Image(TextureHandle),
     Font(FontHandle),
+
+    /// solid colors, drawn with a plain white texture
+    Solid,
 }
 
 pub trait DrawList {
@@ -67,6 +144,39 @@ pub trait DrawList {
         clip: Rect,
     );
 
+    /// push a rect blending from `colors[0]` to `colors[1]` in the specified `direction`, drawn with
+    /// the specified `alpha`.  Draw lists that do not support per-vertex colors fall back to a solid rect
+    #[allow(clippy::too_many_arguments)]
+    fn push_gradient_rect(
+        &mut self,
+        pos: [f32; 2],
+        size: [f32; 2],
+        tex: [TexCoord; 2],
+        colors: [Color; 2],
+        _direction: GradientDirection,
+        alpha: f32,
+        clip: Rect,
+    ) {
+        self.push_rect_alpha(pos, size, tex, colors[0], alpha, clip);
+    }
+
+    /// push a rect drawn with the specified `alpha`, from 0.0 (transparent) to 1.0 (opaque).
+    /// Draw lists that do not support alpha fall back to drawing the rect only if it is
+    /// at least half opaque
+    fn push_rect_alpha(
+        &mut self,
+        pos: [f32; 2],
+        size: [f32; 2],
+        tex: [TexCoord; 2],
+        color: Color,
+        alpha: f32,
+        clip: Rect,
+    ) {
+        if alpha >= 0.5 {
+            self.push_rect(pos, size, tex, color, clip);
+        }
+    }
+
     /// the number of vertices currently contained in this list
     fn len(&self) -> usize;
 
@@ -75,6 +185,69 @@ pub trait DrawList {
     fn back_adjust_positions(&mut self, since_index: usize, amount: Point);
 }
 
+/// A DrawList wrapping another, drawing everything pushed to it multiplied by the
+/// specified `tint` color and `alpha`.
+pub(crate) struct ModulatedDrawList<'a> {
+    inner: &'a mut dyn DrawList,
+    tint: Color,
+    alpha: f32,
+}
+
+impl<'a> ModulatedDrawList<'a> {
+    pub fn alpha(inner: &'a mut dyn DrawList, alpha: f32) -> ModulatedDrawList<'a> {
+        ModulatedDrawList { inner, tint: Color::white(), alpha }
+    }
+
+    pub fn tint(inner: &'a mut dyn DrawList, tint: Color) -> ModulatedDrawList<'a> {
+        ModulatedDrawList { inner, tint, alpha: 1.0 }
+    }
+}
+
+impl<'a> DrawList for ModulatedDrawList<'a> {
+    fn push_rect(
+        &mut self,
+        pos: [f32; 2],
+        size: [f32; 2],
+        tex: [TexCoord; 2],
+        color: Color,
+        clip: Rect,
+    ) {
+        self.inner.push_rect_alpha(pos, size, tex, color * self.tint, self.alpha, clip);
+    }
+
+    fn push_gradient_rect(
+        &mut self,
+        pos: [f32; 2],
+        size: [f32; 2],
+        tex: [TexCoord; 2],
+        colors: [Color; 2],
+        direction: GradientDirection,
+        alpha: f32,
+        clip: Rect,
+    ) {
+        let colors = [colors[0] * self.tint, colors[1] * self.tint];
+        self.inner.push_gradient_rect(pos, size, tex, colors, direction, self.alpha * alpha, clip);
+    }
+
+    fn push_rect_alpha(
+        &mut self,
+        pos: [f32; 2],
+        size: [f32; 2],
+        tex: [TexCoord; 2],
+        color: Color,
+        alpha: f32,
+        clip: Rect,
+    ) {
+        self.inner.push_rect_alpha(pos, size, tex, color * self.tint, self.alpha * alpha, clip);
+    }
+
+    fn len(&self) -> usize { self.inner.len() }
+
+    fn back_adjust_positions(&mut self, since_index: usize, amount: Point) {
+        self.inner.back_adjust_positions(since_index, amount);
+    }
+}
+
 /// An implementation of DrawList that does nothing.  It should be (mostly) optimized
 /// out when used
 pub(crate) struct DummyDrawList {
@@ -124,6 +297,14 @@ impl TextureData {
     }
 
     pub fn handle(&self) -> TextureHandle { self.handle }
+
+    /// Converts the texture coordinate `coord` back to a texel position in this texture
+    pub(crate) fn texel(&self, coord: TexCoord) -> (u32, u32) {
+        (
+            (coord.x() * self.size[0] as f32).round() as u32,
+            (coord.y() * self.size[1] as f32).round() as u32,
+        )
+    }
 }
 
 #[derive(Copy, Clone)]
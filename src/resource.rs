@@ -10,7 +10,7 @@ use notify::{Watcher, RecommendedWatcher, RecursiveMode, watcher, DebouncedEvent
 
 use crate::Error;
 use crate::theme::ThemeSet;
-use crate::theme_definition::ThemeDefinition;
+use crate::theme_definition::{FontStyle, ThemeDefinition};
 use crate::render::{Renderer, TextureData, TextureHandle};
 
 static RELOAD_THEME: AtomicBool = AtomicBool::new(false);
@@ -18,6 +18,14 @@ static RELOAD_THEME: AtomicBool = AtomicBool::new(false);
 struct ThemeSource {
     data: Option<ThemeDefinition>,
     files: Option<ThemeSourceFiles>,
+    strings: Vec<ThemeSourceString>,
+}
+
+struct ThemeSourceString {
+    id: String,
+    src: String,
+    priority: i32,
+    de_func: Box<dyn Fn(&str) -> DeFuncResult>,
 }
 
 struct ThemeSourceFiles {
@@ -36,13 +44,18 @@ struct FontSource {
     font: Option<rusttype::Font<'static>>,
     data: Option<Vec<u8>>,
     file: Option<PathBuf>,
+    style: FontStyle,
 }
 
 pub(crate) struct ResourceSet {
     // preserve ordering of images and fonts
     images: Vec<(String, ImageSource)>,
     fonts: Vec<(String, FontSource)>,
+    font_fallbacks: Vec<(String, String)>,
     theme: ThemeSource,
+    texture_mipmaps: bool,
+    default_font: Option<String>,
+    font_scale: f32,
 
     watcher: Option<RecommendedWatcher>,
 }
@@ -70,10 +83,15 @@ impl ResourceSet {
         ResourceSet {
             images: Vec::new(),
             fonts: Vec::new(),
+            font_fallbacks: Vec::new(),
             theme: ThemeSource {
                 data: None,
                 files: None,
+                strings: Vec::new(),
             },
+            texture_mipmaps: false,
+            default_font: None,
+            font_scale: 1.0,
             watcher,
         }
     }
@@ -100,6 +118,25 @@ impl ResourceSet {
     pub(crate) fn register_theme(&mut self, theme: ThemeDefinition) {
         self.theme.data = Some(theme);
         self.theme.files = None;
+        self.theme.strings.clear();
+    }
+
+    pub(crate) fn register_theme_from_string<E, D, F>(
+        &mut self,
+        id: String,
+        src: String,
+        f: F,
+        priority: i32,
+    ) where
+        E: 'static + std::error::Error,
+        D: 'static + for<'a> serde::Deserializer<'a>,
+        F: 'static + Fn(&str) -> Result<D, E>,
+    {
+        let de_func = box_de_func(f);
+
+        self.theme.data = None;
+        self.theme.strings.retain(|string| string.id != id);
+        self.theme.strings.push(ThemeSourceString { id, src, priority, de_func });
     }
 
     pub(crate) fn register_theme_from_files<E, D, F>(
@@ -111,14 +148,7 @@ impl ResourceSet {
         D: 'static + for<'a> serde::Deserializer<'a>,
         F: 'static + Fn(&str) -> Result<D, E>,
     {
-        let boxed_fn: Box<dyn Fn(&str) -> DeFuncResult> = Box::new(move |input| {
-            let result = match (f)(input) {
-                Err(e) => Err(Box::new(e)),
-                Ok(data) => Ok(data),
-            }?;
-
-            Ok(Box::new(Deserializer::erase(result)))
-        });
+        let boxed_fn = box_de_func(f);
 
         let mut paths_out: Vec<PathBuf> = Vec::new();
         for path in paths {
@@ -132,13 +162,18 @@ impl ResourceSet {
         });
     }
 
-    pub(crate) fn register_font_from_file(&mut self, id: String, path: &Path) {
+    pub(crate) fn register_font_from_file(&mut self, id: String, path: &Path, style: FontStyle) {
         self.add_path_to_watcher(path);
-        self.fonts.push((id, FontSource { font: None, data: None, file: Some(path.to_owned()) }));
+        self.fonts.push((id, FontSource { font: None, data: None, file: Some(path.to_owned()), style }));
     }
 
-    pub(crate) fn register_font_from_data(&mut self, id: String, data: Vec<u8>) {
-        self.fonts.push((id, FontSource { font: None, data: Some(data), file: None }));
+    pub(crate) fn register_font_from_data(&mut self, id: String, data: Vec<u8>, style: FontStyle) {
+        self.fonts.push((id, FontSource { font: None, data: Some(data), file: None, style }));
+    }
+
+    pub(crate) fn register_font_fallback(&mut self, primary: String, fallback: String) {
+        self.font_fallbacks.retain(|(id, _)| *id != primary);
+        self.font_fallbacks.push((primary, fallback));
     }
 
     pub(crate) fn register_image_from_file(&mut self, id: String, path: &Path) {
@@ -146,6 +181,20 @@ impl ResourceSet {
         self.images.push((id, ImageSource { data: None, file: Some(path.to_owned()) }));
     }
 
+    pub(crate) fn set_texture_mipmaps(&mut self, enabled: bool) {
+        self.texture_mipmaps = enabled;
+    }
+
+    pub(crate) fn set_default_font(&mut self, id: String) {
+        self.default_font = Some(id);
+    }
+
+    pub(crate) fn set_font_scale(&mut self, scale: f32) {
+        self.font_scale = scale;
+    }
+
+    pub(crate) fn font_scale(&self) -> f32 { self.font_scale }
+
     pub(crate) fn register_image_from_data(&mut self, id: String, data: Vec<u8>, width: u32, height: u32) {
         self.images.push((id, ImageSource { data: Some((data, width, height)), file: None }));
     }
@@ -196,13 +245,87 @@ impl ResourceSet {
             },
             Some(def) => def,
         };
-        let themes = ThemeSet::new(theme_def, textures, fonts, renderer, scale_factor)?;
+
+        let errors = ThemeSet::validate(theme_def, &textures, &fonts);
+        if !errors.is_empty() {
+            for error in &errors {
+                log::warn!("{}", error);
+            }
+
+            let errors: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+            return Err(Error::Theme(format!("Theme validation failed:\n{}", errors.join("\n"))));
+        }
+
+        let mut themes = ThemeSet::new(theme_def, textures, fonts, renderer, scale_factor, self.font_scale)?;
+        themes.set_default_font(self.default_font.clone())?;
 
         Ok(themes)
     }
 
+    /// Re-reads the theme and all image files and registers the textures with the renderer again, then
+    /// recreates the images in `themes`.  Fonts and widget themes are unchanged.  If anything fails, the
+    /// previously read theme and image data are kept.
+    pub(crate) fn rebuild_images<R: Renderer>(&mut self, renderer: &mut R, themes: &mut ThemeSet) -> Result<(), Error> {
+        let reload_theme = self.theme.files.is_some() || !self.theme.strings.is_empty();
+        let old_theme = if reload_theme { self.theme.data.take() } else { None };
+        let old_images: Vec<_> = self.images.iter_mut()
+            .map(|(_, src)| if src.file.is_some() { src.data.take() } else { None })
+            .collect();
+
+        let result = self.reload_images(renderer, themes);
+
+        if result.is_err() {
+            if reload_theme {
+                self.theme.data = old_theme;
+            }
+
+            for ((_, src), old) in self.images.iter_mut().zip(old_images) {
+                if src.file.is_some() {
+                    src.data = old;
+                }
+            }
+        }
+
+        result
+    }
+
+    fn reload_images<R: Renderer>(&mut self, renderer: &mut R, themes: &mut ThemeSet) -> Result<(), Error> {
+        self.cache_data()?;
+
+        let theme_def = self.theme.data.as_ref().ok_or_else(||
+            Error::Theme("Cannot build assets.  No theme specified.".to_string())
+        )?;
+
+        // textures added at runtime are kept, so their handles must not be reused
+        let textures = self.texture_data(&themes.runtime_texture_handles());
+        themes.rebuild_images(theme_def, textures, |textures| self.register_textures(renderer, textures))
+    }
+
+    /// Re-reads all font files and registers the fonts with the renderer again, updating `themes`.
+    /// Images and widget themes are unchanged.
+    pub(crate) fn rebuild_fonts<R: Renderer>(
+        &mut self,
+        renderer: &mut R,
+        themes: &mut ThemeSet,
+        scale_factor: f32,
+    ) -> Result<(), Error> {
+        for (_, src) in self.fonts.iter_mut() {
+            if src.file.is_some() {
+                src.data = None;
+                src.font = None;
+            }
+        }
+        self.cache_data()?;
+
+        let fonts = self.build_fonts()?;
+        let theme_def = self.theme.data.as_ref().ok_or_else(||
+            Error::Theme("Cannot build assets.  No theme specified.".to_string())
+        )?;
+        themes.rebuild_fonts(theme_def, fonts, renderer, scale_factor, self.font_scale)
+    }
+
     pub(crate) fn clear_data_cache(&mut self) {
-        if self.theme.files.is_some() {
+        if self.theme.files.is_some() || !self.theme.strings.is_empty() {
             self.theme.data = None;
         }
 
@@ -222,9 +345,22 @@ impl ResourceSet {
 
     pub(crate) fn cache_data(&mut self) -> Result<(), Error> {
         if self.theme.data.is_none() {
-            if let Some(theme_source) = self.theme.files.as_ref() {
-                let mut theme_def: Option<ThemeDefinition> = None;
+            let mut theme_def: Option<ThemeDefinition> = None;
+
+            // sources are merged from lowest to highest priority, and in registration order for
+            // equal priorities.  Files have priority zero and are merged after string sources of
+            // the same priority, so files may override embedded themes
+            let mut strings: Vec<&ThemeSourceString> = self.theme.strings.iter().collect();
+            strings.sort_by_key(|string| string.priority);
+            let (low_strings, high_strings): (Vec<_>, Vec<_>) = strings.into_iter()
+                .partition(|string| string.priority <= 0);
+
+            for string in low_strings {
+                log::debug!("Using {} bytes from string '{}' for theme.", string.src.len(), string.id);
+                merge_theme_source(&mut theme_def, &string.de_func, &string.src)?;
+            }
 
+            if let Some(theme_source) = self.theme.files.as_ref() {
                 let mut theme_str = String::new();
                 for path in &theme_source.paths {
                     let mut file = match File::open(path) {
@@ -240,28 +376,16 @@ impl ResourceSet {
                         }
                     }
 
-                    let theme_value = match (theme_source.de_func)(&theme_str) {
-                        Ok(value) => value,
-                        Err(e) => return Err(Error::Serde(e.to_string())),
-                    };
-                    
-                    match theme_def.as_mut() {
-                        None => {
-                            theme_def = Some(match serde::Deserialize::deserialize(theme_value) {
-                                Ok(theme) => theme,
-                                Err(e) => return Err(Error::Serde(e.to_string())),
-                            });
-                        }, Some(theme) => {
-                            let new_theme_def: ThemeDefinition = match serde::Deserialize::deserialize(theme_value) {
-                                Ok(theme) => theme,
-                                Err(e) => return Err(Error::Serde(e.to_string())),
-                            };
-
-                            theme.merge(new_theme_def);
-                        }
-                    }
+                    merge_theme_source(&mut theme_def, &theme_source.de_func, &theme_str)?;
                 }
+            }
+
+            for string in high_strings {
+                log::debug!("Using {} bytes from string '{}' for theme.", string.src.len(), string.id);
+                merge_theme_source(&mut theme_def, &string.de_func, &string.src)?;
+            }
 
+            if self.theme.files.is_some() || !self.theme.strings.is_empty() {
                 if theme_def.is_none() {
                     return Err(Error::Theme("No valid theme was specified".to_string()));
                 }
@@ -278,7 +402,7 @@ impl ResourceSet {
 
             let image = match image::open(path) {
                 Ok(image) => image.into_rgba8(),
-                Err(error) => return Err(Error::Image(error)),
+                Err(error) => return Err(Error::ImageLoad(path.display().to_string(), Box::new(error))),
             };
 
             let dims = image.dimensions();
@@ -311,9 +435,10 @@ impl ResourceSet {
 
             let font = match rusttype::Font::try_from_vec(data) {
                 Some(font) => font,
-                None => return Err(
-                    Error::FontSource(format!("Unable to parse '{}' as ttf", id))
-                )
+                None => return Err(Error::FontParse(id.to_string(), Box::new(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "The data is not a valid TrueType or OpenType font",
+                )))),
             };
 
             log::debug!("Created rusttype font from '{}'", id);
@@ -324,42 +449,144 @@ impl ResourceSet {
         Ok(())
     }
 
-    fn build_fonts(&mut self) -> Result<HashMap<String, crate::font::FontSource>, Error> {
-        let mut output = HashMap::new();
+    fn build_fonts(&mut self) -> Result<HashMap<(String, FontStyle), crate::font::FontSource>, Error> {
+        let mut fonts = HashMap::new();
 
         for (id, source) in self.fonts.iter_mut() {
             let font = source.font.take().unwrap();
-            output.insert(id.to_string(), crate::font::FontSource { font });
+            fonts.insert((id.to_string(), source.style), font);
+        }
+
+        let mut output = HashMap::new();
+
+        for ((id, style), font) in fonts.iter() {
+            // walk the fallback chain, preferring the same style in each fallback source
+            let mut fallbacks = Vec::new();
+            let mut visited = vec![id.as_str()];
+            let mut cur = id.as_str();
+            while let Some(next) = self.font_fallback(cur) {
+                if visited.contains(&next) {
+                    return Err(Error::FontSource(format!("Circular font fallback chain for font source '{}'", id)));
+                }
+
+                let fallback = fonts.get(&(next.to_string(), *style))
+                    .or_else(|| fonts.get(&(next.to_string(), FontStyle::Normal)));
+                match fallback {
+                    None => return Err(Error::FontSource(
+                        format!("Unable to locate font source '{}', used as the fallback for '{}'", next, cur)
+                    )),
+                    Some(fallback) => fallbacks.push(fallback.clone()),
+                }
+
+                visited.push(next);
+                cur = next;
+            }
+
+            output.insert(
+                (id.to_string(), *style),
+                crate::font::FontSource { font: font.clone(), style: *style, fallbacks },
+            );
         }
 
         Ok(output)
     }
 
+    fn font_fallback(&self, id: &str) -> Option<&str> {
+        self.font_fallbacks.iter().find(|(primary, _)| primary == id).map(|(_, fallback)| fallback.as_str())
+    }
+
     fn build_images<R: Renderer>(&self, renderer: &mut R) -> Result<HashMap<String, TextureData>, Error> {
+        let textures = self.texture_data(&[]);
+        self.register_textures(renderer, &textures)?;
+        Ok(textures)
+    }
+
+    /// Assigns a texture handle to the 1x1 pixel texture and to each image, skipping any `reserved` handles
+    fn texture_data(&self, reserved: &[TextureHandle]) -> HashMap<String, TextureData> {
         let mut output = HashMap::new();
         let mut handle = TextureHandle::default();
+        let mut next_handle = || {
+            while reserved.contains(&handle) {
+                handle = handle.next();
+            }
+            let result = handle;
+            handle = handle.next();
+            result
+        };
+
+        output.insert(INTERNAL_SINGLE_PIX_IMAGE_ID.to_string(), TextureData::new(next_handle(), 1, 1));
+
+        for (id, source) in self.images.iter() {
+            let (_, width, height) = source.data.as_ref().unwrap();
+            output.insert(id.to_string(), TextureData::new(next_handle(), *width, *height));
+        }
+
+        output
+    }
+
+    /// Registers the texture data for each image with the `renderer`, using the handles assigned in `textures`
+    fn register_textures<R: Renderer>(
+        &self,
+        renderer: &mut R,
+        textures: &HashMap<String, TextureData>,
+    ) -> Result<(), Error> {
+        renderer.set_texture_mipmaps(self.texture_mipmaps);
 
         // register a 1x1 pixel texture for use with minimal themes
         let tex_data = [0xff, 0xff, 0xff, 0xff];
-        let tex_data = renderer.register_texture(handle, &tex_data, (1, 1))?;
-        output.insert(INTERNAL_SINGLE_PIX_IMAGE_ID.to_string(), tex_data);
-        handle = handle.next();
-        
+        renderer.register_texture(textures[INTERNAL_SINGLE_PIX_IMAGE_ID].handle(), &tex_data, (1, 1))?;
+
         for (id, source) in self.images.iter() {
             let (tex_data, width, height) = source.data.as_ref().unwrap();
-            let dims = (*width, *height);
-            let tex_data = renderer.register_texture(handle, &tex_data, dims)?;
-            output.insert(id.to_string(), tex_data);
-
-            handle = handle.next();
+            renderer.register_texture(textures[id].handle(), tex_data, (*width, *height))?;
         }
 
-        Ok(output)
+        Ok(())
     }
 }
 
 pub(crate) const INTERNAL_SINGLE_PIX_IMAGE_ID: &str = "__INTERNAL_SINGLE_PIX__";
 
+fn box_de_func<E, D, F>(f: F) -> Box<dyn Fn(&str) -> DeFuncResult> where
+    E: 'static + std::error::Error,
+    D: 'static + for<'a> serde::Deserializer<'a>,
+    F: 'static + Fn(&str) -> Result<D, E>,
+{
+    Box::new(move |input| {
+        let result = match (f)(input) {
+            Err(e) => Err(Box::new(e)),
+            Ok(data) => Ok(data),
+        }?;
+
+        Ok(Box::new(Deserializer::erase(result)))
+    })
+}
+
+/// Parses `theme_str` with `de_func` and merges the result into `theme_def`, or sets it if there
+/// is no theme yet
+fn merge_theme_source(
+    theme_def: &mut Option<ThemeDefinition>,
+    de_func: &dyn Fn(&str) -> DeFuncResult,
+    theme_str: &str,
+) -> Result<(), Error> {
+    let theme_value = match (de_func)(theme_str) {
+        Ok(value) => value,
+        Err(e) => return Err(Error::Serde(e.to_string())),
+    };
+
+    let new_theme_def: ThemeDefinition = match serde::Deserialize::deserialize(theme_value) {
+        Ok(theme) => theme,
+        Err(e) => return Err(Error::Serde(e.to_string())),
+    };
+
+    match theme_def.as_mut() {
+        None => *theme_def = Some(new_theme_def),
+        Some(theme) => theme.merge(new_theme_def),
+    }
+
+    Ok(())
+}
+
 fn watcher_loop(rx: Receiver<DebouncedEvent>) {
     loop {
         match rx.recv() {
@@ -1,4 +1,6 @@
-use crate::{Frame, widget::WidgetBuilder, Rect, Point};
+use serde::{Serialize, Deserialize};
+
+use crate::{Context, Frame, widget::WidgetBuilder, Rect, Point};
 
 /**
 A [`WidgetBuilder`](struct.WidgetBuilder.html) specifically for creating scrollpanes.
@@ -25,14 +27,24 @@ fn build_scrollpane(ui: &mut Frame, unique_id: &str) {
 ```
 
 # Theme definition
-An example of a theme definition for a scrollpane:
+An example of a theme definition for a scrollpane.  The optional `scrollbar_vertical` and `scrollbar_horizontal`
+fields set when each scrollbar is shown, while `scrollbar_width` sets the thickness of both scrollbars and their
+scroll handles, and `scrollbar_min_handle_size` sets the minimum length of the scroll handles.  Each of these may be
+overridden with the methods on `ScrollpaneBuilder`.
 
 ```yaml
   scrollpane:
     width_from: Parent
     height_from: Parent
     border: { all: 5 }
+    scrollbar_horizontal: Never
+    scrollbar_min_handle_size: 10
     children:
+      header:
+        width_from: Parent
+        size: [-15, 20]
+        align: TopLeft
+        layout: Horizontal
       content:
         height_from: Parent
         width_from: Parent
@@ -94,29 +106,87 @@ An example of a theme definition for a scrollpane:
 pub struct ScrollpaneBuilder<'a> {
     builder: WidgetBuilder<'a>,
     state: ScrollpaneState,
+    header: Option<HeaderFn<'a>>,
 }
 
+// builds the sticky header content
+type HeaderFn<'a> = Box<dyn FnOnce(&mut Frame) + 'a>;
+
 struct ScrollpaneState {
     content_id: String,
     show_horiz: ShowElement,
     show_vert: ShowElement,
+    scrollbar_width: Option<f32>,
+    min_handle_size: f32,
+}
+
+/// The scrollbar attributes of a scrollpane widget theme
+#[derive(Copy, Clone, Default)]
+pub(crate) struct ScrollbarTheme {
+    pub width: Option<f32>,
+    pub min_handle_size: Option<f32>,
+    pub show_vertical: Option<ShowElement>,
+    pub show_horizontal: Option<ShowElement>,
 }
 
 impl<'a> ScrollpaneBuilder<'a> {
     pub(crate) fn new(builder: WidgetBuilder<'a>, content_id: &str) -> ScrollpaneBuilder<'a> {
+        let theme = builder.scrollbar_theme();
         ScrollpaneBuilder {
             builder,
             state: ScrollpaneState {
                 content_id: content_id.to_string(),
-                show_horiz: ShowElement::Sometimes,
-                show_vert: ShowElement::Sometimes,
-            }
+                show_horiz: theme.show_horizontal.unwrap_or(ShowElement::Sometimes),
+                show_vert: theme.show_vertical.unwrap_or(ShowElement::Sometimes),
+                scrollbar_width: theme.width,
+                min_handle_size: theme.min_handle_size.unwrap_or_default(),
+            },
+            header: None,
         }
     }
 
+    /// Returns the current scroll position of the scrollpane with the specified content `id`, which is
+    /// the ID passed to [`WidgetBuilder.scrollpane`](struct.WidgetBuilder.html#method.scrollpane).  The position
+    /// is the offset, in logical pixels, of the content from its unscrolled position, with positive values
+    /// towards the bottom and right of the content.  This does not require building the scrollpane, and may
+    /// be used outside of a frame.  See [`Frame::set_scroll_pos`](struct.Frame.html#method.set_scroll_pos).
+    /// This is the negation of the raw value returned by [`Frame::scroll`](struct.Frame.html#method.scroll),
+    /// which is also used by [`Frame::change_scroll`](struct.Frame.html#method.change_scroll).
+    pub fn get_scroll_pos(id: &str, context: &Context) -> Point {
+        let internal = context.internal().borrow();
+        Point::default() - internal.state(id).scroll
+    }
+
+    /// Specifies a sticky header for this scrollpane.  The `header` closure is called to add
+    /// children to the `header` child widget, which is positioned at the top of the scrollpane
+    /// and is not affected by scrolling.  The header shares the clip width of the scrollpane,
+    /// and the scrollpane content area is moved down and shortened by the header's height.
+    /// This is useful for column labels in a list or table.  The scrollpane theme should
+    /// define a `header` child, typically with `width_from: Parent` and a fixed height.
+    pub fn sticky_header<F: FnOnce(&mut Frame) + 'a>(mut self, header: F) -> ScrollpaneBuilder<'a> {
+        self.header = Some(Box::new(header));
+        self
+    }
+
+    /// Specify the thickness of both scrollbars and their scroll handles, in logical pixels.  This
+    /// overrides the `scrollbar_width` in the theme, if any.  Otherwise, the sizes from the scrollbar
+    /// themes are used.
+    pub fn scrollbar_width(mut self, width: f32) -> ScrollpaneBuilder<'a> {
+        self.state.scrollbar_width = Some(width);
+        self
+    }
+
+    /// Specify the minimum length of the scroll handles, in logical pixels.  This overrides the
+    /// `scrollbar_min_handle_size` in the theme, if any.  By default, there is no minimum.
+    pub fn scrollbar_min_handle_size(mut self, size: f32) -> ScrollpaneBuilder<'a> {
+        self.state.min_handle_size = size;
+        self
+    }
+
     /// Specify when to show the vertical scrollbar in this scrollpane.  If `show` is
     /// equal to `Sometimes`, will show the vertical scrollbar if the pane content height
-    /// is greater than the scrollpane's inner height.
+    /// is greater than the scrollpane's inner height.  This overrides `scrollbar_vertical`
+    /// in the theme, if any.
     pub fn show_vertical_scrollbar(mut self, show: ShowElement) -> ScrollpaneBuilder<'a> {
         self.state.show_vert = show;
         self
@@ -124,12 +194,33 @@ impl<'a> ScrollpaneBuilder<'a> {
 
     /// Specify when to show the horizontal scrollbar in this scrollpane.  If `show` is
     /// equal to `Sometimes`, will show the horizontal scrollbar if the pane content width
-    /// is greater than the scrollpane's inner width.
+    /// is greater than the scrollpane's inner width.  This overrides `scrollbar_horizontal`
+    /// in the theme, if any.
     pub fn show_horizontal_scrollbar(mut self, show: ShowElement) -> ScrollpaneBuilder<'a> {
         self.state.show_horiz = show;
         self
     }
 
+    /// Scrolls this scrollpane so that the widget with the specified `id` is fully visible, if possible.
+    /// The widget must be a descendant of the scrollpane content, and is located when the scrollpane
+    /// is next laid out.  The request is stored in the [`PersistentState`](struct.PersistentState.html)
+    /// of the scrollpane content.  See [`ScrollRequest`](enum.ScrollRequest.html).
+    pub fn scroll_to_widget(self, id: &str) -> ScrollpaneBuilder<'a> {
+        self.request_scroll(ScrollRequest::Widget(id.to_string()))
+    }
+
+    /// Scrolls this scrollpane so that the content is offset by `offset` logical pixels from its unscrolled
+    /// position.  Positive values scroll towards the bottom and right of the content.  The offset is limited
+    /// to the scrollable area.  See [`ScrollRequest`](enum.ScrollRequest.html).
+    pub fn scroll_to_offset(self, offset: Point) -> ScrollpaneBuilder<'a> {
+        self.request_scroll(ScrollRequest::Offset(offset))
+    }
+
+    fn request_scroll(self, request: ScrollRequest) -> ScrollpaneBuilder<'a> {
+        self.builder.frame.modify(&self.state.content_id, |state| state.scroll_request = Some(request));
+        self
+    }
+
     /// Consumes this builder to create a scrollpane.  Calls the specified `children` closure
     /// to add children to the scrollpane.
     pub fn children<F: FnOnce(&mut Frame)>(self, children: F) {
@@ -141,19 +232,51 @@ impl<'a> ScrollpaneBuilder<'a> {
         let content_id = state.content_id;
         let horiz = state.show_horiz;
         let vert = state.show_vert;
+        let bar_width = state.scrollbar_width;
+        let min_handle = state.min_handle_size;
+        let header = self.header;
+        let request = self.builder.frame.modify(&content_id, |state| state.scroll_request.take());
 
         let (ui, result) = self.builder.finish_with(
             Some(|ui: &mut Frame| {
                 let mut content_bounds = Rect::default();
+
+                // the header is a child of the scrollpane rather than the content,
+                // so it does not pick up the content scroll
+                let mut header_height = 0.0;
+                if let Some(header) = header {
+                    let mut header_rect = Rect::default();
+                    let header_index = ui.num_widgets();
+                    let result = ui.start("header")
+                    .trigger_layout(&mut header_rect)
+                    .clip(header_rect)
+                    .children(header);
+
+                    if result.visible {
+                        header_height = ui.widget(header_index).size().y;
+                    }
+                }
         
                 // TODO if horizontal and/or vertical scrollbars aren't present,
                 // change the scrollpane content size to fill up the available space
         
+                let content_index = ui.num_widgets();
                 ui.start("content")
                 .id(&content_id)
+                .inset_top(header_height)
                 .trigger_layout(&mut content_bounds)
                 .clip(content_bounds)
                 .children(children);
+
+                let mut delta_scroll = Point::default();
+
+                if let Some(ScrollRequest::Widget(id)) = &request {
+                    let end = ui.widget(content_index).subtree_end();
+                    let target = (content_index + 1..end).map(|index| ui.widget(index)).find(|widget| widget.id() == id);
+                    if let Some(target) = target {
+                        delta_scroll = scroll_into_view(content_bounds, Rect::new(target.pos(), target.size()));
+                    }
+                }
         
                 let content_min = content_bounds.pos;
                 let content_max = content_bounds.pos + content_bounds.size;
@@ -161,14 +284,17 @@ impl<'a> ScrollpaneBuilder<'a> {
                 let pane_bounds = ui.parent_max_child_bounds();
                 let pane_min = pane_bounds.pos;
                 let pane_max = pane_bounds.pos + pane_bounds.size;
-        
-                let mut delta_scroll = Point::default();
 
                 let enable_horiz = pane_min.x < content_min.x || pane_max.x > content_max.x;
                 // check whether to show horizontal scrollbar
                 if horiz.show(enable_horiz) {
-                    ui.start("scrollbar_horizontal")
-                    .children(|ui| {
+                    let bar = ui.start("scrollbar_horizontal");
+                    let bar = match bar_width {
+                        None => bar,
+                        Some(width) => { let size = bar.raw_size(); bar.size(size.x, width) },
+                    };
+
+                    bar.children(|ui| {
                         let mut right_rect = Rect::default();
                         let result = ui.start("right")
                         .enabled(pane_max.x > content_max.x)
@@ -192,10 +318,9 @@ impl<'a> ScrollpaneBuilder<'a> {
                         // assume left button starts at 0,0 within the parent widget
                         let min_x = left_rect.size.x;
                         let max_x = right_rect.pos.x - left_rect.pos.x;
-                        let pos_x = min_x + start_frac * (max_x - min_x);
+                        let (pos_x, size_x, drag_scale) = handle_pos_size(min_x, max_x, start_frac, width_frac, min_handle);
                         let pos_y = 0.0;
-                        let size_x = width_frac * (max_x - min_x);
-                        let size_y = left_rect.size.y;
+                        let size_y = bar_width.unwrap_or(left_rect.size.y);
         
                         let result = ui.start("scroll")
                         .size(size_x, size_y)
@@ -204,7 +329,7 @@ impl<'a> ScrollpaneBuilder<'a> {
                         .finish();
         
                         if result.pressed {
-                            delta_scroll.x -= result.moved.x / width_frac;
+                            delta_scroll.x -= result.moved.x * drag_scale / width_frac;
                         }
                     });
                 }
@@ -212,8 +337,13 @@ impl<'a> ScrollpaneBuilder<'a> {
                 let enable_vertical = pane_min.y < content_min.y || pane_max.y > content_max.y;
                 // check whether to show vertical scrollbar
                 if vert.show(enable_vertical) {
-                    ui.start("scrollbar_vertical")
-                    .children(|ui| {
+                    let bar = ui.start("scrollbar_vertical");
+                    let bar = match bar_width {
+                        None => bar,
+                        Some(width) => { let size = bar.raw_size(); bar.size(width, size.y) },
+                    };
+
+                    bar.children(|ui| {
                         let mut top_rect = Rect::default();
                         let result = ui.start("up")
                         .enabled(pane_min.y < content_min.y)
@@ -237,10 +367,9 @@ impl<'a> ScrollpaneBuilder<'a> {
                         // assume top button starts at 0,0 within the parent widget
                         let min_y = top_rect.size.y;
                         let max_y = bot_rect.pos.y - top_rect.pos.y;
-                        let pos_y = min_y + start_frac * (max_y - min_y);
+                        let (pos_y, size_y, drag_scale) = handle_pos_size(min_y, max_y, start_frac, height_frac, min_handle);
                         let pos_x = 0.0;
-                        let size_y = height_frac * (max_y - min_y);
-                        let size_x = top_rect.size.x;
+                        let size_x = bar_width.unwrap_or(top_rect.size.x);
         
                         let result = ui.start("scroll")
                         .size(size_x, size_y)
@@ -249,7 +378,7 @@ impl<'a> ScrollpaneBuilder<'a> {
                         .finish();
         
                         if result.pressed {
-                            delta_scroll.y -= result.moved.y / height_frac;
+                            delta_scroll.y -= result.moved.y * drag_scale / height_frac;
                         }
                     });
                 }
@@ -265,13 +394,70 @@ impl<'a> ScrollpaneBuilder<'a> {
             let min = min_scroll + state.scroll;
             let max = max_scroll + state.scroll;
 
-            state.scroll = (state.scroll + delta + result.moved).max(min).min(max);
+            let scroll = match &request {
+                Some(ScrollRequest::Offset(offset)) => Point::default() - *offset,
+                _ => state.scroll + delta + result.moved,
+            };
+            state.scroll = scroll.max(min).min(max);
         });
     }
 }
 
+/// Computes the position and length of a scroll handle within the track from `min` to `max`, where
+/// `start_frac` is the scrolled fraction of the content and `size_frac` the visible fraction.  The
+/// handle is at least `min_size` long.  Also returns the factor by which drags of the handle must be
+/// scaled to account for the handle being lengthened.
+fn handle_pos_size(min: f32, max: f32, start_frac: f32, size_frac: f32, min_size: f32) -> (f32, f32, f32) {
+    let track = max - min;
+    let natural_size = size_frac * track;
+    let size = natural_size.max(min_size.min(track));
+
+    if size <= natural_size || size >= track {
+        return (min + start_frac * track, size, 1.0);
+    }
+
+    // the handle moves over the remaining length of the track, rather than the whole track
+    let range = track - size;
+    let natural_range = track - natural_size;
+    let pos = min + start_frac * track * range / natural_range;
+    (pos, size, natural_range / range)
+}
+
+/// Returns the amount to scroll content so that `target` is within `view`.  If `target` is larger
+/// than `view`, it is aligned to the top left.
+fn scroll_into_view(view: Rect, target: Rect) -> Point {
+    let axis = |view_min: f32, view_size: f32, min: f32, size: f32| {
+        if min < view_min || size > view_size {
+            view_min - min
+        } else if min + size > view_min + view_size {
+            view_min + view_size - (min + size)
+        } else {
+            0.0
+        }
+    };
+
+    Point::new(
+        axis(view.pos.x, view.size.x, target.pos.x, target.size.x),
+        axis(view.pos.y, view.size.y, target.pos.y, target.size.y),
+    )
+}
+
+/// A request to scroll a [`scrollpane`](struct.ScrollpaneBuilder.html), created with
+/// [`scroll_to_widget`](struct.ScrollpaneBuilder.html#method.scroll_to_widget) or
+/// [`scroll_to_offset`](struct.ScrollpaneBuilder.html#method.scroll_to_offset).  The request
+/// may also be set directly on the [`PersistentState`](struct.PersistentState.html) of the scrollpane
+/// content, for example when opening a window, and is applied the next time the scrollpane is built.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScrollRequest {
+    /// Scroll so that the widget with the specified ID, a descendant of the scrollpane content, is visible.
+    Widget(String),
+
+    /// Scroll the content to the specified offset, in logical pixels, from its unscrolled position.
+    Offset(Point),
+}
+
 /// An enum to define when to show a particular UI element.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum ShowElement {
     /// Never show the element
     Never,
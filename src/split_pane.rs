@@ -0,0 +1,183 @@
+use crate::{Frame, widget::WidgetBuilder, Rect, Point};
+
+/// The default width of the divider between the two panes, used when the theme
+/// does not specify a `split_divider_width`.
+const DEFAULT_DIVIDER_WIDTH: f32 = 6.0;
+
+/**
+A [`WidgetBuilder`](struct.WidgetBuilder.html) specifically for creating split panes.
+
+Create this using [`WidgetBuilder.split_pane`](struct.WidgetBuilder.html#method.split_pane).
+A split pane divides its inner area into two panes, separated by a divider which the user may
+drag to resize the panes.  The fraction of the available space given to the first pane is stored
+in the [`PersistentState`](struct.PersistentState.html) of the split pane's ID.
+There are also [`hsplit`](struct.Frame.html#method.hsplit) and [`vsplit`](struct.Frame.html#method.vsplit)
+methods on `Frame` as a convenience for simple cases.
+
+Once you are finished setting up the split pane, you call [`children`](#method.children) to add children
+to each of the two panes and add the widget to the frame.
+
+# Example
+```
+fn build_split(ui: &mut Frame, unique_id: &str) {
+    ui.start("hsplit")
+    .split_pane(unique_id, SplitDirection::Horizontal, 0.3)
+    .min_fraction(0.1)
+    .max_fraction(0.9)
+    .children(|ui| {
+        // left pane UI here
+    }, |ui| {
+        // right pane UI here
+    });
+}
+```
+
+# Theme definition
+An example of a theme definition for a split pane.  The `split_divider_width` sets the
+width of the divider's draggable area, in logical pixels.
+
+```yaml
+  hsplit:
+    size_from: [Parent, Parent]
+    split_divider_width: 6
+    children:
+      first:
+        align: TopLeft
+        layout: Vertical
+      divider:
+        wants_mouse: true
+        background: gui/small_button
+        align: TopLeft
+      second:
+        align: TopLeft
+        layout: Vertical
+```
+*/
+pub struct SplitPaneBuilder<'a> {
+    builder: WidgetBuilder<'a>,
+    state: SplitPaneState,
+}
+
+struct SplitPaneState {
+    id: String,
+    direction: SplitDirection,
+    initial_fraction: f32,
+    min_fraction: f32,
+    max_fraction: f32,
+}
+
+impl<'a> SplitPaneBuilder<'a> {
+    pub(crate) fn new(
+        builder: WidgetBuilder<'a>,
+        id: &str,
+        direction: SplitDirection,
+        initial_fraction: f32,
+    ) -> SplitPaneBuilder<'a> {
+        SplitPaneBuilder {
+            builder,
+            state: SplitPaneState {
+                id: id.to_string(),
+                direction,
+                initial_fraction,
+                min_fraction: 0.0,
+                max_fraction: 1.0,
+            },
+        }
+    }
+
+    /// Specify the minimum fraction of the available space that the first pane may be
+    /// dragged to.  Defaults to zero.
+    pub fn min_fraction(mut self, min: f32) -> SplitPaneBuilder<'a> {
+        self.state.min_fraction = min;
+        self
+    }
+
+    /// Specify the maximum fraction of the available space that the first pane may be
+    /// dragged to.  Defaults to one.
+    pub fn max_fraction(mut self, max: f32) -> SplitPaneBuilder<'a> {
+        self.state.max_fraction = max;
+        self
+    }
+
+    /// Consumes this builder to create a split pane.  Calls the specified `first` and `second`
+    /// closures to add children to the first (left or top) and second (right or bottom) panes.
+    pub fn children<F: FnOnce(&mut Frame), G: FnOnce(&mut Frame)>(self, first: F, second: G) {
+        let state = self.state;
+        let min = state.min_fraction;
+        let max = state.max_fraction;
+        let direction = state.direction;
+        let divider_width = self.builder.split_divider_width().unwrap_or(DEFAULT_DIVIDER_WIDTH);
+
+        let fraction = {
+            let initial = state.initial_fraction;
+            let internal = self.builder.frame.context_internal().borrow();
+            internal.state(&state.id).split_fraction.unwrap_or(initial).max(min).min(max)
+        };
+
+        let mut inner = Rect::default();
+        let mut delta = 0.0;
+
+        let (ui, _) = self.builder.trigger_layout_inner(&mut inner).finish_with(Some(|ui: &mut Frame| {
+            let (length, cross) = match direction {
+                SplitDirection::Horizontal => (inner.size.x, inner.size.y),
+                SplitDirection::Vertical => (inner.size.y, inner.size.x),
+            };
+
+            let available = (length - divider_width).max(0.0);
+            let first_length = available * fraction;
+            let second_length = available - first_length;
+
+            // converts a position and size along the split direction to a widget pos and size
+            let place = |start: f32, length: f32| match direction {
+                SplitDirection::Horizontal => (Point::new(start, 0.0), Point::new(length, cross)),
+                SplitDirection::Vertical => (Point::new(0.0, start), Point::new(cross, length)),
+            };
+
+            let (pos, size) = place(0.0, first_length);
+            let mut rect = Rect::default();
+            ui.start("first")
+            .pos(pos.x, pos.y)
+            .size(size.x, size.y)
+            .trigger_layout(&mut rect)
+            .clip(rect)
+            .children(first);
+
+            let (pos, size) = place(first_length, divider_width);
+            let result = ui.start("divider")
+            .pos(pos.x, pos.y)
+            .size(size.x, size.y)
+            .finish();
+
+            if result.pressed && available > 0.0 {
+                let moved = match direction {
+                    SplitDirection::Horizontal => result.moved.x,
+                    SplitDirection::Vertical => result.moved.y,
+                };
+                delta = moved / available;
+            }
+
+            let (pos, size) = place(first_length + divider_width, second_length);
+            let mut rect = Rect::default();
+            ui.start("second")
+            .pos(pos.x, pos.y)
+            .size(size.x, size.y)
+            .trigger_layout(&mut rect)
+            .clip(rect)
+            .children(second);
+        }));
+
+        ui.modify(&state.id, |persistent| {
+            persistent.split_fraction = Some((fraction + delta).max(min).min(max));
+        });
+    }
+}
+
+/// The direction in which a [`SplitPaneBuilder`](struct.SplitPaneBuilder.html) divides its area.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SplitDirection {
+    /// The panes are placed side by side, separated by a vertical divider.
+    Horizontal,
+
+    /// The panes are placed one above the other, separated by a horizontal divider.
+    Vertical,
+}
@@ -1,13 +1,22 @@
-use std::collections::{HashMap};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
 use crate::theme_definition::{
-    ThemeDefinition, ImageDefinition, ImageDefinitionKind, WidgetThemeDefinition,
+    ThemeDefinition, ImageDefinition, ImageDefinitionKind, WidgetThemeDefinition, FontDefinition, FontStyle, ImageSet,
+    ThemeColor, WidgetThemeOverride, AnimState, AnimStateKey, ImageFill,
 };
 use crate::font::{Font, FontSummary, FontSource};
-use crate::image::{Image, ImageHandle};
-use crate::render::{TextureData, Renderer, FontHandle};
+use crate::image::{Image, ImageHandle, Texels};
+use crate::render::{TextureData, TextureHandle, Renderer, FontHandle};
 use crate::theme_definition::CharacterRange;
-use crate::{Color, Error, Point, Border, Align, Layout, WidthRelative, HeightRelative};
+use crate::{Color, Error, Point, Rect, Border, Align, Layout, WidthRelative, HeightRelative, ShowElement, CursorIcon};
+
+/// The smoothness used for distance field fonts that do not specify one, about
+/// half a pixel at the font's native size.
+const DEFAULT_SDF_SMOOTHNESS: f32 = 0.06;
+
+/// The prefix of the IDs of textures registered for images added at runtime.
+const RUNTIME_TEXTURE_PREFIX: &str = "__thyme_runtime/";
 
 pub struct ThemeSet {
     fonts: Vec<Font>,
@@ -15,48 +24,37 @@ pub struct ThemeSet {
 
     images: Vec<Image>,
     image_handles: HashMap<String, ImageHandle>,
+    textures: HashMap<String, TextureData>,
 
     theme_handles: HashMap<String, WidgetThemeHandle>,
     themes: Vec<WidgetTheme>,
+
+    palette: HashMap<String, Color>,
+
+    // the font used by widgets whose theme specifies none
+    default_font: Option<String>,
+
+    // the source definitions, kept so the theme can be exported
+    font_definitions: HashMap<String, FontDefinition>,
+    image_set_definitions: HashMap<String, ImageSet>,
 }
 
 impl ThemeSet {
     pub(crate) fn new<R: Renderer>(
         definition: &ThemeDefinition,
         textures: HashMap<String, TextureData>,
-        font_sources: HashMap<String, FontSource>,
+        font_sources: HashMap<(String, FontStyle), FontSource>,
         renderer: &mut R,
         display_scale: f32,
+        font_scale: f32,
     ) -> Result<ThemeSet, Error> {
-        let default_font_ranges = vec![
-            CharacterRange { lower: 32, upper: 126 },
-            CharacterRange { lower: 161, upper: 255 },
-        ];
-
         // TODO need to be able to rebuild fonts when scale factor changes
         // FontSummary size will stay the same for this
         let mut font_handles = HashMap::new();
         let mut font_handle = FontHandle::default();
         let mut fonts = Vec::new();
         for (font_id, font) in &definition.fonts {
-            let source = font_sources.get(&font.source).ok_or_else(||
-                Error::Theme(format!("Unable to locate font handle {}", font.source))
-            )?;
-
-            let ranges = if font.characters.is_empty() {
-                &default_font_ranges
-            } else {
-                &font.characters
-            };
-
-            let font = renderer.register_font(
-                font_handle,
-                source,
-                ranges,
-                font.size,
-                display_scale
-            )?;
-
+            let font = register_font(renderer, font_handle, font, &font_sources, display_scale, font_scale)?;
             font_handle = font_handle.next();
 
             let line_height = font.line_height() / display_scale;
@@ -66,105 +64,7 @@ impl ThemeSet {
             font_handles.insert(font_id.to_string(), FontSummary { handle, line_height });
         }
 
-        let mut aliases: Vec<(String, String)> = Vec::new();
-
-        let mut images = HashMap::new();
-        for (set_id, set) in &definition.image_sets {
-            let mut images_in_set = HashMap::new();
-
-            let texture = if let Some(source) = set.source.as_ref() {
-                textures.get(source).ok_or_else(||
-                    Error::Theme(format!("Unable to locate texture {}", source))
-                )?
-            } else {
-                &textures[crate::resource::INTERNAL_SINGLE_PIX_IMAGE_ID]
-            };
-
-            let mut collected_images: Vec<(&str, &ImageDefinition)> = Vec::new();
-            let mut timed_images: Vec<(&str, &ImageDefinition)> = Vec::new();
-            let mut animated_images: Vec<(&str, &ImageDefinition)> = Vec::new();
-
-            // first parse all images without dependencies
-            for (image_id, image_def) in &set.images {
-                match &image_def.kind {
-                    ImageDefinitionKind::Animated { .. } => animated_images.push((image_id, image_def)),
-                    ImageDefinitionKind::Timed { .. } => timed_images.push((image_id, image_def)),
-                    ImageDefinitionKind::Collected { .. } => collected_images.push((image_id, image_def)),
-                    ImageDefinitionKind::Alias { from } => {
-                        let to = format!("{}/{}", set_id, image_id);
-                        let from = format!("{}/{}", set_id, from);
-                        aliases.push((to, from));
-                    },
-                    ImageDefinitionKind::Group { group_scale, fill, images } => {
-                        for (generated_id, xywh) in images {
-                            let generated_def = ImageDefinition {
-                                color: image_def.color,
-                                kind: ImageDefinitionKind::Simple {
-                                    position: [xywh[0] * group_scale[0], xywh[1] * group_scale[1]],
-                                    size: [xywh[2] * group_scale[0], xywh[3] * group_scale[1]],
-                                    fill: *fill,
-                                }
-                            };
-                            let image = Image::new(generated_id, &generated_def, texture, &images_in_set, set.scale)?;
-                            images_in_set.insert(generated_id.to_string(), image);
-                        }
-                    },
-                    _ => {
-                        let image = Image::new(&image_id, image_def, texture, &images_in_set, set.scale)?;
-                        images_in_set.insert(image_id.to_string(), image);
-                    }
-                }
-            }
-
-            // now parse collected images
-            for (id, image_def) in collected_images {
-                let image = Image::new(id, image_def, texture, &images_in_set, set.scale)?;
-                images_in_set.insert(id.to_string(), image);
-            }
-
-            // now parse timed images
-            for (id, image_def) in timed_images {
-                let image = Image::new(id, image_def, texture, &images_in_set, set.scale)?;
-                images_in_set.insert(id.to_string(), image);
-            }
-
-            // now parse animated images
-            for (id, image_def) in animated_images {
-                let image = Image::new(id, image_def, texture, &images_in_set, set.scale)?;
-                images_in_set.insert(id.to_string(), image);
-            }
-
-            // create the full hashmap with all images
-            for (id, image) in images_in_set {
-                images.insert(format!("{}/{}", set_id, id), image);
-            }
-        }
-
-        let mut images_out = Vec::new();
-        let mut image_handles = HashMap::new();
-        for (index, (id, image)) in images.into_iter().enumerate() {
-            let handle = ImageHandle { id: index };
-            images_out.push(image);
-            image_handles.insert(id, handle);
-        }
-
-        // insert empty image references for just "empty" and all sets as well
-        image_handles.insert("empty".to_string(), ImageHandle { id: images_out.len() });
-        images_out.push(Image::create_empty());
-
-        for set_id in definition.image_sets.keys() {
-            image_handles.insert(format!("{}/{}", set_id, "empty"), ImageHandle { id: images_out.len() });
-            images_out.push(Image::create_empty());
-        }
-
-        // add in aliases
-        for (to, from) in aliases {
-            let handle = *image_handles.get(&from).ok_or_else(||
-                Error::Theme(format!("Unable to locate image alias from '{}'", from))
-            )?;
-
-            image_handles.insert(to, handle);
-        }
+        let (images_out, image_handles) = build_images(definition, &textures)?;
 
         // build the set of themes
         let mut theme_handles = HashMap::new();
@@ -188,6 +88,7 @@ impl ThemeSet {
                 theme, 
                 &image_handles,
                 &font_handles,
+                &definition.palette,
             )?;
         }
 
@@ -245,16 +146,414 @@ impl ThemeSet {
             fonts,
             image_handles,
             images: images_out,
+            textures,
             theme_handles,
             themes,
+            palette: definition.palette.clone(),
+            default_font: None,
+            font_definitions: definition.fonts.clone(),
+            image_set_definitions: definition.image_sets.clone(),
         })
     }
 
+    /// Recreates the image table from `definition` and `textures`, keeping the existing widget themes.
+    /// The textures are passed to `register` once all images are built, and nothing is changed if either
+    /// step fails.  Image handles are preserved.  Images no longer in `definition`, such as those added at
+    /// runtime, keep their current version.
+    pub(crate) fn rebuild_images<F>(
+        &mut self,
+        definition: &ThemeDefinition,
+        mut textures: HashMap<String, TextureData>,
+        register: F,
+    ) -> Result<(), Error> where F: FnOnce(&HashMap<String, TextureData>) -> Result<(), Error> {
+        // build everything before registering the textures, so an invalid theme leaves the renderer unchanged
+        let (images, new_handles) = build_images(definition, &textures)?;
+        register(&textures)?;
+
+        let runtime_textures: Vec<(String, TextureData)> = self.textures.drain()
+            .filter(|(id, _)| id.starts_with(RUNTIME_TEXTURE_PREFIX))
+            .collect();
+        textures.extend(runtime_textures);
+        self.textures = textures;
+        let mut images: Vec<Option<Image>> = images.into_iter().map(Some).collect();
+
+        let mut images_out: Vec<Option<Image>> = (0..self.images.len()).map(|_| None).collect();
+        for (id, handle) in &self.image_handles {
+            // aliases share the same handle
+            if images_out[handle.id].is_some() { continue; }
+
+            images_out[handle.id] = match new_handles.get(id) {
+                None => Some(self.images[handle.id].clone()),
+                Some(new_handle) => images[new_handle.id].take(),
+            };
+        }
+
+        // every handle is referenced by at least one ID
+        self.images = images_out.into_iter().map(|image| image.unwrap_or_else(Image::create_empty)).collect();
+        self.image_set_definitions = definition.image_sets.clone();
+        Ok(())
+    }
+
+    /// Adds the image `image_id` defined by `def` to the image set `set_id`, drawing from the set's existing
+    /// texture.  If the image already exists, it is replaced in place, so widgets already using it will update.
+    pub(crate) fn add_image(&mut self, set_id: &str, image_id: &str, def: ImageDefinition) -> Result<(), Error> {
+        let set = self.image_set_definitions.get(set_id).ok_or_else(||
+            Error::Theme(format!("Unable to locate image set '{}'", set_id))
+        )?;
+
+        let texture = match &def.kind {
+            // gradients only use vertex colors, so they always draw from the blank texture
+            ImageDefinitionKind::Gradient { .. } => None,
+            _ => set.source.as_ref(),
+        };
+        let texture = match texture {
+            None => &self.textures[crate::resource::INTERNAL_SINGLE_PIX_IMAGE_ID],
+            Some(source) => self.textures.get(source).ok_or_else(||
+                Error::Theme(format!("Unable to locate texture {}", source))
+            )?,
+        };
+
+        let prefix = format!("{}/", set_id);
+        let others: HashMap<String, Image> = self.image_handles.iter()
+            .filter_map(|(id, handle)| id.strip_prefix(&prefix).map(|id| (id.to_string(), self.images[handle.id].clone())))
+            .collect();
+
+        let image = match &def.kind {
+            ImageDefinitionKind::Alias { .. } | ImageDefinitionKind::Group { .. } => return Err(
+                Error::Theme(format!("Image '{}' can not be added at runtime.  Alias and Group images are not supported.", image_id))
+            ),
+            _ => Image::new(image_id, &def, texture, &others, set.scale)?,
+        };
+
+        self.insert_image(set_id, image_id, image);
+
+        if let Some(set) = self.image_set_definitions.get_mut(set_id) {
+            set.images.insert(image_id.to_string(), def);
+        }
+        Ok(())
+    }
+
+    /// Registers the RGBA pixel `data` of the specified `dimensions` as a new texture with the `renderer`,
+    /// and adds a `Simple` image `image_id` covering all of it to the image set `set_id`.  If the image
+    /// already exists and was added this way, its texture is replaced in place.
+    pub(crate) fn add_texture_image<R: Renderer>(
+        &mut self,
+        renderer: &mut R,
+        set_id: &str,
+        image_id: &str,
+        data: &[u8],
+        dimensions: (u32, u32),
+    ) -> Result<(), Error> {
+        let scale = self.image_set_definitions.get(set_id).ok_or_else(||
+            Error::Theme(format!("Unable to locate image set '{}'", set_id))
+        )?.scale;
+
+        let texture_id = format!("{}{}/{}", RUNTIME_TEXTURE_PREFIX, set_id, image_id);
+        let handle = match self.textures.get(&texture_id) {
+            Some(texture) => texture.handle(),
+            None => {
+                let last = self.textures.values().map(|texture| texture.handle()).max_by_key(|handle| handle.id());
+                last.map_or_else(TextureHandle::default, |handle| handle.next())
+            }
+        };
+
+        let texture = renderer.register_texture(handle, data, dimensions)?;
+
+        let def = ImageDefinition {
+            color: Color::default(),
+            kind: ImageDefinitionKind::Simple {
+                position: [0, 0],
+                size: [dimensions.0, dimensions.1],
+                fill: ImageFill::None,
+                flip_horizontal: false,
+                flip_vertical: false,
+            },
+        };
+        let image = Image::new(image_id, &def, &texture, &HashMap::new(), scale)?;
+
+        self.textures.insert(texture_id, texture);
+        self.insert_image(set_id, image_id, image);
+        Ok(())
+    }
+
+    /// The handles of all textures added with [`add_texture_image`](#method.add_texture_image)
+    pub(crate) fn runtime_texture_handles(&self) -> Vec<TextureHandle> {
+        self.textures.iter()
+            .filter(|(id, _)| id.starts_with(RUNTIME_TEXTURE_PREFIX))
+            .map(|(_, texture)| texture.handle())
+            .collect()
+    }
+
+    // adds `image` as `set_id/image_id`, replacing any existing image in place so widgets already using it update
+    fn insert_image(&mut self, set_id: &str, image_id: &str, image: Image) {
+        let full_id = format!("{}/{}", set_id, image_id);
+        match self.image_handles.get(&full_id) {
+            Some(handle) => self.images[handle.id] = image,
+            None => {
+                self.image_handles.insert(full_id, ImageHandle { id: self.images.len() });
+                self.images.push(image);
+            }
+        }
+    }
+
+    /// Converts `rect`, in the logical coordinates of the `Simple` image `image_id`, to the texture handle and
+    /// the texel position and size of the region, making sure the region lies within the image.
+    pub(crate) fn image_texel_region(
+        &self,
+        image_id: &str,
+        rect: Rect,
+    ) -> Result<(TextureHandle, Texels, Texels), Error> {
+        let handle = self.find_image(Some(image_id)).ok_or_else(||
+            Error::Theme(format!("Unable to locate image '{}'", image_id))
+        )?;
+        let image = &self.images[handle.id];
+
+        let texture = self.textures.values().find(|texture| texture.handle() == image.texture()).ok_or_else(||
+            Error::Theme(format!("Unable to locate the texture for image '{}'", image_id))
+        )?;
+
+        let (origin, size, base_size) = image.texel_region(texture).ok_or_else(||
+            Error::Theme(format!("Image '{}' is not a Simple image", image_id))
+        )?;
+
+        if base_size.x <= 0.0 || base_size.y <= 0.0 {
+            return Err(Error::Theme(format!("Image '{}' has no area", image_id)));
+        }
+
+        // convert from the image's logical size to texels
+        let to_texels = |point: Point| {
+            Point::new(point.x * size.0 as f32 / base_size.x, point.y * size.1 as f32 / base_size.y)
+        };
+        let pos = to_texels(rect.pos);
+        let end = to_texels(rect.pos + rect.size);
+        if pos.x < 0.0 || pos.y < 0.0 || end.x < pos.x || end.y < pos.y ||
+            end.x > size.0 as f32 + 0.5 || end.y > size.1 as f32 + 0.5 {
+            return Err(Error::Theme(format!("Region {:?} is outside of image '{}'", rect, image_id)));
+        }
+
+        let pos = (pos.x.round() as u32, pos.y.round() as u32);
+        let end = ((end.x.round() as u32).min(size.0), (end.y.round() as u32).min(size.1));
+        Ok((image.texture(), (origin.0 + pos.0, origin.1 + pos.1), (end.0 - pos.0, end.1 - pos.1)))
+    }
+
+    /// Removes the image with the specified `full_id`.  Widgets still referring to the image will draw nothing.
+    pub(crate) fn remove_image(&mut self, full_id: &str) -> Result<(), Error> {
+        let handle = *self.image_handles.get(full_id).ok_or_else(||
+            Error::Theme(format!("Unable to locate image '{}'", full_id))
+        )?;
+        self.images[handle.id] = Image::create_empty();
+
+        // aliases share the handle of the image they point to, so remove them as well
+        let ids: Vec<String> = self.image_handles.iter()
+            .filter(|(_, other)| other.id == handle.id)
+            .map(|(id, _)| id.to_string())
+            .collect();
+
+        for id in ids {
+            self.image_handles.remove(&id);
+
+            if let Some((set_id, image_id)) = id.split_once('/') {
+                if let Some(set) = self.image_set_definitions.get_mut(set_id) {
+                    set.images.remove(image_id);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Registers all fonts in `definition` with the `renderer` again, keeping the existing
+    /// font handles and widget themes.  Every font present in the current theme set must
+    /// still be present.
+    pub(crate) fn rebuild_fonts<R: Renderer>(
+        &mut self,
+        definition: &ThemeDefinition,
+        font_sources: HashMap<(String, FontStyle), FontSource>,
+        renderer: &mut R,
+        display_scale: f32,
+        font_scale: f32,
+    ) -> Result<(), Error> {
+        let mut fonts: Vec<Option<Font>> = (0..self.fonts.len()).map(|_| None).collect();
+        let mut font_handles = self.font_handles.clone();
+        for (font_id, summary) in font_handles.iter_mut() {
+            let font_def = definition.fonts.get(font_id).ok_or_else(|| Error::Theme(
+                format!("Font '{}' was removed.  A full rebuild is required.", font_id)
+            ))?;
+
+            let font = register_font(renderer, summary.handle, font_def, &font_sources, display_scale, font_scale)?;
+            summary.line_height = font.line_height() / display_scale;
+            fonts[summary.handle.id()] = Some(font);
+        }
+
+        // widget themes store a copy of the font line height
+        for theme in self.themes.iter_mut() {
+            if let Some(font) = theme.font.as_mut() {
+                if let Some(summary) = font_handles.values().find(|summary| summary.handle == font.handle) {
+                    *font = *summary;
+                }
+            }
+        }
+
+        self.fonts = fonts.into_iter().map(|font| font.unwrap()).collect();
+        self.font_handles = font_handles;
+        self.font_definitions = definition.fonts.clone();
+        Ok(())
+    }
+
+    /// Reconstructs a `ThemeDefinition` from this theme set.  Widget themes are exported
+    /// with all `from` references already resolved, and image and font handles are
+    /// converted back to their IDs.
+    pub(crate) fn to_definition(&self) -> ThemeDefinition {
+        let (image_ids, font_ids) = self.asset_ids();
+
+        let widgets = self.themes.iter()
+            .filter(|theme| theme.parent_handle.is_none() && theme.handle.id != 0)
+            .map(|theme| (theme.id.to_string(), self.widget_definition(theme, &image_ids, &font_ids)))
+            .collect();
+
+        ThemeDefinition {
+            palette: self.palette.clone(),
+            fonts: self.font_definitions.clone(),
+            image_sets: self.image_set_definitions.clone(),
+            widgets,
+        }
+    }
+
+    /// Returns the resolved definition of the theme with the specified full `id`, without its children
+    pub(crate) fn theme_definition(&self, id: &str) -> Option<WidgetThemeDefinition> {
+        let theme = self.theme(id)?;
+        let (image_ids, font_ids) = self.asset_ids();
+        let mut definition = self.widget_definition(theme, &image_ids, &font_ids);
+        definition.children.clear();
+        Some(definition)
+    }
+
+    // maps image and font handles back to their IDs
+    fn asset_ids(&self) -> (HashMap<usize, &str>, HashMap<FontHandle, &str>) {
+        // several IDs may map to the same image via aliases, so pick the shortest
+        let mut image_ids: HashMap<usize, &str> = HashMap::new();
+        for (id, handle) in &self.image_handles {
+            let entry = image_ids.entry(handle.id).or_insert(id);
+            if (id.len(), id.as_str()) < (entry.len(), *entry) {
+                *entry = id;
+            }
+        }
+
+        let font_ids: HashMap<FontHandle, &str> = self.font_handles.iter()
+            .map(|(id, summary)| (summary.handle, id.as_str()))
+            .collect();
+
+        (image_ids, font_ids)
+    }
+
+    fn widget_definition(
+        &self,
+        theme: &WidgetTheme,
+        image_ids: &HashMap<usize, &str>,
+        font_ids: &HashMap<FontHandle, &str>,
+    ) -> WidgetThemeDefinition {
+        let image_id = |handle: Option<ImageHandle>| {
+            handle.and_then(|handle| image_ids.get(&handle.id)).map(|id| id.to_string())
+        };
+
+        // colors resolved from the palette are exported as references
+        let color = |field: PaletteField, color: Option<Color>| {
+            match theme.palette_refs.iter().find(|(ref_field, _)| *ref_field == field) {
+                Some((_, name)) => Some(ThemeColor::Palette(name.to_string())),
+                None => color.map(ThemeColor::Color),
+            }
+        };
+
+        let children = theme.children.iter().map(|handle| {
+            let child = &self.themes[handle.id as usize];
+            (child.id.to_string(), self.widget_definition(child, image_ids, font_ids))
+        }).collect();
+
+        WidgetThemeDefinition {
+            from: None,
+            text: theme.text.clone(),
+            font: theme.font.and_then(|font| font_ids.get(&font.handle)).map(|id| id.to_string()),
+            background: image_id(theme.background),
+            foreground: image_id(theme.foreground),
+            background_tint: color(PaletteField::BackgroundTint, theme.background_tint),
+            foreground_tint: color(PaletteField::ForegroundTint, theme.foreground_tint),
+            tooltip: theme.tooltip.clone(),
+            tooltip_theme: theme.tooltip_theme.clone(),
+            cursor: theme.cursor,
+            text_color: color(PaletteField::TextColor, theme.text_color),
+            text_shadow_offset: theme.text_shadow_offset,
+            text_shadow_color: color(PaletteField::TextShadowColor, theme.text_shadow_color),
+            wants_mouse: theme.wants_mouse,
+            wants_scroll: theme.wants_scroll,
+            wants_keyboard: theme.wants_keyboard,
+            enabled: theme.enabled,
+            text_align: theme.text_align,
+            truncate: theme.truncate,
+            ellipsis: theme.ellipsis.clone(),
+            pos: theme.pos,
+            screen_pos: theme.screen_pos,
+            size: theme.size,
+            size_from: None,
+            width_from: theme.width_from,
+            height_from: theme.height_from,
+            border: theme.border,
+            align: theme.align,
+            child_align: theme.child_align,
+            layout: theme.layout,
+            layout_spacing: theme.layout_spacing,
+            layout_free_relative: theme.layout_free_relative,
+            z_order: theme.z_order,
+            z_index: theme.z_index,
+            aspect_ratio: theme.aspect_ratio,
+            split_divider_width: theme.split_divider_width,
+            scrollbar_width: theme.scrollbar_width,
+            scrollbar_min_handle_size: theme.scrollbar_min_handle_size,
+            scrollbar_vertical: theme.scrollbar_vertical,
+            scrollbar_horizontal: theme.scrollbar_horizontal,
+            size_active: theme.size_active,
+            size_hover: theme.size_hover,
+            state_overrides: theme.state_overrides.clone(),
+            custom_floats: theme.custom_floats.clone(),
+            children,
+        }
+    }
+
+    /// Returns the palette color with the specified `name`, if it exists
+    pub(crate) fn palette_color(&self, name: &str) -> Option<Color> {
+        self.palette.get(name).copied()
+    }
+
+    /// Sets the palette color with the specified `name` to `color`, adding it if it does not exist.
+    /// Only the widget themes referencing the color are updated.
+    pub(crate) fn set_palette_color(&mut self, name: &str, color: Color) {
+        self.palette.insert(name.to_string(), color);
+
+        for theme in self.themes.iter_mut() {
+            let fields: Vec<PaletteField> = theme.palette_refs.iter()
+                .filter(|(_, ref_name)| ref_name == name)
+                .map(|(field, _)| *field)
+                .collect();
+
+            for field in fields {
+                *theme.palette_color_mut(field) = Some(color);
+            }
+        }
+    }
+
     pub(crate) fn default_theme(&self) -> &WidgetTheme {
         // This is always manually created
         &self.themes[0]
     }
 
+    /// Returns an iterator over the full IDs of every widget theme, including child themes
+    pub fn all_widget_ids(&self) -> impl Iterator<Item = &str> {
+        self.themes.iter().map(|theme| theme.full_id.as_str())
+    }
+
+    /// Returns an iterator over the full IDs of every widget theme starting with `prefix`
+    pub fn widgets_with_prefix<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = &'a str> {
+        self.all_widget_ids().filter(move |id| id.starts_with(prefix))
+    }
+
     pub fn theme(&self, id: &str) -> Option<&WidgetTheme> {
         self.handle(id).map(|handle| &self.themes[handle.id as usize])
     }
@@ -263,6 +562,29 @@ impl ThemeSet {
         &self.fonts[handle.id()]
     }
 
+    /// Returns the font with the smallest line height, used for debug drawing
+    pub(crate) fn debug_font(&self) -> Option<&Font> {
+        self.fonts.iter().min_by(|a, b| a.line_height().partial_cmp(&b.line_height()).unwrap_or(std::cmp::Ordering::Equal))
+    }
+
+    /// Sets the font used by widgets whose theme does not specify a font.  The font must
+    /// be present in this theme set.
+    pub(crate) fn set_default_font(&mut self, id: Option<String>) -> Result<(), Error> {
+        if let Some(id) = &id {
+            if !self.font_handles.contains_key(id) {
+                return Err(Error::Theme(format!("Default font '{}' is not defined in the theme", id)));
+            }
+        }
+
+        self.default_font = id;
+        Ok(())
+    }
+
+    /// Returns the font used by widgets whose theme does not specify a font, if any.
+    pub(crate) fn default_font(&self) -> Option<FontSummary> {
+        self.default_font.as_ref().and_then(|id| self.font_handles.get(id)).copied()
+    }
+
     pub fn find_font(&self, id: Option<&str>) -> Option<FontSummary> {
         match id {
             None => None,
@@ -298,6 +620,197 @@ impl ThemeSet {
     pub fn handle(&self, id: &str) -> Option<WidgetThemeHandle> {
         self.theme_handles.get(id).cloned()
     }
+
+    /// Checks the specified theme `definition` for all broken references to images, fonts, textures, and
+    /// font sources, as well as invalid or circular `from` references.  Unlike building the `ThemeSet`,
+    /// which stops at the first problem, this returns every problem found.
+    pub(crate) fn validate(
+        definition: &ThemeDefinition,
+        textures: &HashMap<String, TextureData>,
+        font_sources: &HashMap<(String, FontStyle), FontSource>,
+    ) -> Vec<ThemeValidationError> {
+        use ThemeValidationError::*;
+        let mut errors = Vec::new();
+
+        for (font_id, font) in &definition.fonts {
+            if !font_sources.contains_key(&(font.source.to_string(), font.style)) {
+                errors.push(MissingFontSource {
+                    font: font_id.to_string(),
+                    source: font.source.to_string(),
+                    style: font.style,
+                });
+            }
+        }
+
+        for (set_id, set) in &definition.image_sets {
+            if let Some(source) = set.source.as_ref() {
+                if !textures.contains_key(source) {
+                    errors.push(MissingTexture { image_set: set_id.to_string(), source: source.to_string() });
+                }
+            }
+        }
+
+        errors.extend(ThemeSet::validate_references(definition));
+        errors
+    }
+
+    /// Checks the specified theme `definition` for broken references between its own images, fonts, and
+    /// widget themes, without regard to the registered textures and font sources.
+    pub(crate) fn validate_references(definition: &ThemeDefinition) -> Vec<ThemeValidationError> {
+        use ThemeValidationError::*;
+        let mut errors = Vec::new();
+
+        // gather all image IDs, including those generated by image groups
+        let mut image_ids: HashSet<String> = HashSet::new();
+        image_ids.insert("empty".to_string());
+        for (set_id, set) in &definition.image_sets {
+            image_ids.insert(format!("{}/empty", set_id));
+            for (image_id, image) in &set.images {
+                match &image.kind {
+                    ImageDefinitionKind::Group { images, .. } => {
+                        for generated_id in images.keys() {
+                            image_ids.insert(format!("{}/{}", set_id, generated_id));
+                        }
+                    },
+                    _ => { image_ids.insert(format!("{}/{}", set_id, image_id)); },
+                }
+            }
+        }
+
+        for (set_id, set) in &definition.image_sets {
+            for (image_id, image) in &set.images {
+                let refs: Vec<&String> = match &image.kind {
+                    ImageDefinitionKind::Alias { from } => vec![from],
+                    ImageDefinitionKind::Collected { sub_images } => sub_images.keys().collect(),
+                    ImageDefinitionKind::Timed { frames, .. } => frames.iter().collect(),
+                    ImageDefinitionKind::Animated { states, .. } => states.values().collect(),
+                    _ => Vec::new(),
+                };
+
+                for image_ref in refs {
+                    let full_ref = format!("{}/{}", set_id, image_ref);
+                    if !image_ids.contains(&full_ref) {
+                        errors.push(MissingImage { user: format!("{}/{}", set_id, image_id), image: full_ref });
+                    }
+                }
+            }
+        }
+
+        // flatten the widget tree into full IDs
+        let mut widgets: HashMap<String, (&WidgetThemeDefinition, Option<String>)> = HashMap::new();
+        let mut to_visit: Vec<(String, &WidgetThemeDefinition, Option<String>)> = definition.widgets.iter()
+            .map(|(id, def)| (id.to_string(), def, None)).collect();
+        while let Some((full_id, def, parent)) = to_visit.pop() {
+            for (child_id, child) in &def.children {
+                to_visit.push((format!("{}/{}", full_id, child_id), child, Some(full_id.to_string())));
+            }
+            widgets.insert(full_id, (def, parent));
+        }
+
+        let resolve = |from: &str, parent: &Option<String>| -> Option<String> {
+            if widgets.contains_key(from) { return Some(from.to_string()); }
+            let parent = parent.as_ref()?;
+            let relative = format!("{}/{}", parent, from);
+            if widgets.contains_key(&relative) { Some(relative) } else { None }
+        };
+
+        for (full_id, (def, parent)) in &widgets {
+            for image in [&def.background, &def.foreground].iter().filter_map(|image| image.as_ref()) {
+                if !image_ids.contains(image) {
+                    errors.push(MissingImage { user: full_id.to_string(), image: image.to_string() });
+                }
+            }
+
+            if let Some(font) = def.font.as_ref() {
+                if !definition.fonts.contains_key(font) {
+                    errors.push(MissingFont { widget: full_id.to_string(), font: font.to_string() });
+                }
+            }
+
+            let colors = [&def.text_color, &def.text_shadow_color, &def.background_tint, &def.foreground_tint];
+            for color in colors.iter().filter_map(|color| color.as_ref()) {
+                if let ThemeColor::Palette(name) = color {
+                    if !definition.palette.contains_key(name) {
+                        errors.push(MissingPaletteColor { widget: full_id.to_string(), color: name.to_string() });
+                    }
+                }
+            }
+
+            let from = match def.from.as_ref() {
+                None => continue,
+                Some(from) => from,
+            };
+
+            let mut next = match resolve(from, parent) {
+                None => {
+                    errors.push(InvalidFrom { widget: full_id.to_string(), from: from.to_string() });
+                    continue;
+                }, Some(next) => next,
+            };
+
+            // follow the chain of from references, looking for a cycle back to this widget
+            let mut visited = HashSet::new();
+            loop {
+                if &next == full_id {
+                    errors.push(CircularFrom { widget: full_id.to_string() });
+                    break;
+                }
+                if !visited.insert(next.to_string()) { break; }
+
+                let (next_def, next_parent) = &widgets[&next];
+                next = match next_def.from.as_ref().and_then(|from| resolve(from, next_parent)) {
+                    None => break,
+                    Some(next) => next,
+                };
+            }
+        }
+
+        errors
+    }
+}
+
+/// A problem with a theme definition, found by [`ThemeSet::validate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ThemeValidationError {
+    /// A widget or image references an image that does not exist
+    MissingImage { user: String, image: String },
+
+    /// A widget references a font that does not exist
+    MissingFont { widget: String, font: String },
+
+    /// A widget references a palette color that does not exist
+    MissingPaletteColor { widget: String, color: String },
+
+    /// A font references a font source that was not registered
+    MissingFontSource { font: String, source: String, style: FontStyle },
+
+    /// An image set references a texture that was not registered
+    MissingTexture { image_set: String, source: String },
+
+    /// A widget's `from` does not refer to any theme
+    InvalidFrom { widget: String, from: String },
+
+    /// A widget's chain of `from` references leads back to itself
+    CircularFrom { widget: String },
+}
+
+impl fmt::Display for ThemeValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ThemeValidationError::*;
+        match self {
+            MissingImage { user, image } => write!(f, "Unable to locate image '{}' for '{}'", image, user),
+            MissingFont { widget, font } => write!(f, "Unable to locate font '{}' for widget '{}'", font, widget),
+            MissingPaletteColor { widget, color } => write!(
+                f, "Unable to locate palette color '{}' for widget '{}'", color, widget
+            ),
+            MissingFontSource { font, source, style } => write!(
+                f, "Unable to locate font source '{}' with style {:?} for font '{}'", source, style, font
+            ),
+            MissingTexture { image_set, source } => write!(f, "Unable to locate texture '{}' for image set '{}'", source, image_set),
+            InvalidFrom { widget, from } => write!(f, "Invalid from theme '{}' in '{}'", from, widget),
+            CircularFrom { widget } => write!(f, "Circular from reference in '{}'", widget),
+        }
+    }
 }
 
 fn resolve_from(
@@ -322,11 +835,182 @@ fn resolve_from(
     None
 }
 
+/// Creates the image table for all image sets in `definition`, along with the handles for each image ID
+fn build_images(
+    definition: &ThemeDefinition,
+    textures: &HashMap<String, TextureData>,
+) -> Result<(Vec<Image>, HashMap<String, ImageHandle>), Error> {
+    let mut aliases: Vec<(String, String)> = Vec::new();
+
+    let mut images = HashMap::new();
+    for (set_id, set) in &definition.image_sets {
+        let mut images_in_set = HashMap::new();
+
+        let texture = if let Some(source) = set.source.as_ref() {
+            textures.get(source).ok_or_else(||
+                Error::Theme(format!("Unable to locate texture {}", source))
+            )?
+        } else {
+            &textures[crate::resource::INTERNAL_SINGLE_PIX_IMAGE_ID]
+        };
+
+        let mut collected_images: Vec<(&str, &ImageDefinition)> = Vec::new();
+        let mut timed_images: Vec<(&str, &ImageDefinition)> = Vec::new();
+        let mut animated_images: Vec<(&str, &ImageDefinition)> = Vec::new();
+
+        // first parse all images without dependencies
+        for (image_id, image_def) in &set.images {
+            match &image_def.kind {
+                ImageDefinitionKind::Animated { .. } => animated_images.push((image_id, image_def)),
+                ImageDefinitionKind::Timed { .. } => timed_images.push((image_id, image_def)),
+                ImageDefinitionKind::Collected { .. } => collected_images.push((image_id, image_def)),
+                ImageDefinitionKind::Alias { from } => {
+                    let to = format!("{}/{}", set_id, image_id);
+                    let from = format!("{}/{}", set_id, from);
+                    aliases.push((to, from));
+                },
+                ImageDefinitionKind::Group { group_scale, fill, images } => {
+                    for (generated_id, xywh) in images {
+                        let generated_def = ImageDefinition {
+                            color: image_def.color,
+                            kind: ImageDefinitionKind::Simple {
+                                position: [xywh[0] * group_scale[0], xywh[1] * group_scale[1]],
+                                size: [xywh[2] * group_scale[0], xywh[3] * group_scale[1]],
+                                fill: *fill,
+                                flip_horizontal: false,
+                                flip_vertical: false,
+                            }
+                        };
+                        let image = Image::new(generated_id, &generated_def, texture, &images_in_set, set.scale)?;
+                        images_in_set.insert(generated_id.to_string(), image);
+                    }
+                },
+                ImageDefinitionKind::Gradient { .. } => {
+                    // gradients only use vertex colors, so they always draw from the blank texture
+                    let blank = &textures[crate::resource::INTERNAL_SINGLE_PIX_IMAGE_ID];
+                    let image = Image::new(&image_id, image_def, blank, &images_in_set, set.scale)?;
+                    images_in_set.insert(image_id.to_string(), image);
+                },
+                _ => {
+                    let image = Image::new(&image_id, image_def, texture, &images_in_set, set.scale)?;
+                    images_in_set.insert(image_id.to_string(), image);
+                }
+            }
+        }
+
+        // now parse collected images
+        for (id, image_def) in collected_images {
+            let image = Image::new(id, image_def, texture, &images_in_set, set.scale)?;
+            images_in_set.insert(id.to_string(), image);
+        }
+
+        // now parse timed images
+        for (id, image_def) in timed_images {
+            let image = Image::new(id, image_def, texture, &images_in_set, set.scale)?;
+            images_in_set.insert(id.to_string(), image);
+        }
+
+        // now parse animated images
+        for (id, image_def) in animated_images {
+            let image = Image::new(id, image_def, texture, &images_in_set, set.scale)?;
+            images_in_set.insert(id.to_string(), image);
+        }
+
+        // create the full hashmap with all images
+        for (id, image) in images_in_set {
+            images.insert(format!("{}/{}", set_id, id), image);
+        }
+    }
+
+    let mut images_out = Vec::new();
+    let mut image_handles = HashMap::new();
+    for (index, (id, image)) in images.into_iter().enumerate() {
+        let handle = ImageHandle { id: index };
+        images_out.push(image);
+        image_handles.insert(id, handle);
+    }
+
+    // insert empty image references for just "empty" and all sets as well
+    image_handles.insert("empty".to_string(), ImageHandle { id: images_out.len() });
+    images_out.push(Image::create_empty());
+
+    for set_id in definition.image_sets.keys() {
+        image_handles.insert(format!("{}/{}", set_id, "empty"), ImageHandle { id: images_out.len() });
+        images_out.push(Image::create_empty());
+    }
+
+    // add in aliases
+    for (to, from) in aliases {
+        let handle = *image_handles.get(&from).ok_or_else(||
+            Error::Theme(format!("Unable to locate image alias from '{}'", from))
+        )?;
+
+        image_handles.insert(to, handle);
+    }
+
+    Ok((images_out, image_handles))
+}
+
+/// Registers the font described by `font` with the `renderer`, using the specified `handle`
+fn register_font<R: Renderer>(
+    renderer: &mut R,
+    handle: FontHandle,
+    font: &FontDefinition,
+    font_sources: &HashMap<(String, FontStyle), FontSource>,
+    display_scale: f32,
+    font_scale: f32,
+) -> Result<Font, Error> {
+    let source = font_sources.get(&(font.source.to_string(), font.style)).ok_or_else(||
+        Error::Theme(format!("Unable to locate font handle {} with style {:?}", font.source, font.style))
+    )?;
+
+    let default_font_ranges = [
+        CharacterRange { lower: 32, upper: 126 },
+        CharacterRange { lower: 161, upper: 255 },
+    ];
+
+    let ranges: &[CharacterRange] = if font.characters.is_empty() {
+        &default_font_ranges
+    } else {
+        &font.characters
+    };
+
+    let size = font.size * font_scale;
+
+    if font.sdf {
+        renderer.register_sdf_font(
+            handle,
+            source,
+            ranges,
+            size,
+            display_scale,
+            font.smoothness.unwrap_or(DEFAULT_SDF_SMOOTHNESS),
+        )
+    } else {
+        renderer.register_font(
+            handle,
+            source,
+            ranges,
+            size,
+            display_scale
+        )
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
 pub struct WidgetThemeHandle {
     id: u64,
 }
 
+/// A widget theme color field which may be resolved from the palette
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum PaletteField {
+    TextColor,
+    TextShadowColor,
+    BackgroundTint,
+    ForegroundTint,
+}
+
 #[derive(Clone)]
 pub struct WidgetTheme {
     from: Option<String>,
@@ -338,16 +1022,26 @@ pub struct WidgetTheme {
 
     pub text: Option<String>,
     pub text_color: Option<Color>,
+    pub text_shadow_offset: Option<Point>,
+    pub text_shadow_color: Option<Color>,
     pub font: Option<FontSummary>,
     pub background: Option<ImageHandle>,
     pub foreground: Option<ImageHandle>,
+    pub background_tint: Option<Color>,
+    pub foreground_tint: Option<Color>,
     pub tooltip: Option<String>,
+    pub tooltip_theme: Option<String>,
+    pub cursor: Option<CursorIcon>,
 
     // all fields are options instead of using default so
     // we can detect when to override them
     pub wants_mouse: Option<bool>,
     pub wants_scroll: Option<bool>,
+    pub wants_keyboard: Option<bool>,
+    pub enabled: Option<bool>,
     pub text_align: Option<Align>,
+    pub truncate: Option<bool>,
+    pub ellipsis: Option<String>,
     pub pos: Option<Point>,
     pub screen_pos: Option<Point>,
     pub size: Option<Point>,
@@ -358,9 +1052,24 @@ pub struct WidgetTheme {
     pub child_align: Option<Align>,
     pub layout: Option<Layout>,
     pub layout_spacing: Option<Point>,
+    pub layout_free_relative: Option<bool>,
+    pub z_order: Option<i32>,
+    pub z_index: Option<i32>,
+    pub aspect_ratio: Option<f32>,
+    pub split_divider_width: Option<f32>,
+    pub scrollbar_width: Option<f32>,
+    pub scrollbar_min_handle_size: Option<f32>,
+    pub scrollbar_vertical: Option<ShowElement>,
+    pub scrollbar_horizontal: Option<ShowElement>,
+    pub size_active: Option<Point>,
+    pub size_hover: Option<Point>,
+    pub state_overrides: HashMap<AnimState, WidgetThemeOverride>,
     pub children: Vec<WidgetThemeHandle>,
 
     pub custom_floats: HashMap<String, f32>,
+
+    // the color fields resolved from the palette, and the names they were resolved from
+    palette_refs: Vec<(PaletteField, String)>,
 }
 
 impl WidgetTheme {
@@ -373,13 +1082,23 @@ impl WidgetTheme {
             handle,
             text: None,
             text_color: None,
+            text_shadow_offset: None,
+            text_shadow_color: None,
             font: None,
             background: None,
             foreground: None,
+            background_tint: None,
+            foreground_tint: None,
             tooltip: None,
+            tooltip_theme: None,
+            cursor: None,
             wants_mouse: None,
             wants_scroll: None,
+            wants_keyboard: None,
+            enabled: None,
             text_align: None,
+            truncate: None,
+            ellipsis: None,
             pos: None,
             screen_pos: None,
             size: None,
@@ -390,11 +1109,64 @@ impl WidgetTheme {
             child_align: None,
             layout: None,
             layout_spacing: None,
+            layout_free_relative: None,
+            z_order: None,
+            z_index: None,
+            aspect_ratio: None,
+            split_divider_width: None,
+            scrollbar_width: None,
+            scrollbar_min_handle_size: None,
+            scrollbar_vertical: None,
+            scrollbar_horizontal: None,
+            size_active: None,
+            size_hover: None,
+            state_overrides: HashMap::new(),
             children: Vec::new(),
             custom_floats: HashMap::new(),
+            palette_refs: Vec::new(),
         }
     }
 
+    fn palette_color_mut(&mut self, field: PaletteField) -> &mut Option<Color> {
+        match field {
+            PaletteField::TextColor => &mut self.text_color,
+            PaletteField::TextShadowColor => &mut self.text_shadow_color,
+            PaletteField::BackgroundTint => &mut self.background_tint,
+            PaletteField::ForegroundTint => &mut self.foreground_tint,
+        }
+    }
+
+    // copies the palette reference for `field` from `from`, if any, when the color itself is inherited
+    fn inherit_palette_ref(&mut self, from: &WidgetTheme, field: PaletteField) {
+        if let Some((_, name)) = from.palette_refs.iter().find(|(ref_field, _)| *ref_field == field) {
+            self.palette_refs.push((field, name.to_string()));
+        }
+    }
+
+    /// Returns true if this theme changes any fields based on the widget's `AnimState`
+    pub(crate) fn has_state_overrides(&self) -> bool {
+        self.size_active.is_some() || self.size_hover.is_some() || !self.state_overrides.is_empty()
+    }
+
+    /// Returns the fields to override for a widget in the specified `state`.  An exact match
+    /// in `state_overrides` is used first, then `size_hover` if the widget is hovered or pressed,
+    /// and finally `size_active` if the widget is active.
+    pub(crate) fn state_override(&self, state: AnimState) -> Option<WidgetThemeOverride> {
+        if let Some(state_override) = self.state_overrides.get(&state) {
+            return Some(*state_override);
+        }
+
+        let size = if state.contains(AnimStateKey::Hover) || state.contains(AnimStateKey::Pressed) {
+            self.size_hover.or(if state.contains(AnimStateKey::Active) { self.size_active } else { None })
+        } else if state.contains(AnimStateKey::Active) {
+            self.size_active
+        } else {
+            None
+        };
+
+        size.map(|size| WidgetThemeOverride { size: Some(size), ..Default::default() })
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn create(
         parent_id: &str,
@@ -406,6 +1178,7 @@ impl WidgetTheme {
         def: &WidgetThemeDefinition,
         images: &HashMap<String, ImageHandle>,
         fonts: &HashMap<String, FontSummary>,
+        palette: &HashMap<String, Color>,
     ) -> Result<WidgetThemeHandle, Error> {
         if id.contains('/') {
             return Err(
@@ -445,6 +1218,25 @@ impl WidgetTheme {
             None
         };
 
+        let mut palette_refs = Vec::new();
+        let mut color = |field: PaletteField, color: &Option<ThemeColor>| -> Result<Option<Color>, Error> {
+            match color {
+                None => Ok(None),
+                Some(ThemeColor::Color(color)) => Ok(Some(*color)),
+                Some(ThemeColor::Palette(name)) => {
+                    let color = palette.get(name).ok_or_else(|| Error::Theme(
+                        format!("Unable to locate palette color '{}' for widget '{}'", name, parent_id)
+                    ))?;
+                    palette_refs.push((field, name.to_string()));
+                    Ok(Some(*color))
+                }
+            }
+        };
+        let text_color = color(PaletteField::TextColor, &def.text_color)?;
+        let text_shadow_color = color(PaletteField::TextShadowColor, &def.text_shadow_color)?;
+        let background_tint = color(PaletteField::BackgroundTint, &def.background_tint)?;
+        let foreground_tint = color(PaletteField::ForegroundTint, &def.foreground_tint)?;
+
         let (width_from, height_from) = if let Some((width_from, height_from)) = def.size_from {
             (Some(width_from), Some(height_from))
         } else {
@@ -460,14 +1252,24 @@ impl WidgetTheme {
             id,
             full_id: parent_id.to_string(),
             text: def.text.clone(),
-            text_color: def.text_color,
+            text_color,
+            text_shadow_offset: def.text_shadow_offset,
+            text_shadow_color,
             font,
             background,
             foreground,
+            background_tint,
+            foreground_tint,
             tooltip: def.tooltip.clone(),
+            tooltip_theme: def.tooltip_theme.clone(),
+            cursor: def.cursor,
             wants_mouse: def.wants_mouse,
             wants_scroll: def.wants_scroll,
+            wants_keyboard: def.wants_keyboard,
+            enabled: def.enabled,
             text_align: def.text_align,
+            truncate: def.truncate,
+            ellipsis: def.ellipsis.clone(),
             pos: def.pos,
             screen_pos: def.screen_pos,
             size: def.size,
@@ -478,8 +1280,21 @@ impl WidgetTheme {
             border: def.border,
             layout: def.layout,
             layout_spacing: def.layout_spacing,
+            layout_free_relative: def.layout_free_relative,
+            z_order: def.z_order,
+            z_index: def.z_index,
+            aspect_ratio: def.aspect_ratio,
+            split_divider_width: def.split_divider_width,
+            scrollbar_width: def.scrollbar_width,
+            scrollbar_min_handle_size: def.scrollbar_min_handle_size,
+            scrollbar_vertical: def.scrollbar_vertical,
+            scrollbar_horizontal: def.scrollbar_horizontal,
+            size_active: def.size_active,
+            size_hover: def.size_hover,
+            state_overrides: def.state_overrides.clone(),
             children: Vec::new(),
             custom_floats: def.custom_floats.clone(),
+            palette_refs,
         };
 
         themes.push(theme);
@@ -495,7 +1310,8 @@ impl WidgetTheme {
                 themes,
                 child_def,
                 images,
-                fonts
+                fonts,
+                palette,
             )?;
             children.push(child);
         }
@@ -522,14 +1338,31 @@ fn merge_from(
     let to_children = to.children.clone();
 
     // preserve any as-yet unresolve child from refs
-    to.from = from.from;
+    to.from = from.from.clone();
 
     if to.wants_mouse.is_none() { to.wants_mouse = from.wants_mouse; }
     if to.wants_scroll.is_none() { to.wants_scroll = from.wants_scroll; }
+    if to.wants_keyboard.is_none() { to.wants_keyboard = from.wants_keyboard; }
+    if to.enabled.is_none() { to.enabled = from.enabled; }
     if to.font.is_none() { to.font = from.font; }
     if to.background.is_none() { to.background = from.background; }
     if to.foreground.is_none() { to.foreground = from.foreground; }
+    if to.background_tint.is_none() {
+        to.background_tint = from.background_tint;
+        to.inherit_palette_ref(&from, PaletteField::BackgroundTint);
+    }
+    if to.foreground_tint.is_none() {
+        to.foreground_tint = from.foreground_tint;
+        to.inherit_palette_ref(&from, PaletteField::ForegroundTint);
+    }
     if to.text_align.is_none() { to.text_align = from.text_align; }
+    if to.text_shadow_offset.is_none() { to.text_shadow_offset = from.text_shadow_offset; }
+    if to.text_shadow_color.is_none() {
+        to.text_shadow_color = from.text_shadow_color;
+        to.inherit_palette_ref(&from, PaletteField::TextShadowColor);
+    }
+    if to.truncate.is_none() { to.truncate = from.truncate; }
+    if to.ellipsis.is_none() { to.ellipsis = from.ellipsis.clone(); }
     if to.pos.is_none() { to.pos = from.pos; }
     if to.screen_pos.is_none() { to.screen_pos = from.screen_pos; }
     if to.size.is_none() { to.size = from.size; }
@@ -540,8 +1373,25 @@ fn merge_from(
     if to.child_align.is_none() { to.child_align = from.child_align; }
     if to.layout.is_none() { to.layout = from.layout; }
     if to.layout_spacing.is_none() { to.layout_spacing = from.layout_spacing; }
+    if to.layout_free_relative.is_none() { to.layout_free_relative = from.layout_free_relative; }
+    if to.z_order.is_none() { to.z_order = from.z_order; }
+    if to.z_index.is_none() { to.z_index = from.z_index; }
+    if to.aspect_ratio.is_none() { to.aspect_ratio = from.aspect_ratio; }
+    if to.split_divider_width.is_none() { to.split_divider_width = from.split_divider_width; }
+    if to.scrollbar_width.is_none() { to.scrollbar_width = from.scrollbar_width; }
+    if to.scrollbar_min_handle_size.is_none() { to.scrollbar_min_handle_size = from.scrollbar_min_handle_size; }
+    if to.scrollbar_vertical.is_none() { to.scrollbar_vertical = from.scrollbar_vertical; }
+    if to.scrollbar_horizontal.is_none() { to.scrollbar_horizontal = from.scrollbar_horizontal; }
     if to.text.is_none() { to.text = from.text.clone(); }
     if to.tooltip.is_none() { to.tooltip = from.tooltip.clone(); }
+    if to.tooltip_theme.is_none() { to.tooltip_theme = from.tooltip_theme.clone(); }
+    if to.cursor.is_none() { to.cursor = from.cursor; }
+    if to.size_active.is_none() { to.size_active = from.size_active; }
+    if to.size_hover.is_none() { to.size_hover = from.size_hover; }
+
+    for (state, value) in from.state_overrides.iter() {
+        to.state_overrides.entry(*state).or_insert(*value);
+    }
 
     for (id, value) in from.custom_floats.iter() {
         match to.custom_floats.entry(id.to_string()) {
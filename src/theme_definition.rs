@@ -1,19 +1,32 @@
 use std::collections::{HashMap, hash_map::Entry};
 use std::fmt;
+use std::ops::Mul;
 
 use serde::{Serialize, Deserialize, Deserializer, Serializer, de::{self, Visitor}};
 
-use crate::{Border, Point};
+use crate::{Border, CursorIcon, Error, Point, ShowElement};
+use crate::theme::ThemeSet;
 
-#[derive(Serialize, Deserialize)]
+/// A complete theme, consisting of fonts, image sets, and widget themes.  This is normally
+/// deserialized from theme files, but may also be constructed in code with a
+/// [`ThemeDefinitionBuilder`](struct.ThemeDefinitionBuilder.html) and registered with
+/// [`ContextBuilder::register_theme_definition`](struct.ContextBuilder.html#method.register_theme_definition).
+#[derive(Serialize, Deserialize, Default)]
 #[serde(deny_unknown_fields)]
 pub struct ThemeDefinition {
+    /// Named colors which widget color fields may reference as `$name`
+    #[serde(default)]
+    pub palette: HashMap<String, Color>,
+
+    /// The fonts in this theme, keyed by their ID
     #[serde(default)]
     pub fonts: HashMap<String, FontDefinition>,
 
+    /// The image sets in this theme, keyed by their ID
     #[serde(default)]
     pub image_sets: HashMap<String, ImageSet>,
 
+    /// The top level widget themes in this theme, keyed by their ID
     #[serde(default)]
     pub widgets: HashMap<String, WidgetThemeDefinition>,
 }
@@ -23,6 +36,16 @@ impl ThemeDefinition {
     pub fn merge(&mut self, other: ThemeDefinition) {
         use Entry::*;
 
+        for (id, color) in other.palette {
+            match self.palette.entry(id) {
+                Occupied(mut entry) => {
+                    log::warn!("Overwriting palette color '{}'", entry.key());
+                    entry.insert(color);
+                },
+                Vacant(entry) => { entry.insert(color); }
+            }
+        }
+
         for (id, font) in other.fonts {
             match self.fonts.entry(id) {
                 Occupied(mut entry) => {
@@ -51,70 +74,422 @@ impl ThemeDefinition {
             }
         }
     }
+
+    /**
+    Merges `base` and `other` into a new theme definition, without modifying either.  Unlike
+    [`merge`](#method.merge), which replaces widget themes with the same ID entirely, widget themes
+    present in both are merged recursively.  Each field set in `other` overrides the same field in
+    `base`, and children are merged in the same way.  Palette colors and fonts from `other` override
+    those with the same ID in `base`.  Image sets are combined, but an image set ID present in both
+    definitions is an error.
+
+    # Example
+    ```
+    use thyme::{ThemeDefinition, ThemeDefinitionBuilder, WidgetThemeDefinition, Point};
+
+    let base = ThemeDefinitionBuilder::new()
+        .widget("button", WidgetThemeDefinition { size: Some(Point::new(100.0, 30.0)), ..Default::default() })
+        .build().unwrap();
+    let other = ThemeDefinitionBuilder::new()
+        .widget("button", WidgetThemeDefinition { text: Some("Ok".to_string()), ..Default::default() })
+        .build().unwrap();
+
+    let merged = ThemeDefinition::merged(base, other).unwrap();
+    assert_eq!(merged.widgets["button"].size, Some(Point::new(100.0, 30.0)));
+    assert_eq!(merged.widgets["button"].text.as_deref(), Some("Ok"));
+    ```
+    **/
+    pub fn merged(base: ThemeDefinition, other: ThemeDefinition) -> Result<ThemeDefinition, Error> {
+        let mut result = base;
+
+        for id in other.image_sets.keys() {
+            if result.image_sets.contains_key(id) {
+                return Err(Error::Theme(format!("Image set '{}' is defined in both themes", id)));
+            }
+        }
+        result.image_sets.extend(other.image_sets);
+        result.palette.extend(other.palette);
+        result.fonts.extend(other.fonts);
+
+        for (id, widget) in other.widgets {
+            let widget = match result.widgets.remove(&id) {
+                None => widget,
+                Some(base_widget) => merge_widget_definitions(base_widget, widget)?,
+            };
+            result.widgets.insert(id, widget);
+        }
+
+        Ok(result)
+    }
 }
 
-#[derive(Serialize, Deserialize)]
+/// Merges the fields set in `other` over those in `base`, recursively merging children
+fn merge_widget_definitions(
+    base: WidgetThemeDefinition,
+    other: WidgetThemeDefinition,
+) -> Result<WidgetThemeDefinition, Error> {
+    use serde_yaml::Value;
+    let to_value = |def: WidgetThemeDefinition| serde_yaml::to_value(def).map_err(|e| Error::Serde(e.to_string()));
+
+    let (mut base, other) = match (to_value(base)?, to_value(other)?) {
+        (Value::Mapping(base), Value::Mapping(other)) => (base, other),
+        _ => return Err(Error::Serde("Widget theme definitions must serialize as mappings".to_string())),
+    };
+    merge_widget_values(&mut base, other);
+
+    serde_yaml::from_value(Value::Mapping(base)).map_err(|e| Error::Serde(e.to_string()))
+}
+
+/// Merges the serialized widget definition `other` into `base`.  Unset fields in `other` are ignored, while
+/// the `children`, `custom_floats`, and `state_overrides` maps are merged key by key
+fn merge_widget_values(base: &mut serde_yaml::Mapping, other: serde_yaml::Mapping) {
+    use serde_yaml::Value;
+
+    for (key, value) in other {
+        if value.is_null() { continue; }
+
+        let merge_children = key.as_str() == Some("children");
+        let merge_map = merge_children ||
+            key.as_str() == Some("custom_floats") || key.as_str() == Some("state_overrides");
+
+        match (base.get_mut(&key), value) {
+            (Some(Value::Mapping(base_map)), Value::Mapping(other_map)) if merge_map => {
+                for (child_key, child_value) in other_map {
+                    match (base_map.get_mut(&child_key), child_value) {
+                        (Some(Value::Mapping(base_child)), Value::Mapping(other_child)) if merge_children => {
+                            merge_widget_values(base_child, other_child);
+                        },
+                        (_, child_value) => { base_map.insert(child_key, child_value); },
+                    }
+                }
+            },
+            (_, value) => { base.insert(key, value); },
+        }
+    }
+}
+
+/// A builder for constructing a [`ThemeDefinition`](struct.ThemeDefinition.html) in code, rather than
+/// deserializing it from a theme file.  References between widgets, images, and fonts are checked
+/// when calling [`build`](#method.build).
+/// # Example
+/// ```
+/// use thyme::{ThemeDefinitionBuilder, WidgetThemeDefinition, Point};
+///
+/// let theme = ThemeDefinitionBuilder::new()
+///     .font("medium", "roboto", 20.0)
+///     .image_set("gui", "gui")
+///     .simple_image("gui", "button", [0, 0], [32, 32])
+///     .widget("button", WidgetThemeDefinition {
+///         font: Some("medium".to_string()),
+///         background: Some("gui/button".to_string()),
+///         size: Some(Point::new(100.0, 30.0)),
+///         ..Default::default()
+///     })
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Default)]
+pub struct ThemeDefinitionBuilder {
+    definition: ThemeDefinition,
+    errors: Vec<String>,
+}
+
+impl ThemeDefinitionBuilder {
+    /// Creates a new, empty `ThemeDefinitionBuilder`
+    pub fn new() -> ThemeDefinitionBuilder {
+        ThemeDefinitionBuilder::default()
+    }
+
+    /// Adds a font with the specified `id`, using the registered font `source` at the specified `size`.
+    pub fn font<T: Into<String>, U: Into<String>>(mut self, id: T, source: U, size: f32) -> ThemeDefinitionBuilder {
+        self.definition.fonts.insert(id.into(), FontDefinition {
+            source: source.into(),
+            size,
+            style: FontStyle::default(),
+            characters: Vec::new(),
+            sdf: false,
+            smoothness: None,
+        });
+        self
+    }
+
+    /// Adds an empty image set with the specified `id`, using the registered texture `source`.
+    /// Images may then be added to the set with [`simple_image`](#method.simple_image) or
+    /// [`image`](#method.image).
+    pub fn image_set<T: Into<String>, U: Into<String>>(mut self, id: T, source: U) -> ThemeDefinitionBuilder {
+        self.definition.image_sets.insert(id.into(), ImageSet {
+            source: Some(source.into()),
+            scale: 1.0,
+            images: HashMap::new(),
+        });
+        self
+    }
+
+    /// Adds a `Simple` image with the specified `image_id` to the image set `set_id`, at the specified
+    /// texel `pos` and `size` within the set's texture.
+    pub fn simple_image<T: Into<String>>(
+        self,
+        set_id: &str,
+        image_id: T,
+        pos: [u32; 2],
+        size: [u32; 2],
+    ) -> ThemeDefinitionBuilder {
+        self.image(set_id, image_id, ImageDefinition {
+            color: Color::default(),
+            kind: ImageDefinitionKind::Simple {
+                position: pos,
+                size,
+                fill: ImageFill::None,
+                flip_horizontal: false,
+                flip_vertical: false,
+            },
+        })
+    }
+
+    /// Adds the image `def` with the specified `image_id` to the image set `set_id`.  The image set
+    /// must have previously been added with [`image_set`](#method.image_set).
+    pub fn image<T: Into<String>>(mut self, set_id: &str, image_id: T, def: ImageDefinition) -> ThemeDefinitionBuilder {
+        let image_id = image_id.into();
+        match self.definition.image_sets.get_mut(set_id) {
+            None => self.errors.push(format!("Unable to locate image set '{}' for image '{}'", set_id, image_id)),
+            Some(set) => { set.images.insert(image_id, def); }
+        }
+        self
+    }
+
+    /// Adds the named `color` to the palette, which widget color fields may then reference
+    /// with [`ThemeColor::Palette`](enum.ThemeColor.html).
+    pub fn palette_color<T: Into<String>>(mut self, name: T, color: Color) -> ThemeDefinitionBuilder {
+        self.definition.palette.insert(name.into(), color);
+        self
+    }
+
+    /// Adds the top level widget theme `def` with the specified `id`.
+    pub fn widget<T: Into<String>>(mut self, id: T, def: WidgetThemeDefinition) -> ThemeDefinitionBuilder {
+        self.definition.widgets.insert(id.into(), def);
+        self
+    }
+
+    /// Consumes this builder, producing the `ThemeDefinition`.  Returns an error if any widget references
+    /// an image, font, or palette color that was not added, if any `from` reference is invalid, or if an image was added
+    /// to a missing image set.
+    pub fn build(self) -> Result<ThemeDefinition, Error> {
+        let mut errors = self.errors;
+        errors.extend(ThemeSet::validate_references(&self.definition).iter().map(|error| error.to_string()));
+
+        if !errors.is_empty() {
+            return Err(Error::Theme(format!("Theme definition is invalid:\n{}", errors.join("\n"))));
+        }
+
+        Ok(self.definition)
+    }
+}
+
+/// A subset of the [`WidgetThemeDefinition`](struct.WidgetThemeDefinition.html) fields, which replace
+/// the corresponding fields of a widget's theme while the widget is in a particular
+/// [`AnimState`](struct.AnimState.html).  Any unset fields keep the widget's normal theme value.
+#[derive(Serialize, Deserialize, Copy, Clone, Default, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct WidgetThemeOverride {
+    /// Replaces the widget's `pos`
+    pub pos: Option<Point>,
+
+    /// Replaces the widget's `size`
+    pub size: Option<Point>,
+
+    /// Replaces the widget's `border`
+    pub border: Option<Border>,
+}
+
+/// The theme for a single widget and its children.  See [`the crate root`](index.html) for a
+/// description of each field.  All fields default to unset.
+#[derive(Serialize, Deserialize, Default)]
 #[serde(deny_unknown_fields)]
 pub struct WidgetThemeDefinition {
+    /// The ID of another widget theme this theme inherits from
     pub from: Option<String>,
 
+    /// The text shown by the widget
     pub text: Option<String>,
+
+    /// The ID of the font used to draw the text
     pub font: Option<String>,
+
+    /// The ID of the image drawn behind the widget's text
     pub background: Option<String>,
+
+    /// The ID of the image drawn in front of the widget's text
     pub foreground: Option<String>,
+
+    /// The color the background image is multiplied by
+    pub background_tint: Option<ThemeColor>,
+
+    /// The color the foreground image is multiplied by
+    pub foreground_tint: Option<ThemeColor>,
+
+    /// The tooltip text shown while the widget is hovered
+    #[serde(alias = "tooltip_text")]
     pub tooltip: Option<String>,
 
+    /// The theme of the tooltip, instead of the default `tooltip` theme
+    pub tooltip_theme: Option<String>,
+
+    /// The mouse cursor shown while the widget is hovered
+    pub cursor: Option<CursorIcon>,
+
     // all fields are options instead of using default so
     // we can detect when to override them
-    pub text_color: Option<Color>,
+
+    /// The color of the widget's text
+    pub text_color: Option<ThemeColor>,
+
+    /// The offset of the text shadow, in logical pixels
+    pub text_shadow_offset: Option<Point>,
+
+    /// The color of the text shadow
+    pub text_shadow_color: Option<ThemeColor>,
+
+    /// Whether the widget receives mouse events
     pub wants_mouse: Option<bool>,
+
+    /// Whether the widget receives mouse scroll events
     pub wants_scroll: Option<bool>,
+
+    /// Whether the widget may take keyboard focus
+    pub wants_keyboard: Option<bool>,
+
+    /// Whether the widget is enabled
+    pub enabled: Option<bool>,
+
+    /// The alignment of the text within the widget
     pub text_align: Option<Align>,
+
+    /// Whether text that does not fit on one line is truncated
+    pub truncate: Option<bool>,
+
+    /// The text shown in place of truncated characters
+    pub ellipsis: Option<String>,
+
+    /// The position of the widget relative to its alignment within the parent
     pub pos: Option<Point>,
+
+    /// The absolute position of the widget on the screen
     pub screen_pos: Option<Point>,
+
+    /// The size of the widget, interpreted according to `width_from` and `height_from`
     pub size: Option<Point>,
+
+    /// Shorthand for setting both `width_from` and `height_from`
     pub size_from: Option<(WidthRelative, HeightRelative)>,
+
+    /// What the widget's width is computed relative to
     pub width_from: Option<WidthRelative>,
+
+    /// What the widget's height is computed relative to
     pub height_from: Option<HeightRelative>,
+
+    /// The inner border between the widget's edges and its content
     pub border: Option<Border>,
+
+    /// The alignment of the widget within its parent
     pub align: Option<Align>,
+
+    /// The default alignment of this widget's children
     pub child_align: Option<Align>,
+
+    /// How this widget lays out its children
     pub layout: Option<Layout>,
+
+    /// The spacing between children in the layout
     pub layout_spacing: Option<Point>,
 
+    /// Whether child positions are relative to this widget in a `Free` layout
+    pub layout_free_relative: Option<bool>,
+
+    /// The draw order of the render group started by this widget
+    pub z_order: Option<i32>,
+
+    /// The draw order of this widget relative to its siblings
+    pub z_index: Option<i32>,
+
+    /// The ratio of width to height the widget is constrained to
+    pub aspect_ratio: Option<f32>,
+
+    /// The width of the divider in a split pane
+    pub split_divider_width: Option<f32>,
+
+    /// The width of a scrollpane's scrollbars
+    pub scrollbar_width: Option<f32>,
+
+    /// The minimum length of a scrollbar's handle
+    pub scrollbar_min_handle_size: Option<f32>,
+
+    /// When a scrollpane shows its vertical scrollbar
+    pub scrollbar_vertical: Option<ShowElement>,
+
+    /// When a scrollpane shows its horizontal scrollbar
+    pub scrollbar_horizontal: Option<ShowElement>,
+
+    /// The size of the widget while it is active
+    pub size_active: Option<Point>,
+
+    /// The size of the widget while it is hovered or pressed
+    pub size_hover: Option<Point>,
+
+    /// Overrides of the position, size, and border for specific animation states
+    #[serde(default)]
+    pub state_overrides: HashMap<AnimState, WidgetThemeOverride>,
+
+    /// Custom values for use by specialized widgets
     #[serde(default)]
     pub custom_floats: HashMap<String, f32>,
 
+    /// The themes of this widget's children, keyed by child ID
     #[serde(default)]
     pub children: HashMap<String, WidgetThemeDefinition>,
 }
 
-#[derive(Serialize, Deserialize)]
+/// A set of images sharing a single texture `source`.
+#[derive(Serialize, Deserialize, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct ImageSet {
+    /// The ID of the registered image the set is drawn from, or a single pixel if unset
     pub source: Option<String>,
 
+    /// The factor all images in the set are pre-scaled by
     #[serde(default="f32_one")]
     pub scale: f32,
 
+    /// The images in the set, keyed by image ID
     pub images: HashMap<String, ImageDefinition>,
 }
 
 fn f32_one() -> f32 { 1.0 }
 
-#[derive(Serialize, Deserialize)]
+/// The definition of a single image within an image set.  This is normally read from the theme,
+/// but may also be created directly and passed to
+/// [`Context::add_image_at_runtime`](struct.Context.html#method.add_image_at_runtime).
+#[derive(Serialize, Deserialize, Clone)]
 pub struct ImageDefinition {
+    /// The color the image is multiplied by when drawn
     #[serde(default)]
     pub color: Color,
 
+    /// The type of image and its associated data
     #[serde(flatten)]
     pub kind: ImageDefinitionKind,
 }
 
+/// How an image is drawn when its size is larger than its base size.
 #[derive(Serialize, Deserialize, Copy, Clone)]
 pub enum ImageFill {
+    /// The image is drawn once at its base size
     None,
+
+    /// The image is stretched to fill the area
     Stretch,
+
+    /// The image is repeated to fill the area
     Repeat,
 }
 
@@ -124,56 +499,196 @@ impl Default for ImageFill {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+/// The direction a Gradient image blends from its start color to its end color.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum GradientDirection {
+    /// Blend from the left edge to the right edge
+    #[default]
+    Horizontal,
+
+    /// Blend from the top edge to the bottom edge
+    Vertical,
+
+    /// Blend from the top left corner to the bottom right corner
+    Diagonal,
+}
+
+/// One of the images making up a `Collected` image.
+#[derive(Serialize, Deserialize, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct CollectedSubImage {
+    /// The position of the sub image within the collected image
     pub position: [i32; 2],
+
+    /// The size of the sub image
     pub size: [i32; 2],
 }
 
-#[derive(Serialize, Deserialize)]
+/// The type of an [`ImageDefinition`](struct.ImageDefinition.html).  See [`the crate root`](index.html)
+/// for a description of each type of image.
+#[derive(Serialize, Deserialize, Clone)]
 #[serde(untagged)]
 #[serde(deny_unknown_fields)]
 pub enum ImageDefinitionKind {
+    /// A shorthand for multiple `Simple` images, which are expanded when the theme is loaded
     Group {
+        /// The scale factor that each image's position and size is multiplied by
         group_scale: [u32; 2],
+
+        /// The fill used by each image in the group
         fill: ImageFill,
+
+        /// The x, y, width, and height of each image, keyed by image ID
         images: HashMap<String, [u32; 4]>,
     },
+
+    /// An alias to another image in the same image set
     Alias {
+        /// The ID of the aliased image
         from: String,
     },
+
+    /// An image made up of one or more arbitrarily positioned sub images
     Collected {
+        /// The sub images, keyed by the ID of the image each one references
         sub_images: HashMap<String, CollectedSubImage>,
     },
+
+    /// An image made up of a 3 by 3 grid of equally sized cells
     Composed {
+        /// The position of the top left corner of the image, in pixels
         position: [u32; 2],
+
+        /// The size of a single grid cell, in pixels
         grid_size: [u32; 2],
+
+        /// Whether the image is mirrored horizontally when drawn
+        #[serde(default)]
+        flip_horizontal: bool,
+
+        /// Whether the image is mirrored vertically when drawn
+        #[serde(default)]
+        flip_vertical: bool,
     },
+
+    /// An image made up of a 1 by 3 grid of equally sized cells
     ComposedVertical {
+        /// The position of the top left corner of the image, in pixels
         position: [u32; 2],
+
+        /// The size of a single grid cell, in pixels
         grid_size_vert: [u32; 2],
+
+        /// Whether the image is mirrored horizontally when drawn
+        #[serde(default)]
+        flip_horizontal: bool,
+
+        /// Whether the image is mirrored vertically when drawn
+        #[serde(default)]
+        flip_vertical: bool,
     },
+
+    /// An image made up of a 3 by 1 grid of equally sized cells
     ComposedHorizontal {
+        /// The position of the top left corner of the image, in pixels
         position: [u32; 2],
+
+        /// The size of a single grid cell, in pixels
         grid_size_horiz: [u32; 2],
+
+        /// Whether the image is mirrored horizontally when drawn
+        #[serde(default)]
+        flip_horizontal: bool,
+
+        /// Whether the image is mirrored vertically when drawn
+        #[serde(default)]
+        flip_vertical: bool,
     },
+
+    /// A composed image where each border may have a different width
+    ComposedAsymmetric {
+        /// The position of the top left corner of the image, in pixels
+        position: [u32; 2],
+
+        /// The size of the entire image, in pixels
+        size: [u32; 2],
+
+        /// The width of the left border, in pixels
+        left: u32,
+
+        /// The width of the right border, in pixels
+        right: u32,
+
+        /// The height of the top border, in pixels
+        top: u32,
+
+        /// The height of the bottom border, in pixels
+        bottom: u32,
+    },
+
+    /// A single rectangular region of the image source
     Simple {
+        /// The position of the top left corner of the image, in pixels
         position: [u32; 2],
+
+        /// The size of the image, in pixels
         size: [u32; 2],
 
+        /// How the image is drawn when its area is larger than its size
         #[serde(default)]
         fill: ImageFill,
+
+        /// Whether the image is mirrored horizontally when drawn
+        #[serde(default)]
+        flip_horizontal: bool,
+
+        /// Whether the image is mirrored vertically when drawn
+        #[serde(default)]
+        flip_vertical: bool,
+    },
+
+    /// A blend between two colors that does not reference the image source
+    Gradient {
+        /// The color at the start of the gradient
+        start_color: Color,
+
+        /// The color at the end of the gradient
+        end_color: Color,
+
+        /// The direction the gradient blends in
+        #[serde(default)]
+        direction: GradientDirection,
     },
+
+    /// An image that cycles through several frames on a timer
     Timed {
+        /// How long each frame is shown for, in milliseconds
         frame_time_millis: u32,
+
+        /// The IDs of the images shown as each frame, in order
         frames: Vec<String>,
 
+        /// Whether the frames play only once, equivalent to a `repeat_count` of 1
         #[serde(default)]
         once: bool,
+
+        /// The number of times the frames play before holding the final frame
+        #[serde(default)]
+        repeat_count: Option<u32>,
+
+        /// Whether each cycle plays the frames forwards and then backwards
+        #[serde(default)]
+        reverse: bool,
     },
+
+    /// An image that shows one of several images based on the widget's `AnimState`
     Animated {
+        /// The ID of the image shown for each state
         states: HashMap<AnimState, String>,
+
+        /// The time to cross-fade between images when the state changes, in milliseconds
+        #[serde(default)]
+        transition_ms: u32,
     }
 }
 
@@ -188,7 +703,7 @@ pub enum ImageDefinitionKind {
 /// to concatenate multiple states, and whitespace is ignored.  The [`Normal`](enum.AnimStateKey.html#normal)
 /// key is special and can only be present by itself.
 /// `AnimState`s are used in Animated images in order to pick a particular image from a set.
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct AnimState {
     keys: [AnimStateKey; 4],
 }
@@ -311,7 +826,15 @@ impl<'de> Visitor<'de> for AnimStateVisitor {
                     add_if_not_already_present(&mut keys, key_index, AnimStateKey::Active)?;
                 }
                 _ => {
-                    return Err(E::custom(format!("Unable to parse AnimStateKey from {}", key_id)));
+                    let index = parse_custom_key(key_id)
+                        .ok_or_else(|| E::custom(format!("Unable to parse AnimStateKey from {}", key_id)))?;
+                    if index < AnimStateKey::FIRST_CUSTOM {
+                        return Err(E::custom(format!(
+                            "{} is reserved.  Custom AnimStateKeys must be at least Custom({})",
+                            key_id, AnimStateKey::FIRST_CUSTOM
+                        )));
+                    }
+                    add_if_not_already_present(&mut keys, key_index, AnimStateKey::Custom(index))?;
                 }
             }
         }
@@ -322,6 +845,12 @@ impl<'de> Visitor<'de> for AnimStateVisitor {
     }
 }
 
+/// Parses the index `N` from a key of the form `Custom(N)`
+fn parse_custom_key(key_id: &str) -> Option<u8> {
+    let index = key_id.strip_prefix("Custom")?.trim().strip_prefix('(')?.strip_suffix(')')?;
+    index.trim().parse().ok()
+}
+
 fn add_if_not_already_present<E: de::Error>(keys: &mut [AnimStateKey; 4], max_index: usize, key: AnimStateKey) -> Result<(), E> {
     for other in keys.iter().copied().take(max_index) {
         if other == key {
@@ -340,23 +869,26 @@ impl<'de> Deserialize<'de> for AnimState {
 
 impl Serialize for AnimState {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        let mut first = true;
         let mut val = String::new();
         for key in self.keys.iter() {
-            if !first {
-                val.push('+');
-            }
-
             use AnimStateKey::*;
-            match key {
-                Normal => (),
-                Hover => val.push_str("Hover"),
-                Pressed => val.push_str("Pressed"),
-                Disabled => val.push_str("Disabled"),
-                Active => val.push_str("Active"),
+            let key_id = match key {
+                Normal => continue,
+                Hover => "Hover".to_string(),
+                Pressed => "Pressed".to_string(),
+                Disabled => "Disabled".to_string(),
+                Active => "Active".to_string(),
+                Custom(index) => format!("Custom({})", index),
+            };
+
+            if !val.is_empty() {
+                val.push('+');
             }
+            val.push_str(&key_id);
+        }
 
-            first = false;
+        if val.is_empty() {
+            val.push_str("Normal");
         }
 
         serializer.serialize_str(&val)
@@ -385,13 +917,24 @@ pub enum AnimStateKey {
 
     /// The widget is activated.
     Active,
+
+    /// A state defined by a library or application built on Thyme, such as `Focused` or `Loading`.
+    /// In the theme, this is specified as `Custom(N)`.  `Custom(0)` through `Custom(3)` are reserved
+    /// for future use by Thyme and may not be used in themes.
+    Custom(u8),
+}
+
+impl AnimStateKey {
+    /// The lowest index that may be used for a [`Custom`](#variant.Custom) key.  Lower indices are
+    /// reserved for future use by Thyme.
+    pub const FIRST_CUSTOM: u8 = 4;
 }
 
 /// The Layout direction for a widget's children.
 ///
 /// This only has effect if the child widget does not manually specify an alignment.
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
-#[serde(deny_unknown_fields)]
+#[serde(from = "LayoutDefinition", into = "LayoutDefinition")]
 pub enum Layout {
     /// Layout children horizontally, from left to right
     Horizontal,
@@ -400,8 +943,58 @@ pub enum Layout {
     Vertical,
 
     /// Don't layout children in any order.  Children must specify manual alignments to
-    /// avoid overlap.
+    /// avoid overlap.  Child positions are relative to this widget's inner top left corner, and
+    /// may use `align` to anchor to other corners.  If this widget sets `layout_free_relative`
+    /// to false, children with a `pos` are instead placed at that absolute screen position.
     Free,
+
+    /// Layout children in a grid with the specified number of `columns`, from left to right
+    /// and then top to bottom.  Each column is as wide as its widest child, and each row
+    /// is as tall as its tallest child.  In the theme, this is specified as `Grid: 3`.
+    Grid {
+        /// The number of columns in the grid
+        columns: usize
+    },
+
+    /// Layout children horizontally, from left to right, starting a new row whenever
+    /// the next child would not fit in the parent's inner width.  Each row is as tall
+    /// as its tallest child.
+    Wrap,
+}
+
+// The serialized form of `Layout`, allowing grids to be specified as `Grid: 3`
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+enum LayoutDefinition {
+    Horizontal,
+    Vertical,
+    Free,
+    Grid(usize),
+    Wrap,
+}
+
+impl From<LayoutDefinition> for Layout {
+    fn from(def: LayoutDefinition) -> Layout {
+        match def {
+            LayoutDefinition::Horizontal => Layout::Horizontal,
+            LayoutDefinition::Vertical => Layout::Vertical,
+            LayoutDefinition::Free => Layout::Free,
+            LayoutDefinition::Grid(columns) => Layout::Grid { columns },
+            LayoutDefinition::Wrap => Layout::Wrap,
+        }
+    }
+}
+
+impl From<Layout> for LayoutDefinition {
+    fn from(layout: Layout) -> LayoutDefinition {
+        match layout {
+            Layout::Horizontal => LayoutDefinition::Horizontal,
+            Layout::Vertical => LayoutDefinition::Vertical,
+            Layout::Free => LayoutDefinition::Free,
+            Layout::Grid { columns } => LayoutDefinition::Grid(columns),
+            Layout::Wrap => LayoutDefinition::Wrap,
+        }
+    }
 }
 
 impl Default for Layout {
@@ -444,6 +1037,17 @@ pub enum Align {
 
     /// Top Right alignment
     TopRight,
+
+    /// Fills the parent's inner width and height, overriding the widget's size
+    Stretch,
+
+    /// Fills the parent's inner width, overriding the widget's width.  The widget is
+    /// aligned vertically according to the parent's `child_align`
+    StretchHorizontal,
+
+    /// Fills the parent's inner height, overriding the widget's height.  The widget is
+    /// aligned horizontally according to the parent's `child_align`
+    StretchVertical,
 }
 
 impl Default for Align {
@@ -466,24 +1070,103 @@ impl Align {
             BotRight => Point { x: size.x, y: size.y },
             TopLeft => Point { x: 0.0, y: 0.0 },
             TopRight => Point { x: size.x, y: 0.0 },
+            Stretch | StretchHorizontal | StretchVertical => Point { x: 0.0, y: 0.0 },
+        }
+    }
+
+    /// Returns whether this is one of the stretch variants, which fill the parent on at least one axis
+    pub fn is_stretch(self) -> bool {
+        matches!(self, Align::Stretch | Align::StretchHorizontal | Align::StretchVertical)
+    }
+
+    pub(crate) fn stretches_horizontal(self) -> bool {
+        matches!(self, Align::Stretch | Align::StretchHorizontal)
+    }
+
+    pub(crate) fn stretches_vertical(self) -> bool {
+        matches!(self, Align::Stretch | Align::StretchVertical)
+    }
+
+    /// Returns the alignment used to position a widget with this alignment, within a parent
+    /// with the specified `child_align`.  Stretched axes are anchored to the left or top edge,
+    /// while other axes follow `child_align`.  Non stretch alignments are returned unchanged.
+    pub(crate) fn stretch_anchor(self, child_align: Align) -> Align {
+        use Align::*;
+        match self {
+            Stretch => TopLeft,
+            StretchHorizontal => match child_align {
+                Left | Center | Right => Left,
+                Bot | BotLeft | BotRight => BotLeft,
+                _ => TopLeft,
+            },
+            StretchVertical => match child_align {
+                Top | Center | Bot => Top,
+                Right | TopRight | BotRight => TopRight,
+                _ => TopLeft,
+            },
+            _ => self,
         }
     }
 }
 
+/// A font in the theme, referencing a registered font `source` at a given `size`.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct FontDefinition {
+    /// The ID of the registered font source
     pub source: String,
+
+    /// The font size, in logical pixels
     pub size: f32,
 
+    /// The style variant of the font source to use
+    #[serde(default)]
+    pub style: FontStyle,
+
+    /// The ranges of characters to rasterize, or the default range if empty
     #[serde(default)]
     pub characters: Vec<CharacterRange>,
+
+    /// Whether the font atlas stores a signed distance field for each glyph
+    #[serde(default)]
+    pub sdf: bool,
+
+    /// How soft the edges of distance field glyphs are
+    #[serde(default)]
+    pub smoothness: Option<f32>,
+}
+
+/// The style variant of a font source.  Each style of a given font source is registered
+/// separately via [`ContextBuilder`](struct.ContextBuilder.html), and fonts in the theme
+/// select the variant to use with their `style` field.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum FontStyle {
+    /// The regular style
+    #[serde(alias = "normal")]
+    #[default]
+    Normal,
+
+    /// The bold style
+    #[serde(alias = "bold")]
+    Bold,
+
+    /// The italic style
+    #[serde(alias = "italic")]
+    Italic,
+
+    /// The combined bold and italic style
+    #[serde(alias = "bold_italic")]
+    BoldItalic,
 }
 
+/// An inclusive range of characters to rasterize for a font.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct CharacterRange {
+    /// The first character in the range
     pub lower: u32,
+
+    /// The last character in the range
     pub upper: u32,
 }
 
@@ -582,12 +1265,30 @@ impl Default for Color {
     fn default() -> Self { Color::white() }
 }
 
+impl Mul<Color> for Color {
+    type Output = Color;
+
+    /// Multiplies the components of the two colors, as is done when tinting an image
+    fn mul(self, other: Color) -> Color {
+        let mul = |a: u8, b: u8| ((a as u16 * b as u16 + 127) / 255) as u8;
+        Color { r: mul(self.r, other.r), g: mul(self.g, other.g), b: mul(self.b, other.b) }
+    }
+}
+
 impl Into<[f32; 3]> for Color {
     fn into(self) -> [f32; 3] {
         [self.r as f32 / 255.0, self.g as f32 / 255.0, self.b as f32 / 255.0]
     }
 }
 
+impl From<Color> for [f32; 4] {
+    /// Converts the color to normalized RGBA components.  Colors are always fully opaque, so
+    /// the alpha component is `1.0`
+    fn from(color: Color) -> [f32; 4] {
+        [color.r as f32 / 255.0, color.g as f32 / 255.0, color.b as f32 / 255.0, 1.0]
+    }
+}
+
 struct ColorVisitor;
 
 impl<'de> Visitor<'de> for ColorVisitor {
@@ -646,6 +1347,56 @@ fn hex_str_to_color_component<E: de::Error>(input: &str) -> Result<u8, E> {
     Ok(c)
 }
 
+/// A color in a widget theme, either given directly or as a reference to a named color in the theme's
+/// `palette`.  In the theme, palette references are written as the name prefixed with `$`, i.e. `"$primary"`.
+/// See [`Context::set_palette_color`](struct.Context.html#method.set_palette_color).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThemeColor {
+    /// A fixed color
+    Color(Color),
+
+    /// A reference to the palette color with the specified name
+    Palette(String),
+}
+
+impl From<Color> for ThemeColor {
+    fn from(color: Color) -> ThemeColor {
+        ThemeColor::Color(color)
+    }
+}
+
+struct ThemeColorVisitor;
+
+impl<'de> Visitor<'de> for ThemeColorVisitor {
+    type Value = ThemeColor;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("A valid color, or $ followed by the name of a palette color")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        match value.strip_prefix('$') {
+            Some(name) => Ok(ThemeColor::Palette(name.to_string())),
+            None => Ok(ThemeColor::Color(ColorVisitor.visit_str(value)?)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ThemeColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<ThemeColor, D::Error> {
+        deserializer.deserialize_str(ThemeColorVisitor)
+    }
+}
+
+impl Serialize for ThemeColor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ThemeColor::Color(color) => color.serialize(serializer),
+            ThemeColor::Palette(name) => serializer.serialize_str(&format!("${}", name)),
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for Color {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
         deserializer.deserialize_str(ColorVisitor)
@@ -654,6 +1405,43 @@ impl<'de> Deserialize<'de> for Color {
 
 impl Serialize for Color {
         fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-            serializer.serialize_str(&format!("#{:x?}{:x?}{:x?}", self.r, self.g, self.b))
+            serializer.serialize_str(&format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b))
         }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_round_trip() {
+        for text in &["#FF0000", "#0A0B0C", "#000000", "#FFFFFF"] {
+            let color: Color = serde_yaml::from_str(&format!("\"{}\"", text)).unwrap();
+            let serialized = serde_yaml::to_string(&color).unwrap();
+            let round_trip: Color = serde_yaml::from_str(&serialized).unwrap();
+            assert_eq!(color, round_trip);
+        }
+    }
+
+    #[test]
+    fn color_into_rgba() {
+        let rgba: [f32; 4] = Color { r: 255, g: 0, b: 51 }.into();
+        assert_eq!(rgba, [1.0, 0.0, 0.2, 1.0]);
+    }
+
+    #[test]
+    fn merge_keeps_colors() {
+        let base = WidgetThemeDefinition {
+            text_color: Some(ThemeColor::Color(Color { r: 10, g: 11, b: 12 })),
+            ..Default::default()
+        };
+        let other = WidgetThemeDefinition {
+            background_tint: Some(ThemeColor::Color(Color::red())),
+            ..Default::default()
+        };
+
+        let merged = merge_widget_definitions(base, other).unwrap();
+        assert_eq!(merged.text_color, Some(ThemeColor::Color(Color { r: 10, g: 11, b: 12 })));
+        assert_eq!(merged.background_tint, Some(ThemeColor::Color(Color::red())));
+    }
 }
\ No newline at end of file
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::sync::Arc;
 
 use wgpu::{
@@ -238,6 +239,8 @@ impl WgpuRenderer {
                         clip: widget.clip(),
                         time_millis,
                         scale,
+                        anim_transition: context.anim_transition_for(widget.id()),
+                        tint: widget.background_tint(),
                     }
                 );
             }
@@ -256,29 +259,70 @@ impl WgpuRenderer {
 
                     self.buffer_if_changed(&mut draw_mode, DrawMode::Image(image.texture()));
 
-                    image.draw(
-                        &mut self.draw_list,
-                        ImageDrawParams {
-                            pos: fg_pos.into(),
-                            size: fg_size.into(),
-                            anim_state: widget.anim_state(),
-                            clip: widget.clip(),
-                            time_millis,
-                            scale,
-                        }
-                    );
+                    // widgets with lines only draw the foreground image along those lines
+                    if widget.lines().is_empty() {
+                        image.draw(
+                            &mut self.draw_list,
+                            ImageDrawParams {
+                                pos: fg_pos.into(),
+                                size: fg_size.into(),
+                                anim_state: widget.anim_state(),
+                                clip: widget.clip(),
+                                time_millis,
+                                scale,
+                                anim_transition: context.anim_transition_for(widget.id()),
+                                tint: widget.foreground_tint(),
+                            }
+                        );
+                    }
+
+                    for &(from, to, thickness) in widget.lines() {
+                        image.draw_line(
+                            &mut self.draw_list,
+                            fg_pos + from,
+                            fg_pos + to,
+                            thickness,
+                            ImageDrawParams {
+                                pos: fg_pos.into(),
+                                size: fg_size.into(),
+                                anim_state: widget.anim_state(),
+                                clip: widget.clip(),
+                                time_millis,
+                                scale,
+                                anim_transition: context.anim_transition_for(widget.id()),
+                                tint: widget.foreground_tint(),
+                            },
+                        );
+                    }
                 }
 
                 if let Some(text) = widget.text() {
                     if let Some(font_sum) = widget.font() {
                         self.buffer_if_changed(&mut draw_mode, DrawMode::Font(font_sum.handle));
                         let font = context.themes().font(font_sum.handle);
+                        let text = if widget.truncate() {
+                            font.truncate(text, fg_size.x * scale, widget.ellipsis())
+                        } else {
+                            Cow::Borrowed(text)
+                        };
     
+                        if let Some((offset, color)) = widget.text_shadow() {
+                            font.draw(
+                                &mut self.draw_list,
+                                fg_size * scale,
+                                ((fg_pos + offset) * scale).into(),
+                                &text,
+                                widget.text_align(),
+                                color,
+                                widget.clip() * scale,
+                            );
+                        }
+
                         font.draw(
                             &mut self.draw_list,
                             fg_size * scale,
                             (fg_pos * scale).into(),
-                            text,
+                            &text,
                             widget.text_align(),
                             widget.text_color(),
                             widget.clip() * scale,
@@ -306,7 +350,9 @@ impl WgpuRenderer {
                 anim_state,
                 clip,
                 time_millis,
-                scale
+                scale,
+                anim_transition: None,
+                tint: None,
             };
 
             image.draw(&mut self.draw_list, params);
@@ -339,6 +385,8 @@ impl WgpuRenderer {
                         render_pass.set_pipeline(&self.font_pipe);
                         &self.fonts[handle.id()]
                     }
+                    // solid colors are not drawn by this renderer
+                    DrawMode::Solid => continue,
                 };
     
                 render_pass.set_bind_group(1, &texture.bind_group, &[]);
@@ -478,9 +526,7 @@ impl<'a> Renderer for WgpuRenderer {
         size: f32,
         scale: f32,
     ) -> Result<crate::font::Font, crate::Error> {
-        let font = &source.font;
-
-        let writer = FontTextureWriter::new(font, ranges, size, scale);
+        let writer = FontTextureWriter::new(source, ranges, size, scale);
         let writer_out = writer.write(handle, ranges)?;
 
         let bind_group = self.create_texture(
@@ -5,7 +5,9 @@ use crate::{
 use crate::{frame::{RendGroup}, font::FontSummary, image::ImageHandle};
 use crate::theme::{WidgetTheme};
 use crate::window::WindowBuilder;
-use crate::scrollpane::ScrollpaneBuilder;
+use crate::scrollpane::{ScrollpaneBuilder, ScrollbarTheme};
+use crate::winit_io::CursorIcon;
+use crate::split_pane::{SplitPaneBuilder, SplitDirection};
 
 pub struct Widget {
     // identifier for persistent state
@@ -21,20 +23,50 @@ pub struct Widget {
     child_align: Align,
     layout: Layout,
     layout_spacing: Point,
+    layout_free_relative: bool,
+    layout_index: usize,
+    row_height: f32,
+
+    // grid column widths from the previous frame, and as measured this frame
+    grid_columns: Vec<f32>,
+    grid_measured: Vec<f32>,
+
+    // extent of the children after a spring from the previous frame, and the cursor position
+    // along the layout direction immediately after the spring in this frame
+    spring_extent: f32,
+    spring_end: Option<f32>,
+
+    // the number of direct children added this frame, and whether they extended outside of this widget
+    child_count: usize,
+    overflowed: bool,
 
     // stored in the widget for drawing purposes
     clip: Rect,
     text: Option<String>,
     text_color: Color,
+    text_shadow_offset: Option<Point>,
+    text_shadow_color: Color,
     text_align: Align,
+    truncate: bool,
+    ellipsis: Option<String>,
     font: Option<FontSummary>,
     background: Option<ImageHandle>,
     foreground: Option<ImageHandle>,
+    background_tint: Option<Color>,
+    foreground_tint: Option<Color>,
+    lines: Vec<(Point, Point, f32)>,
     pos: Point,
     size: Point,
     border: Border,
     anim_state: AnimState,
     visible: bool,
+
+    // the offset from this widget's bounds to the bounds used for mouse hit testing
+    hit_delta: Rect,
+
+    // draw ordering within the parent, and the index one past this widget's last descendant
+    z_index: i32,
+    subtree_end: usize,
 }
 
 impl Widget {
@@ -43,12 +75,28 @@ impl Widget {
             theme_id: String::new(),
             text: None,
             text_align: Align::default(),
+            truncate: false,
+            ellipsis: None,
             text_color: Color::default(),
+            text_shadow_offset: None,
+            text_shadow_color: Color::black(),
             font: None,
             background: None,
             foreground: None,
+            background_tint: None,
+            foreground_tint: None,
+            lines: Vec::new(),
             layout: Layout::default(),
             layout_spacing: Point::default(),
+            layout_free_relative: true,
+            layout_index: 0,
+            row_height: 0.0,
+            grid_columns: Vec::new(),
+            grid_measured: Vec::new(),
+            spring_extent: 0.0,
+            spring_end: None,
+            child_count: 0,
+            overflowed: false,
             child_align: Align::default(),
             pos: Point::default(),
             scroll: Point::default(),
@@ -59,30 +107,79 @@ impl Widget {
             rend_group: RendGroup::default(),
             anim_state: AnimState::normal(),
             visible: true,
+            hit_delta: Rect::default(),
             clip: Rect { pos: Point::default(), size },
+            z_index: 0,
+            subtree_end: 0,
         }
     }
 
-    fn create(parent: &Widget, theme: &WidgetTheme, id: String) -> (WidgetData, Widget) {
-        let font = theme.font;
-        let border = theme.border.unwrap_or_default();
-        let raw_size = theme.size.unwrap_or_default();
+    fn create(
+        parent: &Widget,
+        theme: &WidgetTheme,
+        id: String,
+        default_font: Option<FontSummary>,
+        anim_state: Option<AnimState>,
+    ) -> (WidgetData, Widget) {
+        // fields overridden by the theme for the widget's state on the previous frame
+        let state_override = anim_state.and_then(|state| theme.state_override(state));
+        let has_override = state_override.is_some();
+        let state_override = state_override.unwrap_or_default();
+        let theme_pos = state_override.pos.or(theme.pos);
+
+        let font = theme.font.or(default_font);
+        let border = state_override.border.or(theme.border).unwrap_or_default();
+        let raw_size = state_override.size.or(theme.size).unwrap_or_default();
         let width_from = theme.width_from.unwrap_or_default();
         let height_from = theme.height_from.unwrap_or_default();
-        let size = size(parent, raw_size, border, font, width_from, height_from);
-
+        let aspect_ratio = theme.aspect_ratio;
         let mut align = theme.align.unwrap_or(parent.child_align);
-        let mut manual_pos = theme.pos.is_some() || align != parent.child_align;
-        let cursor_pos = if align == parent.child_align {
+        let box_size = size(parent, raw_size, border, font, width_from, height_from, align);
+        let (size, aspect_offset) = fit_aspect_ratio(box_size, aspect_ratio);
+
+        // stretched widgets still take part in the parent's layout
+        let in_layout = align == parent.child_align || align.is_stretch();
+        let mut manual_pos = theme_pos.is_some() || !in_layout;
+        let cursor_pos = if in_layout {
             parent.cursor + parent.scroll
         } else {
             parent.scroll
         };
-        let mut raw_pos = theme.pos.unwrap_or(cursor_pos) + parent.scroll;
-        let mut pos = pos(parent, raw_pos, size, align);
+
+        // mouse hit testing uses the bounds without the state override.  Otherwise, an override that moves the
+        // widget's edge past the mouse would change the state, and so switch the override on and off each frame
+        let hit_delta = if has_override {
+            let bounds = |theme_pos: Option<Point>, raw_size: Point, border: Border| {
+                let box_size = self::size(parent, raw_size, border, font, width_from, height_from, align);
+                let (size, aspect_offset) = fit_aspect_ratio(box_size, aspect_ratio);
+                let screen_pos = match theme_pos {
+                    Some(pos) if parent.positions_absolute() => Some(pos),
+                    _ => theme.screen_pos,
+                };
+                let pos = screen_pos.unwrap_or_else(||
+                    pos(parent, theme_pos.unwrap_or(cursor_pos) + parent.scroll, box_size, align) + aspect_offset
+                );
+                Rect::new(pos, size)
+            };
+
+            let normal = bounds(theme.pos, theme.size.unwrap_or_default(), theme.border.unwrap_or_default());
+            let overridden = bounds(theme_pos, raw_size, border);
+            Rect::new(normal.pos - overridden.pos, normal.size - overridden.size)
+        } else {
+            Rect::default()
+        };
+
+        let mut raw_pos = theme_pos.unwrap_or(cursor_pos) + parent.scroll;
+        let mut pos = pos(parent, raw_pos, box_size, align) + aspect_offset;
         let mut recalc_pos_size = true;
 
-        if let Some(screen_pos) = theme.screen_pos {
+        // children positioned in an absolute free layout are placed on the screen directly
+        let screen_pos = match theme_pos {
+            Some(pos) if parent.positions_absolute() => Some(pos),
+            _ => theme.screen_pos,
+        };
+
+        if let Some(screen_pos) = screen_pos {
             raw_pos = screen_pos;
             pos = screen_pos;
             align = Align::TopLeft;
@@ -92,32 +189,63 @@ impl Widget {
 
         let data = WidgetData {
             manual_pos,
+            screen_positioned: screen_pos.is_some(),
+            movable_screen_pos: false,
             wants_mouse: theme.wants_mouse.unwrap_or_default(),
             wants_scroll: theme.wants_scroll.unwrap_or_default(),
+            wants_keyboard: theme.wants_keyboard.unwrap_or_default(),
             raw_size,
             raw_pos,
             width_from,
             height_from,
+            aspect_ratio,
             align,
-            enabled: true,
+            enabled: theme.enabled.unwrap_or(true),
             active: false,
+            track_anim_state: theme.has_state_overrides(),
             recalc_pos_size,
             next_render_group: NextRenderGroup::None,
             unparent: false,
+            z_order: theme.z_order.unwrap_or_default(),
+            split_divider_width: theme.split_divider_width,
+            scrollbar: ScrollbarTheme {
+                width: theme.scrollbar_width,
+                min_handle_size: theme.scrollbar_min_handle_size,
+                show_vertical: theme.scrollbar_vertical,
+                show_horizontal: theme.scrollbar_horizontal,
+            },
             tooltip: theme.tooltip.clone(),
+            tooltip_theme: theme.tooltip_theme.clone().unwrap_or_else(|| "tooltip".to_string()),
+            cursor: theme.cursor,
         };
 
         let widget = Widget {
             layout: theme.layout.unwrap_or_default(),
             layout_spacing: theme.layout_spacing.unwrap_or_default(),
+            layout_free_relative: theme.layout_free_relative.unwrap_or(true),
+            layout_index: 0,
+            row_height: 0.0,
+            grid_columns: Vec::new(),
+            grid_measured: Vec::new(),
+            spring_extent: 0.0,
+            spring_end: None,
+            child_count: 0,
+            overflowed: false,
             child_align: theme.child_align.unwrap_or_default(),
             theme_id: theme.full_id.to_string(),
             text: theme.text.clone(),
             text_color: theme.text_color.unwrap_or_default(),
+            text_shadow_offset: theme.text_shadow_offset,
+            text_shadow_color: theme.text_shadow_color.unwrap_or_else(Color::black),
             text_align: theme.text_align.unwrap_or_default(),
+            truncate: theme.truncate.unwrap_or_default(),
+            ellipsis: theme.ellipsis.clone(),
             font,
             background: theme.background,
             foreground: theme.foreground,
+            background_tint: theme.background_tint,
+            foreground_tint: theme.foreground_tint,
+            lines: Vec::new(),
             pos,
             scroll: Point::default(),
             cursor: Point::default(),
@@ -127,20 +255,45 @@ impl Widget {
             rend_group: RendGroup::default(),
             anim_state: AnimState::normal(),
             visible: true,
+            hit_delta,
             clip: parent.clip,
+            z_index: theme.z_index.unwrap_or_default(),
+            subtree_end: 0,
         };
 
         (data, widget)
     }
 
+    // whether children with a position are placed at absolute screen positions
+    fn positions_absolute(&self) -> bool {
+        self.layout == Layout::Free && !self.layout_free_relative
+    }
+
     pub fn clip(&self) -> Rect { self.clip }
     pub fn visible(&self) -> bool { self.visible }
+
+    /// The bounds used for mouse hit testing.  These exclude any change in position or size from
+    /// the theme's state overrides.
+    pub(crate) fn hit_bounds(&self) -> Rect {
+        Rect::new(self.pos + self.hit_delta.pos, self.size + self.hit_delta.size)
+    }
     pub fn text_color(&self) -> Color { self.text_color }
     pub fn text_align(&self) -> Align { self.text_align }
+    pub fn text_shadow(&self) -> Option<(Point, Color)> {
+        self.text_shadow_offset.map(|offset| (offset, self.text_shadow_color))
+    }
     pub fn text(&self) -> Option<&str> { self.text.as_deref() }
+    pub fn layout(&self) -> Layout { self.layout }
+    pub fn child_count(&self) -> usize { self.child_count }
+    pub fn overflowed(&self) -> bool { self.overflowed }
+    pub fn truncate(&self) -> bool { self.truncate }
+    pub fn ellipsis(&self) -> &str { self.ellipsis.as_deref().unwrap_or("\u{2026}") }
     pub fn font(&self) -> Option<FontSummary> { self.font }
     pub fn foreground(&self) -> Option<ImageHandle> { self.foreground }
+    pub fn lines(&self) -> &[(Point, Point, f32)] { &self.lines }
     pub fn background(&self) -> Option<ImageHandle> { self.background }
+    pub fn background_tint(&self) -> Option<Color> { self.background_tint }
+    pub fn foreground_tint(&self) -> Option<Color> { self.foreground_tint }
     pub fn border(&self) -> Border { self.border }
     pub fn id(&self) -> &str { &self.id }
     pub fn theme_id(&self) -> &str { &self.theme_id }
@@ -162,14 +315,88 @@ impl Widget {
 
     pub fn gap(&mut self, gap: f32) {
         match self.layout {
-            Layout::Horizontal => self.cursor.x += gap,
+            Layout::Horizontal | Layout::Wrap => self.cursor.x += gap,
             Layout::Vertical => self.cursor.y += gap,
-            Layout::Free => (),
+            Layout::Grid { .. } | Layout::Free => (),
+        }
+    }
+
+    pub(crate) fn hgap(&mut self, gap: f32) {
+        match self.layout {
+            Layout::Horizontal | Layout::Vertical | Layout::Wrap => self.cursor.x += gap,
+            Layout::Grid { .. } | Layout::Free => (),
+        }
+    }
+
+    pub(crate) fn vgap(&mut self, gap: f32) {
+        match self.layout {
+            Layout::Horizontal | Layout::Vertical | Layout::Wrap => self.cursor.y += gap,
+            Layout::Grid { .. } | Layout::Free => (),
         }
     }
 
+    /// Moves the cursor so that the children added after this point, as measured in the previous
+    /// frame, end at the far edge of this widget
+    pub(crate) fn spring(&mut self) {
+        let inner = self.inner_size();
+        match self.layout {
+            Layout::Horizontal => {
+                self.cursor.x = self.cursor.x.max(inner.x - self.spring_extent);
+                self.spring_end = Some(self.cursor.x);
+            },
+            Layout::Vertical => {
+                self.cursor.y = self.cursor.y.max(inner.y - self.spring_extent);
+                self.spring_end = Some(self.cursor.y);
+            },
+            Layout::Wrap | Layout::Grid { .. } | Layout::Free => (),
+        }
+    }
+
+    /// Returns the extent of the children added after the spring in this frame, if there is a spring
+    fn measure_spring(&self) -> Option<f32> {
+        let end = self.spring_end?;
+        let extent = match self.layout {
+            Layout::Horizontal => self.cursor.x - self.layout_spacing.x - end,
+            Layout::Vertical => self.cursor.y - self.layout_spacing.y - end,
+            Layout::Wrap | Layout::Grid { .. } | Layout::Free => return None,
+        };
+        Some(extent.max(0.0))
+    }
+
     pub(crate) fn rend_group(&self) -> RendGroup { self.rend_group }
 
+    pub(crate) fn z_index(&self) -> i32 { self.z_index }
+
+    pub(crate) fn subtree_end(&self) -> usize { self.subtree_end }
+
+    pub(crate) fn set_subtree_end(&mut self, end: usize) {
+        self.subtree_end = end;
+    }
+
+    /// Moves the cursor to the next cell of the grid, after a child of the specified `size` was
+    /// added to the current cell
+    fn advance_grid(&mut self, columns: usize, size: Point) {
+        let columns = columns.max(1);
+        if self.grid_columns.len() < columns { self.grid_columns.resize(columns, 0.0); }
+        if self.grid_measured.len() < columns { self.grid_measured.resize(columns, 0.0); }
+
+        let column = self.layout_index % columns;
+        self.grid_columns[column] = self.grid_columns[column].max(size.x);
+        self.grid_measured[column] = self.grid_measured[column].max(size.x);
+        self.row_height = self.row_height.max(size.y);
+        self.layout_index += 1;
+
+        let next_column = self.layout_index % columns;
+        if next_column == 0 {
+            self.cursor.x = 0.0;
+            self.cursor.y += self.row_height + self.layout_spacing.y;
+            self.row_height = 0.0;
+        } else {
+            let width: f32 = self.grid_columns[..next_column].iter().sum();
+            self.cursor.x = width + next_column as f32 * self.layout_spacing.x;
+        }
+    }
+
     pub(crate) fn set_rend_group(&mut self, group: RendGroup) {
         self.rend_group = group;
     }
@@ -210,8 +437,24 @@ pub struct WidgetState {
     /// per click.
     pub clicked: bool,
 
+    /// Whether the right mouse button clicked on this widget on the current frame.  See
+    /// [`Frame.context_menu`](struct.Frame.html#method.context_menu).
+    pub right_clicked: bool,
+
     /// How far the mouse has been dragged or scrolled on this widget, in logical pixels.
     pub moved: Point,
+
+    /// If the mouse is being dragged on this widget, how far it moved since the previous frame, in logical
+    /// pixels.  This is `None` if the widget is not being dragged.
+    pub drag_delta: Option<Point>,
+
+    /// The mouse wheel amount received by this widget while hovered, in logical pixels.  This is only
+    /// set for widgets that [`want scroll`](struct.WidgetBuilder.html#method.wants_scroll).
+    pub scroll_amount: Point,
+
+    /// Whether this click was the second of two clicks on this widget within the
+    /// [`double click time`](struct.Context.html#method.set_double_click_ms).
+    pub double_clicked: bool,
 }
 
 impl WidgetState {
@@ -221,11 +464,15 @@ impl WidgetState {
             hovered: false,
             pressed: false,
             clicked: false,
+            right_clicked: false,
             moved: Point::default(),
+            drag_delta: None,
+            scroll_amount: Point::default(),
+            double_clicked: false,
         }
     }
 
-    fn new(anim_state: AnimState, clicked: bool, moved: Point) -> WidgetState {
+    fn new(anim_state: AnimState, clicked: bool, right_clicked: bool, dragged: Point, scroll_amount: Point) -> WidgetState {
         let (hovered, pressed) = if anim_state.contains(AnimStateKey::Pressed) {
             (true, true)
         } else if anim_state.contains(AnimStateKey::Hover) {
@@ -239,7 +486,11 @@ impl WidgetState {
             hovered,
             pressed,
             clicked,
-            moved,
+            right_clicked,
+            moved: dragged + scroll_amount,
+            drag_delta: if pressed { Some(dragged) } else { None },
+            scroll_amount,
+            double_clicked: false,
         }
     }
 }
@@ -251,6 +502,7 @@ fn size(
     font: Option<FontSummary>,
     width_from: WidthRelative,
     height_from: HeightRelative,
+    align: Align,
 ) -> Point {
     let x = match width_from {
         WidthRelative::Children => size.x, // this will be added to after children are layed out
@@ -264,12 +516,42 @@ fn size(
         HeightRelative::FontLine => size.y + font.map_or(0.0,
             |sum| sum.line_height) + border.vertical(),
     };
+
+    // stretched axes always fill the parent
+    let x = if align.stretches_horizontal() { parent.size.x - parent.border.horizontal() } else { x };
+    let y = if align.stretches_vertical() { parent.size.y - parent.border.vertical() } else { y };
     Point { x, y }
 }
 
+/// Fits `size` to the aspect `ratio` (width / height).  If one dimension is zero, it is
+/// derived from the other.  If both are set, the size is shrunk to fit (letterboxed), and
+/// the returned offset centers the fitted size within the original.
+fn fit_aspect_ratio(size: Point, ratio: Option<f32>) -> (Point, Point) {
+    let ratio = match ratio {
+        Some(ratio) if ratio > 0.0 => ratio,
+        _ => return (size, Point::default()),
+    };
+
+    if size.x <= 0.0 && size.y <= 0.0 {
+        (size, Point::default())
+    } else if size.y <= 0.0 {
+        (Point::new(size.x, size.x / ratio), Point::default())
+    } else if size.x <= 0.0 {
+        (Point::new(size.y * ratio, size.y), Point::default())
+    } else {
+        let fitted = if size.x / size.y > ratio {
+            Point::new(size.y * ratio, size.y)
+        } else {
+            Point::new(size.x, size.x / ratio)
+        };
+        (fitted, ((size - fitted) / 2.0).round())
+    }
+}
+
 fn pos(parent: &Widget, pos: Point, self_size: Point, align: Align) -> Point {
     let size = parent.size;
     let border = parent.border;
+    let align = align.stretch_anchor(parent.child_align);
 
     let pos = parent.pos + match align {
         Align::Left => Point {
@@ -308,6 +590,7 @@ fn pos(parent: &Widget, pos: Point, self_size: Point, align: Align) -> Point {
             x: size.x - border.right - pos.x,
             y: border.top + pos.y
         },
+        Align::Stretch | Align::StretchHorizontal | Align::StretchVertical => unreachable!(),
     };
 
     pos - align.adjust_for(self_size).round()
@@ -321,22 +604,37 @@ pub(crate) enum NextRenderGroup {
 
 pub(crate) struct WidgetData {
     manual_pos: bool,
+
+    // whether raw_pos is an absolute screen position rather than relative to the parent
+    screen_positioned: bool,
+
+    // whether the screen position is the initial position of a movable window, which is
+    // expected to be placed in screen space regardless of the parent's layout
+    movable_screen_pos: bool,
     wants_mouse: bool,
     wants_scroll: bool,
+    wants_keyboard: bool,
 
     raw_pos: Point,
     raw_size: Point,
     width_from: WidthRelative,
     height_from: HeightRelative,
+    aspect_ratio: Option<f32>,
     align: Align,
 
     enabled: bool,
     active: bool,
+    track_anim_state: bool,
     recalc_pos_size: bool,
     next_render_group: NextRenderGroup,
     unparent: bool,
+    z_order: i32,
+    split_divider_width: Option<f32>,
+    scrollbar: ScrollbarTheme,
 
     tooltip: Option<String>,
+    tooltip_theme: String,
+    cursor: Option<CursorIcon>,
 }
 
 /// A `WidgetBuilder` is used to customize widgets within your UI tree, following a builder pattern.
@@ -376,17 +674,28 @@ impl<'a> WidgetBuilder<'a> {
 
             let id = {
                 let parent_widget = frame.widget(parent);
+                let base_id = match frame.id_scope_prefix(parent) {
+                    None => theme.id.to_string(),
+                    Some(prefix) => format!("{}/{}", prefix, theme.id),
+                };
+
                 if parent_widget.id.is_empty() {
-                    theme.id.to_string()
+                    base_id
                 } else {
-                    format!("{}/{}", parent_widget.id, theme.id)
+                    format!("{}/{}", parent_widget.id, base_id)
                 }
             };
 
             let id = frame.generate_id(id);
             let parent_widget = frame.widget(parent);
+            let default_font = context.themes().default_font();
+            let anim_state = if theme.has_state_overrides() {
+                context.state(&id).anim_state
+            } else {
+                None
+            };
 
-            let (data, widget) = Widget::create(parent_widget, theme, id);
+            let (data, widget) = Widget::create(parent_widget, theme, id, default_font, anim_state);
 
             (data, widget)
         };
@@ -400,26 +709,32 @@ impl<'a> WidgetBuilder<'a> {
     }
 
     fn recalculate_pos_size(&mut self, state_moved: Point, state_resize: Point) {
-        {
+        let (box_size, aspect_offset) = {
             let parent = self.frame.widget(self.parent);
             let widget = &self.widget;
-            let size = size (
+            let box_size = size (
                 parent,
                 self.data.raw_size,
                 widget.border,
                 widget.font,
                 self.data.width_from,
-                self.data.height_from
+                self.data.height_from,
+                self.data.align,
             );
 
+            let (size, aspect_offset) = fit_aspect_ratio(box_size, self.data.aspect_ratio);
             self.widget.size = size;
-        }
+            (box_size, aspect_offset)
+        };
 
         {
             let parent = self.frame.widget(self.parent);
-            let widget = &self.widget;
-            let pos = pos(parent, self.data.raw_pos, widget.size, self.data.align);
-            self.widget.pos = pos + state_moved;
+            let pos = if self.data.screen_positioned {
+                self.data.raw_pos
+            } else {
+                pos(parent, self.data.raw_pos, box_size, self.data.align)
+            };
+            self.widget.pos = pos + aspect_offset + state_moved;
         }
 
         self.widget.size = self.widget.size + state_resize;
@@ -427,14 +742,108 @@ impl<'a> WidgetBuilder<'a> {
         self.data.recalc_pos_size = false;
     }
 
+    /// If the parent has a [`Wrap`](enum.Layout.html#variant.Wrap) layout and this widget would extend
+    /// past the parent's inner width, moves this widget and the parent's cursor to the next row.
+    fn wrap_to_next_row(&mut self) {
+        let size = self.widget.size;
+        let parent = self.frame.widget_mut(self.parent);
+        if parent.layout != Layout::Wrap || parent.cursor.x <= 0.0 { return; }
+
+        let inner_width = parent.size.x - parent.border.horizontal();
+        if parent.cursor.x + size.x <= inner_width { return; }
+
+        let old_cursor = parent.cursor;
+        parent.cursor.x = 0.0;
+        parent.cursor.y += parent.row_height + parent.layout_spacing.y;
+        parent.row_height = 0.0;
+
+        let delta = parent.cursor - old_cursor;
+        self.widget.pos = self.widget.pos + delta;
+    }
+
     fn parent(&self) -> &Widget {
         self.frame.widget(self.parent)
     }
 
+    /// Lays out the widget, and then moves its top edge down by `amount`, reducing
+    /// its height by the same amount.
+    pub(crate) fn inset_top(mut self, amount: f32) -> WidgetBuilder<'a> {
+        if amount == 0.0 { return self; }
+
+        let mut rect = Rect::default();
+        self = self.trigger_layout(&mut rect);
+        self.widget.pos.y += amount;
+        self.widget.size.y -= amount;
+        self
+    }
+
+    pub(crate) fn split_divider_width(&self) -> Option<f32> {
+        self.data.split_divider_width
+    }
+
+    pub(crate) fn scrollbar_theme(&self) -> ScrollbarTheme {
+        self.data.scrollbar
+    }
+
+    pub(crate) fn raw_size(&self) -> Point {
+        self.data.raw_size
+    }
+
+    /// If this widget's foreground is a `Timed` image, selects the frame at `fraction` of the way
+    /// through the image by adjusting this widget's base time.  Otherwise, does nothing.
+    #[must_use]
+    pub(crate) fn foreground_frame(self, fraction: f32) -> WidgetBuilder<'a> {
+        let handle = match self.widget.foreground {
+            None => return self,
+            Some(handle) => handle,
+        };
+
+        {
+            let mut internal = self.frame.context_internal().borrow_mut();
+            if let Some((frame_time, frames)) = internal.themes().image(handle).timed_frames() {
+                let max_index = frames.saturating_sub(1);
+                let index = ((fraction.clamp(0.0, 1.0) * max_index as f32).round() as usize).min(max_index);
+                let offset = frame_time * index as u32 + frame_time / 2;
+                let now = internal.time_millis();
+                internal.state_mut(&self.widget.id).base_time_millis = now.saturating_sub(offset);
+            }
+        }
+
+        self
+    }
+
     pub(crate) fn set_next_render_group(&mut self, val: NextRenderGroup) {
         self.data.next_render_group = val;
     }
 
+    pub(crate) fn set_z_order(&mut self, z_order: i32) {
+        self.data.z_order = z_order;
+    }
+
+    /// Positions this widget in screen space, in the same manner as [`screen_pos`](#method.screen_pos),
+    /// but still applies the `moved` offset from the widget's persistent state
+    pub(crate) fn set_movable_screen_pos(&mut self, pos: Point) {
+        self.data.raw_pos = pos;
+        self.widget.hit_delta.pos = Point::default();
+        self.data.align = Align::TopLeft;
+        self.data.manual_pos = true;
+        self.data.screen_positioned = true;
+        self.data.movable_screen_pos = true;
+        self.data.recalc_pos_size = true;
+    }
+
+    /// Specifies the order in which this widget is drawn relative to its siblings.  Siblings with a higher
+    /// `z_index` are drawn on top of those with a lower one, while siblings with an equal `z_index` are
+    /// drawn in the order they were declared.  A negative `z_index` draws this widget below its parent's
+    /// background.  This only affects drawing; layout is always performed in declaration order.
+    /// The default is zero.
+    /// This may also be specified in the widget's [`theme`](index.html).
+    #[must_use]
+    pub fn z_index(mut self, z_index: i32) -> WidgetBuilder<'a> {
+        self.widget.z_index = z_index;
+        self
+    }
+
     /// Specifies that this widget and its children should be part of a new Render Group that is always shown on top of other groups.
     /// See [`new_render_group`](#method.new_render_group)
     #[must_use]
@@ -463,7 +872,9 @@ impl<'a> WidgetBuilder<'a> {
     }
 
     /// Sets whether this widget will receive mouse scrollwheel events.  By default, widgets will not receive scroll wheel events, so this is set
-    /// to `true` for scrollpanes.
+    /// to `true` for scrollpanes.  Any enabled widget that wants scroll will receive the wheel amount in
+    /// [`WidgetState.scroll_amount`](struct.WidgetState.html#structfield.scroll_amount) while hovered, allowing custom
+    /// scroll driven widgets, such as a zoom control.  When nested widgets want scroll, the innermost one receives the wheel.
     /// This may also be specified in the widget's [`theme`](index.html).
     #[must_use]
     pub fn wants_scroll(mut self, wants_scroll: bool) -> WidgetBuilder<'a> {
@@ -471,6 +882,17 @@ impl<'a> WidgetBuilder<'a> {
         self
     }
 
+    /// Sets whether this widget will receive keyboard input.  A widget that wants the keyboard takes
+    /// keyboard focus when it is clicked, so it should generally also [`want the mouse`](#method.wants_mouse).
+    /// While focused, characters sent to the [`Context`](struct.Context.html) are available via
+    /// [`Frame.keyboard_input`](struct.Frame.html#method.keyboard_input).
+    /// This may also be specified in the widget's [`theme`](index.html).
+    #[must_use]
+    pub fn wants_keyboard(mut self, wants_keyboard: bool) -> WidgetBuilder<'a> {
+        self.data.wants_keyboard = wants_keyboard;
+        self
+    }
+
     /// Sets an `id` for this widget.  This `id` is used internally to associate the widget with its [`PersistentState`](struct.PersistentState.html).
     /// You will need to specify an `id` if you want to make changes to the [`PersistentState`](struct.PersistentState.html).  Otherwise,
     /// Thyme can usually generate a unique internal ID for most elements.
@@ -502,6 +924,25 @@ impl<'a> WidgetBuilder<'a> {
         self
     }
 
+    /// Specify an offset at which to draw a shadow behind the widget's text.  The shadow is
+    /// the same text drawn first in the [`text_shadow_color`](#method.text_shadow_color), and
+    /// is only drawn when an offset is specified.
+    /// This may also be specified in the widget's [`theme`](index.html).
+    #[must_use]
+    pub fn text_shadow_offset(mut self, offset: Point) -> WidgetBuilder<'a> {
+        self.widget.text_shadow_offset = Some(offset);
+        self
+    }
+
+    /// Specify a [`Color`](struct.Color.html) for the widget's text shadow.  The default
+    /// color is black.  See [`text_shadow_offset`](#method.text_shadow_offset).
+    /// This may also be specified in the widget's [`theme`](index.html).
+    #[must_use]
+    pub fn text_shadow_color(mut self, color: Color) -> WidgetBuilder<'a> {
+        self.widget.text_shadow_color = color;
+        self
+    }
+
     /// Specify the [`alignment`](enum.Align.html) of the widget's text within the widget's
     /// inner area, as defined by its overall [`size`](#method.size) and [`border`](#method.border).
     /// This may also be specified in the widget's [`theme`](index.html).
@@ -511,6 +952,16 @@ impl<'a> WidgetBuilder<'a> {
         self
     }
 
+    /// Specify whether the widget's text should be truncated to a single line that fits within the
+    /// widget's inner width.  When truncated, the end of the text is replaced with an ellipsis, which
+    /// defaults to `…` and may be changed with the `ellipsis` field of the widget's theme.
+    /// This may also be specified in the widget's [`theme`](index.html).
+    #[must_use]
+    pub fn truncate(mut self, truncate: bool) -> WidgetBuilder<'a> {
+        self.widget.truncate = truncate;
+        self
+    }
+
     /// Specify `text` to display for this widget.  The widget must have a [`font`](#method.font)
     /// specified to render text.
     /// This may also be specified in the widget's [`theme`](index.html).
@@ -520,12 +971,23 @@ impl<'a> WidgetBuilder<'a> {
         self
     }
 
-    /// Specify `tooltip` to display as a tooltip if this widget is hovered with the mouse.
-    /// The tooltip will use the "tooltip" theme which must be present in the theme.
-    /// This may also be specified in the widget's [`theme`](index.html).
+    /// Specify the shape of the operating system mouse cursor while this widget is hovered.  See
+    /// [`Context.set_os_cursor`](struct.Context.html#method.set_os_cursor).  This may also be specified
+    /// in the widget's theme with `cursor`.
     #[must_use]
-    pub fn tooltip<T: Into<String>>(mut self, tooltip: T) -> WidgetBuilder<'a> {
-        self.data.tooltip = Some(tooltip.into());
+    pub fn cursor(mut self, icon: CursorIcon) -> WidgetBuilder<'a> {
+        self.data.cursor = Some(icon);
+        self
+    }
+
+    /// Specify `text` to display as a tooltip if this widget is hovered with the mouse.
+    /// The tooltip will use the specified `theme_id`, which must be present in the theme.
+    /// The text may also be specified in the widget's [`theme`](index.html) with `tooltip`, along
+    /// with an optional `tooltip_theme`.  If no `tooltip_theme` is specified, the "tooltip" theme is used.
+    #[must_use]
+    pub fn tooltip<T: Into<String>>(mut self, theme_id: &str, text: T) -> WidgetBuilder<'a> {
+        self.data.tooltip = Some(text.into());
+        self.data.tooltip_theme = theme_id.to_string();
         self
     }
 
@@ -553,6 +1015,17 @@ impl<'a> WidgetBuilder<'a> {
         self
     }
 
+    /// Adds a line from `from` to `to`, with the specified `thickness`, to this widget.  The positions are
+    /// in logical pixels, relative to the widget's inner top left corner.  Lines are drawn using the widget's
+    /// foreground image, stretched over a series of rects approximating the line.  Lines are not drawn if the
+    /// widget has no foreground, or for a non positive `thickness`.  This may be called multiple times to draw
+    /// multiple lines.
+    #[must_use]
+    pub fn line(mut self, from: Point, to: Point, thickness: f32) -> WidgetBuilder<'a> {
+        self.widget.lines.push((from, to, thickness));
+        self
+    }
+
     /// Specify a background image for this widget.  The image ID, `bg` must be registered in the theme's
     /// image definitions.  The ID consists of "{image_set_id}/{image_id}".
     /// Background images are drawn below text and any children.
@@ -565,6 +1038,24 @@ impl<'a> WidgetBuilder<'a> {
         self
     }
 
+    /// Specify a color to tint this widget's background image with.  The color is multiplied with
+    /// the image's own color, so white leaves the image unchanged.
+    /// This may also be specified in the widget's [`theme`](index.html).
+    #[must_use]
+    pub fn background_tint(mut self, tint: Color) -> WidgetBuilder<'a> {
+        self.widget.background_tint = Some(tint);
+        self
+    }
+
+    /// Specify a color to tint this widget's foreground image, and any [`lines`](#method.line), with.
+    /// The color is multiplied with the image's own color, so white leaves the image unchanged.
+    /// This may also be specified in the widget's [`theme`](index.html).
+    #[must_use]
+    pub fn foreground_tint(mut self, tint: Color) -> WidgetBuilder<'a> {
+        self.widget.foreground_tint = Some(tint);
+        self
+    }
+
     /// Specifies the default alignment of children added to this widget.  See [`Align`](enum.Align.html).
     /// This may be overridden by the child, either in the theme or by calling [`align`](#method.align).
     /// This may also be specified in the widget's [`theme`](index.html).
@@ -582,7 +1073,15 @@ impl<'a> WidgetBuilder<'a> {
         self
     }
 
-    /// Specifies that the children of this widget should be laid out vertically.  See [`Layout`](enum.Layout.html).
+    /// Specifies the horizontal spacing `x` and vertical spacing `y`, in logical pixels, to use between
+    /// children that are laid out in this widget.  This is equivalent to calling
+    /// [`layout_spacing`](#method.layout_spacing), and overrides the `layout_spacing` in the theme.
+    #[must_use]
+    pub fn child_spacing(self, x: f32, y: f32) -> WidgetBuilder<'a> {
+        self.layout_spacing(Point { x, y })
+    }
+
+    /// Specifies that the children of this widget should be laid out horizontally.  See [`Layout`](enum.Layout.html).
     /// This may also be specified in the widget's [`theme`](index.html).
     #[must_use]
     pub fn layout_horizontal(self) -> WidgetBuilder<'a> {
@@ -596,7 +1095,35 @@ impl<'a> WidgetBuilder<'a> {
         self.layout(Layout::Vertical)
     }
 
-    /// Specifies the `layout` for children of this widget.  See [`Layout`](enum.Layout.html).
+    /// Specifies that the children of this widget should be laid out in a grid with the specified
+    /// number of `columns`.  See [`Layout`](enum.Layout.html).
+    /// This may also be specified in the widget's [`theme`](index.html).
+    #[must_use]
+    pub fn layout_grid(self, columns: usize) -> WidgetBuilder<'a> {
+        self.layout(Layout::Grid { columns })
+    }
+
+    /// Specifies that the children of this widget should be laid out horizontally, wrapping
+    /// onto a new row when the parent's width is exceeded.  See [`Layout`](enum.Layout.html).
+    /// This may also be specified in the widget's [`theme`](index.html).
+    #[must_use]
+    pub fn layout_wrap(self) -> WidgetBuilder<'a> {
+        self.layout(Layout::Wrap)
+    }
+
+    /// Specifies whether the positions of children are relative to this widget's inner top left
+    /// corner when using a [`Free`](enum.Layout.html#variant.Free) layout.  This is `true` by default.
+    /// When `false`, children specifying a [`pos`](#method.pos) are placed at that absolute screen position.
+    /// This may also be specified in the widget's [`theme`](index.html).
+    #[must_use]
+    pub fn layout_free_relative(mut self, relative: bool) -> WidgetBuilder<'a> {
+        self.widget.layout_free_relative = relative;
+        self
+    }
+
+    /// Specifies the `layout` for children of this widget, overriding the `layout` in the theme.
+    /// See [`Layout`](enum.Layout.html).  This is useful when the layout direction depends on
+    /// runtime conditions, such as the available space.
     /// This may also be specified in the widget's [`theme`](index.html).
     #[must_use]
     pub fn layout(mut self, layout: Layout) -> WidgetBuilder<'a> {
@@ -606,26 +1133,56 @@ impl<'a> WidgetBuilder<'a> {
 
     /// Manually specify a position for this widget, basedon the specified
     /// `x` and `y` logical pixel positions.  This position ignores alignment
-    /// or any other considerations.
+    /// or any other considerations, including the parent's position, layout, and
+    /// [`scroll`](struct.Frame.html#method.scroll) offset.  The widget is still clipped
+    /// to the parent unless [`unclip`](#method.unclip) is also used, and still counts
+    /// towards the parent's child bounds unless [`unparent`](#method.unparent) is also used.
+    /// A warning is logged if the widget is neither unparented nor inside a parent with a
+    /// [`Free`](enum.Layout.html#variant.Free) layout, as the parent's size would then
+    /// depend on the widget's screen position.
     /// This may also be specified in the widget's [`theme`](index.html).
     #[must_use]
     pub fn screen_pos(mut self, x: f32, y: f32) -> WidgetBuilder<'a> {
         self.data.raw_pos = Point { x, y };
+        self.widget.hit_delta.pos = Point::default();
         self.widget.pos = Point { x, y };
         self.data.align = Align::TopLeft;
         self.data.manual_pos = true;
+        self.data.screen_positioned = true;
+        self.data.movable_screen_pos = false;
         self.data.recalc_pos_size = false;
         self
     }
 
+    /// Manually specify both the position and size of this widget in screen space, in logical
+    /// pixels.  This is equivalent to calling [`screen_pos`](#method.screen_pos) along with
+    /// [`size`](#method.size), with [`WidthRelative::Normal`](enum.WidthRelative.html) and
+    /// [`HeightRelative::Normal`](enum.HeightRelative.html), so the size is exactly `rect.size`.
+    #[must_use]
+    pub fn screen_rect(mut self, rect: Rect) -> WidgetBuilder<'a> {
+        self.data.raw_size = rect.size;
+        self.widget.hit_delta.size = Point::default();
+        self.data.width_from = WidthRelative::Normal;
+        self.data.height_from = HeightRelative::Normal;
+        self.widget.size = rect.size;
+        self.screen_pos(rect.pos.x, rect.pos.y)
+    }
+
     /// Specify the position of the widget, with respect to its alignment within the parent.
     /// The `x` and `` values are in logical pixels.
     /// See [`align`](#method.align).
     /// This may also be specified in the widget's [`theme`](index.html).
     #[must_use]
     pub fn pos(mut self, x: f32, y: f32) -> WidgetBuilder<'a> {
+        if self.parent().positions_absolute() {
+            return self.screen_pos(x, y);
+        }
+
         self.data.raw_pos = Point { x, y } + self.parent().scroll;
+        self.widget.hit_delta.pos = Point::default();
         self.data.manual_pos = true;
+        self.data.screen_positioned = false;
+        self.data.movable_screen_pos = false;
         self.data.recalc_pos_size = true;
         self
     }
@@ -635,14 +1192,18 @@ impl<'a> WidgetBuilder<'a> {
     #[must_use]
     pub fn align(mut self, align: Align) -> WidgetBuilder<'a> {
         self.data.align = align;
-        self.data.manual_pos = true;
+        // stretched widgets keep their place in the parent's layout
+        if !align.is_stretch() {
+            self.data.manual_pos = true;
+        }
         self.data.recalc_pos_size = true;
         self
     }
     
     /// Specify the widget's border size, which determines the inner size of the widget
     /// relative to its [`size`](#method.size).  See [`Border`](struct.Border.html).
-    /// This may also be specified in the widget's [`theme`](index.html).
+    /// This overrides the `border` in the widget's [`theme`](index.html), and is useful for
+    /// padding which depends on the content, i.e. `.border(Border::uniform(padding))`.
     #[must_use]
     pub fn border(mut self, border: Border) -> WidgetBuilder<'a> {
         self.widget.border = border;
@@ -657,6 +1218,7 @@ impl<'a> WidgetBuilder<'a> {
     #[must_use]
     pub fn size(mut self, x: f32, y: f32) -> WidgetBuilder<'a> {
         self.data.raw_size = Point { x, y };
+        self.widget.hit_delta.size = Point::default();
 
         // usually, setting the size here will have no effect since we will recalculate anyway.
         // but in some cases involving manual positioning this is needed
@@ -666,6 +1228,19 @@ impl<'a> WidgetBuilder<'a> {
         self
     }
 
+    /// Constrains the widget's size to the specified aspect `ratio`, which is width divided by height.
+    /// This is applied after [`width_from`](#method.width_from) and [`height_from`](#method.height_from)
+    /// are resolved.  If the resulting height is zero, it is computed as `width / ratio`.  If the width
+    /// is zero, it is computed as `height * ratio`.  If both are set, the widget is shrunk to the largest
+    /// size with this ratio that fits, and is centered in the original area.
+    /// This may also be specified in the widget's [`theme`](index.html).
+    #[must_use]
+    pub fn aspect_ratio(mut self, ratio: f32) -> WidgetBuilder<'a> {
+        self.data.aspect_ratio = Some(ratio);
+        self.data.recalc_pos_size = true;
+        self
+    }
+
     /// Specify how to compute the widget's width from its [`size`](#method.size).
     /// See [`WidthRelative`](enum.WidthRelative.html).
     /// This may also be specified in the widget's [`theme`](index.html).  You may also
@@ -717,7 +1292,13 @@ impl<'a> WidgetBuilder<'a> {
     }
 
     /// Removes all constraints from the widget's clip [`Rectangle`](struct.Rect.html).  This will
-    /// allow the widget to render outside of its parent's area.  See [`clip`](#method.clip).
+    /// allow the widget to render outside of its parent's area, such as for a dropdown inside a
+    /// [`scrollpane`](#method.scrollpane).  The widget's clip is set to the entire display.  Children
+    /// of this widget inherit its clip, so they may also render outside of the parent's area, unless
+    /// they are clipped again.  The clip also limits where the widget receives mouse input.  Each
+    /// widget's clip is passed to the renderer along with its geometry, so an unclipped widget is drawn
+    /// correctly regardless of the clip of the widgets drawn before or after it.
+    /// See [`clip`](#method.clip) and [`clip_to_parent`](#method.clip_to_parent).
     #[must_use]
     pub fn unclip(mut self) -> WidgetBuilder<'a> {
         let display_size = self.frame.context_internal().borrow().display_size();
@@ -725,10 +1306,27 @@ impl<'a> WidgetBuilder<'a> {
         self
     }
 
+    /// Sets whether this widget is clipped to its parent's clip [`Rectangle`](struct.Rect.html).  By
+    /// default, widgets are clipped to their parent.  Passing `false` is equivalent to calling
+    /// [`unclip`](#method.unclip), while passing `true` resets the clip to the parent's clip, removing
+    /// any previous call to [`clip`](#method.clip).
+    #[must_use]
+    pub fn clip_to_parent(mut self, clip: bool) -> WidgetBuilder<'a> {
+        if !clip {
+            return self.unclip();
+        }
+
+        self.widget.clip = self.frame.widget(self.parent).clip;
+        self
+    }
+
     /// If called, the current parent widget will not treat this widget as a child for the purposes
     /// of computing its child bounds.  This is useful for popups and similar which are not
     /// neccesarily children of the widgets that create them.  You usually will also want
     /// [`unclip`](#method.unclip) and [`new_render_group`](#method.new_render_group).
+    /// The widget is still positioned relative to the parent, including the parent's scroll offset,
+    /// and still advances the parent's layout, unless it also uses [`screen_pos`](#method.screen_pos)
+    /// or another manual position.
     #[must_use]
     pub fn unparent(mut self) -> WidgetBuilder<'a> {
         self.data.unparent = true;
@@ -753,13 +1351,25 @@ impl<'a> WidgetBuilder<'a> {
     }
 
     /// Sets whether this widget will be `enabled`.  If the widget is not
-    /// enabled, it will not interact with any user input.
+    /// enabled, it will not interact with any user input.  Its [`AnimState`](struct.AnimState.html)
+    /// will be `Disabled`, never including `Hover` or `Pressed`, and it will never be clicked.
+    /// Mouse input over the widget is still consumed, so widgets underneath it will not receive it.
+    /// All children of a widget that is not enabled are also not enabled.  This is independent of
+    /// [`active`](#method.active), so a disabled widget may still show the `Active` state.
+    /// This may also be specified in the widget's [`theme`](index.html).
     #[must_use]
     pub fn enabled(mut self, enabled: bool) -> WidgetBuilder<'a> {
         self.data.enabled = enabled;
         self
     }
 
+    /// Specifies that this widget is not enabled.  This is equivalent to calling
+    /// [`enabled(false)`](#method.enabled).
+    #[must_use]
+    pub fn disabled(self) -> WidgetBuilder<'a> {
+        self.enabled(false)
+    }
+
     
     /// Force the widget to layout its `size` and `position` immediately.
     /// Assuming these attributes are not changed after this method is
@@ -879,6 +1489,15 @@ impl<'a> WidgetBuilder<'a> {
         ScrollpaneBuilder::new(self.wants_scroll(true), content_id)
     }
 
+    /// Turns this builder into a [`SplitPaneBuilder`](struct.SplitPaneBuilder.html).  You should use all
+    /// `WidgetBuilder` methods you need before calling this method.  The split pane must still be completed
+    /// with one of the methods on [`SplitPaneBuilder`](struct.SplitPaneBuilder.html).  You must pass a unique
+    /// `id`, and the `initial_fraction` of the available space to give to the first pane.
+    #[must_use]
+    pub fn split_pane(self, id: &str, direction: SplitDirection, initial_fraction: f32) -> SplitPaneBuilder<'a> {
+        SplitPaneBuilder::new(self.id(id), id, direction, initial_fraction)
+    }
+
     /// Consumes the builder and adds a widget to the current frame.  The
     /// returned data includes information about the animation state and
     /// mouse interactions of the created element.
@@ -901,6 +1520,10 @@ impl<'a> WidgetBuilder<'a> {
     pub(crate) fn finish_with<F: FnOnce(&mut Frame)>(mut self, f: Option<F>) -> (&'a mut Frame, WidgetState) {
         if !self.widget.visible { return (self.frame, WidgetState::hidden()); }
 
+        if self.frame.in_disabled_tree {
+            self.data.enabled = false;
+        }
+
         let (state, text, in_modal_tree) = {
             let internal = self.frame.context_internal().borrow();
             let state = internal.state(&self.widget.id);
@@ -912,6 +1535,12 @@ impl<'a> WidgetBuilder<'a> {
 
             let in_modal_tree = Some(self.widget.id()) == internal.modal_id();
 
+            match self.widget.layout {
+                Layout::Grid { .. } => self.widget.grid_columns = internal.grid_columns(&self.widget.id),
+                Layout::Horizontal | Layout::Vertical => self.widget.spring_extent = internal.spring_extent(&self.widget.id),
+                Layout::Wrap | Layout::Free => (),
+            }
+
             (state.copy_data(), text, in_modal_tree)
         };
 
@@ -930,6 +1559,10 @@ impl<'a> WidgetBuilder<'a> {
             self.recalculate_pos_size(state.moved, state.resize);
         }
 
+        if !self.data.manual_pos {
+            self.wrap_to_next_row();
+        }
+
         let self_pos = self.widget.pos;
         let self_size = self.widget.size;
         let mut self_bounds = Rect::new(self_pos, self_size);
@@ -950,12 +1583,17 @@ impl<'a> WidgetBuilder<'a> {
 
         match self.data.next_render_group {
             NextRenderGroup::None => (),
-            NextRenderGroup::Normal => self.frame.next_render_group(self_bounds, self.widget.id.to_string(), false),
-            NextRenderGroup::AlwaysTop => self.frame.next_render_group(self_bounds, self.widget.id.to_string(), true),
+            NextRenderGroup::Normal => self.frame.next_render_group(
+                self_bounds, self.widget.id.to_string(), false, self.data.z_order
+            ),
+            NextRenderGroup::AlwaysTop => self.frame.next_render_group(
+                self_bounds, self.widget.id.to_string(), true, self.data.z_order
+            ),
         }
 
         let widget_index = self.frame.num_widgets();
         self.frame.push_widget(self.widget);
+        self.frame.widget_mut(self.parent).child_count += 1;
 
         // if there is a child function
         if let Some(f) = f {
@@ -963,12 +1601,29 @@ impl<'a> WidgetBuilder<'a> {
             self.frame.set_max_child_bounds(self_bounds);
             let old_parent_index = self.frame.parent_index();
             self.frame.set_parent_index(widget_index);
+            let old_in_disabled_tree = self.frame.in_disabled_tree;
+            self.frame.in_disabled_tree = !self.data.enabled;
 
             // build all children
             (f)(self.frame);
 
+            self.frame.in_disabled_tree = old_in_disabled_tree;
             self.frame.set_parent_index(old_parent_index);
             let this_children_max_bounds = self.frame.max_child_bounds();
+
+            // store the measured grid columns for use in the next frame
+            if let Layout::Grid { .. } = self.frame.widget(widget_index).layout {
+                let widget = self.frame.widget_mut(widget_index);
+                let columns = std::mem::take(&mut widget.grid_measured);
+                let id = widget.id.to_string();
+                self.frame.context_internal().borrow_mut().set_grid_columns(id, columns);
+            }
+
+            // store the measured extent after the spring, if any, for use in the next frame
+            if let Some(extent) = self.frame.widget(widget_index).measure_spring() {
+                let id = self.frame.widget(widget_index).id.to_string();
+                self.frame.context_internal().borrow_mut().set_spring_extent(id, extent);
+            }
             self.frame.set_parent_max_child_bounds(this_children_max_bounds);
 
             // adjust widget size if needed for Child relative size
@@ -997,6 +1652,26 @@ impl<'a> WidgetBuilder<'a> {
                     NextRenderGroup::Normal | NextRenderGroup::AlwaysTop => self.frame.rebound_cur_render_group(self_bounds),
                 }
             }
+
+            let overflowed = this_children_max_bounds.pos.x < self_bounds.pos.x ||
+                this_children_max_bounds.pos.y < self_bounds.pos.y ||
+                this_children_max_bounds.right() > self_bounds.right() ||
+                this_children_max_bounds.bot() > self_bounds.bot();
+            self.frame.widget_mut(widget_index).overflowed = overflowed;
+        }
+
+        let subtree_end = self.frame.num_widgets();
+        self.frame.widget_mut(widget_index).subtree_end = subtree_end;
+
+        let parent = self.frame.widget(self.parent);
+        if self.data.screen_positioned && !self.data.movable_screen_pos && !self.data.unparent &&
+            parent.layout != Layout::Free {
+            let message = format!(
+                "Widget '{}' uses a screen position inside '{}', which does not have a Free layout.  \
+                The parent's child bounds will depend on the screen position.  Consider using unparent.",
+                self.frame.widget(widget_index).id, parent.id,
+            );
+            self.frame.context_internal().borrow_mut().log(log::Level::Warn, message);
         }
 
         if !self.data.unparent {
@@ -1005,26 +1680,53 @@ impl<'a> WidgetBuilder<'a> {
             self.frame.set_max_child_bounds(old_max_child_bounds);
         }
 
-        let (clicked, mut anim_state, mut dragged) = if self.data.enabled && self.data.wants_mouse {
+        let (clicked, right_clicked, mut anim_state, dragged) = if self.data.enabled && self.data.wants_mouse {
             let mouse_state = self.frame.check_mouse_state(widget_index);
-            (mouse_state.clicked, mouse_state.anim, mouse_state.dragged)
+            (mouse_state.clicked, mouse_state.right_clicked, mouse_state.anim, mouse_state.dragged)
         } else {
-            (false, AnimState::disabled(), Point::default())
+            if self.data.wants_mouse {
+                // a disabled widget still takes the mouse, blocking widgets behind it
+                self.frame.check_mouse_state(widget_index);
+            }
+            (false, false, AnimState::disabled(), Point::default())
         };
 
-
-        if self.data.wants_scroll {
+        let mut scroll_amount = Point::default();
+        if self.data.wants_scroll && self.data.enabled {
             if let Some(wheel) = self.frame.check_mouse_wheel(widget_index) {
-                dragged.x += wheel.x;
-                dragged.y += wheel.y;
+                scroll_amount = wheel;
             }
         }
 
-        let state = WidgetState::new(anim_state, clicked, dragged);
+        let mut state = WidgetState::new(anim_state, clicked, right_clicked, dragged, scroll_amount);
+
+        if state.right_clicked {
+            let id = self.frame.widget(widget_index).id().to_string();
+            self.frame.set_right_clicked_widget(id);
+        }
+
+        if state.clicked {
+            let id = self.frame.widget(widget_index).id();
+            state.double_clicked = self.frame.context_internal().borrow_mut().check_double_click(id);
+        }
+
+        if self.data.wants_keyboard && state.clicked {
+            let id = self.frame.widget(widget_index).id().to_string();
+            self.frame.focus_keyboard(id);
+        }
+
+        let widget = self.frame.widget(widget_index);
+        let bounds = Rect::new(widget.pos, widget.size);
+        self.frame.set_last_widget_bounds(bounds);
 
         if state.hovered {
+            if let Some(cursor) = self.data.cursor {
+                self.frame.set_os_cursor(cursor);
+            }
+
             if let Some(tooltip) = self.data.tooltip.take() {
-                self.frame.tooltip("tooltip", tooltip);
+                let theme = std::mem::take(&mut self.data.tooltip_theme);
+                self.frame.tooltip(&theme, tooltip);
             }
         }
 
@@ -1043,6 +1745,13 @@ impl<'a> WidgetBuilder<'a> {
         }
 
         self.frame.widget_mut(widget_index).anim_state = anim_state;
+        self.frame.track_anim_transition(widget_index, anim_state);
+
+        // the state is needed to apply the theme's state overrides on the next frame
+        if self.data.track_anim_state {
+            let id = self.frame.widget(widget_index).id().to_string();
+            self.frame.context_internal().borrow_mut().state_mut(&id).anim_state = Some(anim_state);
+        }
 
         
         let size = self.frame.widget(widget_index).size;
@@ -1058,6 +1767,7 @@ impl<'a> WidgetBuilder<'a> {
                 BotRight => (-size.x, -size.y),
                 TopLeft => (size.x, size.y),
                 TopRight => (-size.x, size.y),
+                Stretch | StretchHorizontal | StretchVertical => (size.x, size.y),
             };
 
             let parent = self.frame.widget_mut(self.parent);
@@ -1065,6 +1775,11 @@ impl<'a> WidgetBuilder<'a> {
             match parent.layout {
                 Horizontal => parent.cursor.x += x + parent.layout_spacing.x,
                 Vertical => parent.cursor.y += y + parent.layout_spacing.y,
+                Grid { columns } => parent.advance_grid(columns, size),
+                Wrap => {
+                    parent.cursor.x += size.x + parent.layout_spacing.x;
+                    parent.row_height = parent.row_height.max(size.y);
+                },
                 Free => (),
             }
         }
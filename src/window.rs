@@ -1,4 +1,4 @@
-use crate::{Frame, widget::WidgetBuilder, WidgetState, Point};
+use crate::{Frame, widget::WidgetBuilder, WidgetState, Point, Rect};
 
 /**
 A [`WidgetBuilder`](struct.WidgetBuilder.html) specifically for creating windows.
@@ -131,6 +131,60 @@ impl<'a> WindowBuilder<'a> {
         self
     }
 
+    /// Specifies the position of this window, in logical pixels in screen space, before the user has
+    /// moved it.  Once the window is moved, the `moved` offset stored in its
+    /// [`PersistentState`](struct.PersistentState.html) is applied on top of this position, so the
+    /// user's placement is kept.
+    #[must_use]
+    pub fn initial_pos(mut self, x: f32, y: f32) -> WindowBuilder<'a> {
+        self.builder.set_movable_screen_pos(Point::new(x, y));
+        self
+    }
+
+    /// Specifies that this window should initially be centered on the screen, based on
+    /// [`Frame::display_size`](struct.Frame.html#method.display_size) and the window's size
+    /// before any resizing.  See [`initial_pos`](#method.initial_pos).
+    #[must_use]
+    pub fn center_on_screen(mut self) -> WindowBuilder<'a> {
+        let mut rect = Rect::default();
+        self.builder = self.builder.trigger_layout(&mut rect);
+
+        let resize = {
+            let internal = self.builder.frame.context_internal().borrow();
+            internal.state(self.builder.widget.id()).resize
+        };
+        let size = rect.size - resize;
+        let pos = ((self.builder.frame.display_size() - size) / 2.0).max(Point::default()).round();
+        self.initial_pos(pos.x, pos.y)
+    }
+
+    /// Specifies the minimum size, in logical pixels, that the user can resize this window to.
+    /// Defaults to the window's size before any resizing, typically the `size` specified in the theme.
+    #[must_use]
+    pub fn min_size<P: Into<Point>>(mut self, size: P) -> WindowBuilder<'a> {
+        self.state.min_size = Some(size.into());
+        self
+    }
+
+    /// Specifies the maximum size, in logical pixels, that the user can resize this window to.
+    /// By default, there is no maximum size.
+    #[must_use]
+    pub fn max_size<P: Into<Point>>(mut self, size: P) -> WindowBuilder<'a> {
+        self.state.max_size = Some(size.into());
+        self
+    }
+
+    /// Specifies the z order of this window.  Windows with a higher `z_order` are
+    /// always drawn on top of, and receive mouse input before, windows with a lower
+    /// `z_order`, regardless of declaration order or which window was last clicked.
+    /// Windows with equal `z_order` keep their normal ordering.  The default is zero.
+    /// This may also be specified in the widget's [`theme`](index.html).
+    #[must_use]
+    pub fn z_order(mut self, z_order: i32) -> WindowBuilder<'a> {
+        self.builder.set_z_order(z_order);
+        self
+    }
+
     /// Consumes the builder and adds a widget to the current frame.  The
     /// returned data includes information about the animation state and
     /// mouse interactions of the created element.
@@ -171,6 +225,15 @@ impl<'a> WindowBuilder<'a> {
             };
 
             if drag_move != Point::default() {
+                // keep the titlebar, at the top of the window, on screen
+                let (pos, size, top) = {
+                    let window = ui.widget(ui.parent_index());
+                    (window.pos(), window.size(), window.border().top)
+                };
+                let display = ui.display_size();
+                let max = Point::new((display.x - size.x).max(0.0), (display.y - top).max(0.0));
+                let drag_move = (pos + drag_move).min(max).max(Point::default()) - pos;
+
                 ui.modify(&id, |state| {
                     state.moved = state.moved + drag_move;
                 });
@@ -178,11 +241,24 @@ impl<'a> WindowBuilder<'a> {
 
             if state.resizable {
                 let result = ui.button("handle", "");
-                if result.pressed {
-                    ui.modify(&id, |state| {
+                let size = ui.widget(ui.parent_index()).size();
+                let (min_size, max_size) = (state.min_size, state.max_size);
+                ui.modify(&id, |state| {
+                    // the window size without any resizing applied
+                    let base = size - state.resize;
+                    if result.pressed {
                         state.resize = state.resize + result.moved;
-                    });
-                }
+                    }
+
+                    let min = min_size.unwrap_or(base);
+                    let mut new_size = base + state.resize;
+                    if let Some(max) = max_size {
+                        new_size = new_size.min(max);
+                    }
+                    state.resize = new_size.max(min) - base;
+                    state.min_size = Some(min);
+                    state.max_size = max_size;
+                });
             }
         })
     }
@@ -193,6 +269,8 @@ struct WindowState {
     with_close_button: bool,
     moveable: bool,
     resizable: bool,
+    min_size: Option<Point>,
+    max_size: Option<Point>,
     title: Option<String>,
 }
 
@@ -203,6 +281,8 @@ impl Default for WindowState {
             with_close_button: true,
             moveable: true,
             resizable: true,
+            min_size: None,
+            max_size: None,
             title: None,
         }
     }
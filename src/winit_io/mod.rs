@@ -1,7 +1,12 @@
+use std::collections::VecDeque;
 use std::error::Error;
+use std::time::{Duration, Instant};
 
-use winit::event::{Event, WindowEvent, MouseButton, MouseScrollDelta, ElementState};
+use winit::event::{Event, WindowEvent, MouseButton, MouseScrollDelta, ElementState, VirtualKeyCode, Touch, TouchPhase};
 use winit::event_loop::EventLoop;
+use winit::window::Window;
+
+use serde::{Serialize, Deserialize};
 
 use crate::point::Point;
 use crate::context::{InputModifiers, Context};
@@ -14,6 +19,11 @@ This adapter handles events from `winit` and sends them to the Thyme [`Context`]
 WindowEvents should be passed to this handler, assuming [`Context.wants_mouse`](struct.Context.html#method.wants_mouse)
 returns true for the given frame.
 
+Touch events are also handled.  The first finger on the screen acts as the left mouse button, while dragging
+with two fingers scrolls.  When the fingers are lifted, scrolling continues with momentum based on the recent
+speed of the drag, and gradually slows down.  Momentum is applied when `NewEvents` events are passed to the handler,
+and by [`update_touch_momentum`](#method.update_touch_momentum), which should be called once per frame.
+
 # Example
 ```
 fn main_loop(event_loop: winit::EventLoop<()>, thyme: thyme::Context) {
@@ -37,6 +47,52 @@ fn main_loop(event_loop: winit::EventLoop<()>, thyme: thyme::Context) {
 pub struct WinitIo {
     scale_factor: f32,
     display_size: Point,
+    touch: TouchState,
+    cursor: CursorIcon,
+}
+
+/// How long touch scroll samples are kept for computing the momentum velocity
+const TOUCH_SAMPLE_TIME: Duration = Duration::from_millis(100);
+
+/// The fraction of the momentum velocity remaining after one second
+const TOUCH_MOMENTUM_DECAY: f32 = 0.05;
+
+/// The momentum speed, in logical pixels per second, below which momentum scrolling stops
+const TOUCH_MOMENTUM_MIN_SPEED: f32 = 10.0;
+
+#[derive(Default)]
+struct TouchState {
+    // active touches, in the order they started, with their positions in logical pixels
+    fingers: Vec<(u64, Point)>,
+
+    // recent scroll amounts, used to compute the momentum velocity
+    samples: VecDeque<(Instant, Point)>,
+
+    // the current momentum velocity, in logical pixels per second, and the last time it was applied
+    momentum: Option<(Point, Instant)>,
+
+    // whether the primary finger is currently pressing the left mouse button
+    pressed: bool,
+}
+
+impl TouchState {
+    fn centroid(&self) -> Option<Point> {
+        match self.fingers.as_slice() {
+            [(_, first), (_, second), ..] => Some((*first + *second) * 0.5),
+            _ => None,
+        }
+    }
+
+    fn velocity(&self, now: Instant) -> Point {
+        let start = match self.samples.front() {
+            None => return Point::default(),
+            Some((time, _)) => *time,
+        };
+
+        let total = self.samples.iter().fold(Point::default(), |total, (_, delta)| total + *delta);
+        let secs = (now - start).as_secs_f32().max(1.0 / 60.0);
+        total / secs
+    }
 }
 
 impl IO for WinitIo {
@@ -54,13 +110,30 @@ impl WinitIo {
         Ok(WinitIo {
             scale_factor,
             display_size: logical_display_size * scale_factor,
+            touch: TouchState::default(),
+            cursor: CursorIcon::Default,
         })
     }
 
+    /// Sets the OS cursor shape of `window` to the cursor requested by the most recent frame of `context`.
+    /// See [`Context.os_cursor`](struct.Context.html#method.os_cursor).  This should be called once per
+    /// frame, after the frame has been built.
+    pub fn update_cursor(&mut self, context: &Context, window: &Window) {
+        let cursor = context.os_cursor();
+        if cursor != self.cursor {
+            self.cursor = cursor;
+            window.set_cursor_icon(cursor.to_winit());
+        }
+    }
+
     /// Handles a winit `Event` and passes it to the Thyme [`Context`](struct.Context.html).
     pub fn handle_event<T>(&mut self, context: &mut Context, event: &Event<T>) {
         let event = match event {
             Event::WindowEvent { event, .. } => event,
+            Event::NewEvents(_) => {
+                self.update_touch_momentum(context);
+                return;
+            },
             _ => return,
         };
 
@@ -114,12 +187,541 @@ impl WinitIo {
             CursorMoved { position, .. } => {
                 context.set_mouse_pos((position.x as f32 / self.scale_factor, position.y as f32 / self.scale_factor).into());
             },
+            KeyboardInput { input, .. } => {
+                let pressed = match input.state {
+                    ElementState::Pressed => true,
+                    ElementState::Released => false,
+                };
+
+                if let Some(key) = input.virtual_keycode.and_then(KeyCode::from_winit) {
+                    context.set_key_pressed(key, pressed);
+                }
+            },
             ReceivedCharacter(c) => {
                 context.push_character(*c);
             }
+            Touch(touch) => self.handle_touch(context, touch),
+            DroppedFile(path) => context.push_dropped_file(path.clone()),
             _ => (),
         }
     }
+
+    // `Option::is_some_and` is avoided to support older compilers
+    #[allow(clippy::unnecessary_map_or)]
+    fn handle_touch(&mut self, context: &mut Context, touch: &Touch) {
+        let pos = Point::new(touch.location.x as f32 / self.scale_factor, touch.location.y as f32 / self.scale_factor);
+        let state = &mut self.touch;
+        let is_primary = state.fingers.first().map(|(id, _)| *id) == Some(touch.id);
+
+        match touch.phase {
+            TouchPhase::Started => {
+                state.momentum = None;
+                state.fingers.push((touch.id, pos));
+
+                if state.fingers.len() == 1 {
+                    context.set_mouse_pos(pos);
+                    context.set_mouse_pressed(true, 0);
+                    state.pressed = true;
+                } else {
+                    // a second finger turns the press into a scroll
+                    if state.pressed {
+                        context.set_mouse_pressed(false, 0);
+                        state.pressed = false;
+                    }
+                    state.samples.clear();
+                }
+            },
+            TouchPhase::Moved => {
+                let old_centroid = state.centroid();
+                if let Some(finger) = state.fingers.iter_mut().find(|(id, _)| *id == touch.id) {
+                    finger.1 = pos;
+                }
+
+                match (old_centroid, state.centroid()) {
+                    (Some(old), Some(new)) => {
+                        let now = Instant::now();
+                        let delta = new - old;
+                        context.add_mouse_wheel(delta);
+
+                        state.samples.push_back((now, delta));
+                        while state.samples.front().map_or(false, |(time, _)| now - *time > TOUCH_SAMPLE_TIME) {
+                            state.samples.pop_front();
+                        }
+                    },
+                    _ => if is_primary {
+                        context.set_mouse_pos(pos);
+                    },
+                }
+            },
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                let was_scrolling = state.fingers.len() >= 2;
+                state.fingers.retain(|(id, _)| *id != touch.id);
+
+                if is_primary && state.pressed {
+                    context.set_mouse_pressed(false, 0);
+                    state.pressed = false;
+                }
+
+                if was_scrolling && state.fingers.len() < 2 {
+                    let now = Instant::now();
+                    state.momentum = Some((state.velocity(now), now));
+                    state.samples.clear();
+                }
+            }
+        }
+    }
+
+    /// Advances any touch momentum scrolling by the time elapsed since it was last applied, passing the
+    /// scroll amount to the [`Context`](struct.Context.html).  This should be called once per frame, before
+    /// the frame is created, so that momentum continues smoothly even when no other events are received.
+    pub fn update_touch_momentum(&mut self, context: &mut Context) {
+        let (velocity, last) = match self.touch.momentum {
+            None => return,
+            Some(momentum) => momentum,
+        };
+
+        let now = Instant::now();
+        let secs = (now - last).as_secs_f32();
+        context.add_mouse_wheel(velocity * secs);
+
+        let velocity = velocity * TOUCH_MOMENTUM_DECAY.powf(secs);
+        if velocity.x.abs() < TOUCH_MOMENTUM_MIN_SPEED && velocity.y.abs() < TOUCH_MOMENTUM_MIN_SPEED {
+            self.touch.momentum = None;
+        } else {
+            self.touch.momentum = Some((velocity, now));
+        }
+    }
+}
+
+/// A keyboard key, used to register shortcuts with
+/// [`Context.register_shortcut`](struct.Context.html#method.register_shortcut).
+/// When using [`WinitIo`](struct.WinitIo.html), these are mapped from winit's `VirtualKeyCode`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum KeyCode {
+    // Number keys along the top of the keyboard
+    /// The `1` key along the top of the keyboard
+    Key1,
+    /// The `2` key along the top of the keyboard
+    Key2,
+    /// The `3` key along the top of the keyboard
+    Key3,
+    /// The `4` key along the top of the keyboard
+    Key4,
+    /// The `5` key along the top of the keyboard
+    Key5,
+    /// The `6` key along the top of the keyboard
+    Key6,
+    /// The `7` key along the top of the keyboard
+    Key7,
+    /// The `8` key along the top of the keyboard
+    Key8,
+    /// The `9` key along the top of the keyboard
+    Key9,
+    /// The `0` key along the top of the keyboard
+    Key0,
+
+    // Letter keys
+    /// The `A` key
+    A,
+    /// The `B` key
+    B,
+    /// The `C` key
+    C,
+    /// The `D` key
+    D,
+    /// The `E` key
+    E,
+    /// The `F` key
+    F,
+    /// The `G` key
+    G,
+    /// The `H` key
+    H,
+    /// The `I` key
+    I,
+    /// The `J` key
+    J,
+    /// The `K` key
+    K,
+    /// The `L` key
+    L,
+    /// The `M` key
+    M,
+    /// The `N` key
+    N,
+    /// The `O` key
+    O,
+    /// The `P` key
+    P,
+    /// The `Q` key
+    Q,
+    /// The `R` key
+    R,
+    /// The `S` key
+    S,
+    /// The `T` key
+    T,
+    /// The `U` key
+    U,
+    /// The `V` key
+    V,
+    /// The `W` key
+    W,
+    /// The `X` key
+    X,
+    /// The `Y` key
+    Y,
+    /// The `Z` key
+    Z,
+
+    // Function keys
+    /// The `F1` function key
+    F1,
+    /// The `F2` function key
+    F2,
+    /// The `F3` function key
+    F3,
+    /// The `F4` function key
+    F4,
+    /// The `F5` function key
+    F5,
+    /// The `F6` function key
+    F6,
+    /// The `F7` function key
+    F7,
+    /// The `F8` function key
+    F8,
+    /// The `F9` function key
+    F9,
+    /// The `F10` function key
+    F10,
+    /// The `F11` function key
+    F11,
+    /// The `F12` function key
+    F12,
+
+    // Navigation and editing keys
+    /// The escape key
+    Escape,
+    /// The tab key
+    Tab,
+    /// The space bar
+    Space,
+    /// The return or enter key
+    Return,
+    /// The backspace key
+    Back,
+    /// The insert key
+    Insert,
+    /// The delete key
+    Delete,
+    /// The home key
+    Home,
+    /// The end key
+    End,
+    /// The page up key
+    PageUp,
+    /// The page down key
+    PageDown,
+    /// The left arrow key
+    Left,
+    /// The up arrow key
+    Up,
+    /// The right arrow key
+    Right,
+    /// The down arrow key
+    Down,
+
+    // Numpad keys
+    /// The `0` key on the numpad
+    Numpad0,
+    /// The `1` key on the numpad
+    Numpad1,
+    /// The `2` key on the numpad
+    Numpad2,
+    /// The `3` key on the numpad
+    Numpad3,
+    /// The `4` key on the numpad
+    Numpad4,
+    /// The `5` key on the numpad
+    Numpad5,
+    /// The `6` key on the numpad
+    Numpad6,
+    /// The `7` key on the numpad
+    Numpad7,
+    /// The `8` key on the numpad
+    Numpad8,
+    /// The `9` key on the numpad
+    Numpad9,
+    /// The `+` key on the numpad
+    NumpadAdd,
+    /// The `-` key on the numpad
+    NumpadSubtract,
+    /// The `*` key on the numpad
+    NumpadMultiply,
+    /// The `/` key on the numpad
+    NumpadDivide,
+    /// The `.` key on the numpad
+    NumpadDecimal,
+    /// The enter key on the numpad
+    NumpadEnter,
+
+    // Punctuation keys
+    /// The `'` key
+    Apostrophe,
+    /// The `\` key
+    Backslash,
+    /// The `,` key
+    Comma,
+    /// The `=` key
+    Equals,
+    /// The `` ` `` key
+    Grave,
+    /// The `[` key
+    LBracket,
+    /// The `-` key
+    Minus,
+    /// The `.` key
+    Period,
+    /// The `]` key
+    RBracket,
+    /// The `;` key
+    Semicolon,
+    /// The `/` key
+    Slash,
+}
+
+impl KeyCode {
+    /// Whether pressing this key typically also sends a character, including control characters such as
+    /// backspace.  Function and navigation keys do not.
+    pub(crate) fn generates_text(self) -> bool {
+        use KeyCode::*;
+        !matches!(
+            self,
+            F1 | F2 | F3 | F4 | F5 | F6 | F7 | F8 | F9 | F10 | F11 | F12 |
+            Insert | Home | End | PageUp | PageDown | Left | Up | Right | Down
+        )
+    }
+
+    fn from_winit(key: VirtualKeyCode) -> Option<KeyCode> {
+        use VirtualKeyCode::*;
+        Some(match key {
+            Key1 => KeyCode::Key1,
+            Key2 => KeyCode::Key2,
+            Key3 => KeyCode::Key3,
+            Key4 => KeyCode::Key4,
+            Key5 => KeyCode::Key5,
+            Key6 => KeyCode::Key6,
+            Key7 => KeyCode::Key7,
+            Key8 => KeyCode::Key8,
+            Key9 => KeyCode::Key9,
+            Key0 => KeyCode::Key0,
+            A => KeyCode::A,
+            B => KeyCode::B,
+            C => KeyCode::C,
+            D => KeyCode::D,
+            E => KeyCode::E,
+            F => KeyCode::F,
+            G => KeyCode::G,
+            H => KeyCode::H,
+            I => KeyCode::I,
+            J => KeyCode::J,
+            K => KeyCode::K,
+            L => KeyCode::L,
+            M => KeyCode::M,
+            N => KeyCode::N,
+            O => KeyCode::O,
+            P => KeyCode::P,
+            Q => KeyCode::Q,
+            R => KeyCode::R,
+            S => KeyCode::S,
+            T => KeyCode::T,
+            U => KeyCode::U,
+            V => KeyCode::V,
+            W => KeyCode::W,
+            X => KeyCode::X,
+            Y => KeyCode::Y,
+            Z => KeyCode::Z,
+            F1 => KeyCode::F1,
+            F2 => KeyCode::F2,
+            F3 => KeyCode::F3,
+            F4 => KeyCode::F4,
+            F5 => KeyCode::F5,
+            F6 => KeyCode::F6,
+            F7 => KeyCode::F7,
+            F8 => KeyCode::F8,
+            F9 => KeyCode::F9,
+            F10 => KeyCode::F10,
+            F11 => KeyCode::F11,
+            F12 => KeyCode::F12,
+            Escape => KeyCode::Escape,
+            Tab => KeyCode::Tab,
+            Space => KeyCode::Space,
+            Return => KeyCode::Return,
+            Back => KeyCode::Back,
+            Insert => KeyCode::Insert,
+            Delete => KeyCode::Delete,
+            Home => KeyCode::Home,
+            End => KeyCode::End,
+            PageUp => KeyCode::PageUp,
+            PageDown => KeyCode::PageDown,
+            Left => KeyCode::Left,
+            Up => KeyCode::Up,
+            Right => KeyCode::Right,
+            Down => KeyCode::Down,
+            Numpad0 => KeyCode::Numpad0,
+            Numpad1 => KeyCode::Numpad1,
+            Numpad2 => KeyCode::Numpad2,
+            Numpad3 => KeyCode::Numpad3,
+            Numpad4 => KeyCode::Numpad4,
+            Numpad5 => KeyCode::Numpad5,
+            Numpad6 => KeyCode::Numpad6,
+            Numpad7 => KeyCode::Numpad7,
+            Numpad8 => KeyCode::Numpad8,
+            Numpad9 => KeyCode::Numpad9,
+            NumpadAdd => KeyCode::NumpadAdd,
+            NumpadSubtract => KeyCode::NumpadSubtract,
+            NumpadMultiply => KeyCode::NumpadMultiply,
+            NumpadDivide => KeyCode::NumpadDivide,
+            NumpadDecimal => KeyCode::NumpadDecimal,
+            NumpadEnter => KeyCode::NumpadEnter,
+            Apostrophe => KeyCode::Apostrophe,
+            Backslash => KeyCode::Backslash,
+            Comma => KeyCode::Comma,
+            Equals => KeyCode::Equals,
+            Grave => KeyCode::Grave,
+            LBracket => KeyCode::LBracket,
+            Minus => KeyCode::Minus,
+            Period => KeyCode::Period,
+            RBracket => KeyCode::RBracket,
+            Semicolon => KeyCode::Semicolon,
+            Slash => KeyCode::Slash,
+            _ => return None,
+        })
+    }
+}
+
+/// The shape of the operating system mouse cursor, set with
+/// [`Context.set_os_cursor`](struct.Context.html#method.set_os_cursor) or the `cursor` field of a widget theme.
+/// When using [`WinitIo`](struct.WinitIo.html), these are mapped to winit's `CursorIcon`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CursorIcon {
+    /// The platform's default cursor, usually an arrow
+    #[default]
+    Default,
+    /// A crosshair, for precise selection
+    Crosshair,
+    /// A pointing hand, for links and clickable widgets
+    Hand,
+    /// A plain arrow
+    Arrow,
+    /// Indicates something is to be moved
+    Move,
+    /// A text selection I-beam
+    Text,
+    /// Indicates the program is busy and can not be interacted with
+    Wait,
+    /// Indicates help is available
+    Help,
+    /// Indicates the program is busy, but can still be interacted with
+    Progress,
+    /// Indicates the requested action will not be carried out
+    NotAllowed,
+    /// Indicates a context menu is available
+    ContextMenu,
+    /// Indicates a table cell or set of cells can be selected
+    Cell,
+    /// A vertical text selection I-beam
+    VerticalText,
+    /// Indicates an alias or shortcut is to be created
+    Alias,
+    /// Indicates something is to be copied
+    Copy,
+    /// Indicates the dragged item can not be dropped here
+    NoDrop,
+    /// Indicates something can be grabbed
+    Grab,
+    /// Indicates something is being grabbed
+    Grabbing,
+    /// Indicates something can be scrolled in any direction
+    AllScroll,
+    /// Indicates something can be zoomed in
+    ZoomIn,
+    /// Indicates something can be zoomed out
+    ZoomOut,
+
+    // resize cursors, named by the direction(s) of the arrows
+    /// Resizing from the right edge
+    EResize,
+    /// Resizing from the top edge
+    NResize,
+    /// Resizing from the top right corner
+    NeResize,
+    /// Resizing from the top left corner
+    NwResize,
+    /// Resizing from the bottom edge
+    SResize,
+    /// Resizing from the bottom right corner
+    SeResize,
+    /// Resizing from the bottom left corner
+    SwResize,
+    /// Resizing from the left edge
+    WResize,
+    /// Resizing horizontally
+    EwResize,
+    /// Resizing vertically
+    NsResize,
+    /// Resizing diagonally, between the top right and bottom left
+    NeswResize,
+    /// Resizing diagonally, between the top left and bottom right
+    NwseResize,
+    /// Resizing a column
+    ColResize,
+    /// Resizing a row
+    RowResize,
+}
+
+impl CursorIcon {
+    fn to_winit(self) -> winit::window::CursorIcon {
+        use CursorIcon::*;
+        match self {
+            Default => winit::window::CursorIcon::Default,
+            Crosshair => winit::window::CursorIcon::Crosshair,
+            Hand => winit::window::CursorIcon::Hand,
+            Arrow => winit::window::CursorIcon::Arrow,
+            Move => winit::window::CursorIcon::Move,
+            Text => winit::window::CursorIcon::Text,
+            Wait => winit::window::CursorIcon::Wait,
+            Help => winit::window::CursorIcon::Help,
+            Progress => winit::window::CursorIcon::Progress,
+            NotAllowed => winit::window::CursorIcon::NotAllowed,
+            ContextMenu => winit::window::CursorIcon::ContextMenu,
+            Cell => winit::window::CursorIcon::Cell,
+            VerticalText => winit::window::CursorIcon::VerticalText,
+            Alias => winit::window::CursorIcon::Alias,
+            Copy => winit::window::CursorIcon::Copy,
+            NoDrop => winit::window::CursorIcon::NoDrop,
+            Grab => winit::window::CursorIcon::Grab,
+            Grabbing => winit::window::CursorIcon::Grabbing,
+            AllScroll => winit::window::CursorIcon::AllScroll,
+            ZoomIn => winit::window::CursorIcon::ZoomIn,
+            ZoomOut => winit::window::CursorIcon::ZoomOut,
+            EResize => winit::window::CursorIcon::EResize,
+            NResize => winit::window::CursorIcon::NResize,
+            NeResize => winit::window::CursorIcon::NeResize,
+            NwResize => winit::window::CursorIcon::NwResize,
+            SResize => winit::window::CursorIcon::SResize,
+            SeResize => winit::window::CursorIcon::SeResize,
+            SwResize => winit::window::CursorIcon::SwResize,
+            WResize => winit::window::CursorIcon::WResize,
+            EwResize => winit::window::CursorIcon::EwResize,
+            NsResize => winit::window::CursorIcon::NsResize,
+            NeswResize => winit::window::CursorIcon::NeswResize,
+            NwseResize => winit::window::CursorIcon::NwseResize,
+            ColResize => winit::window::CursorIcon::ColResize,
+            RowResize => winit::window::CursorIcon::RowResize,
+        }
+    }
 }
 
 #[derive(Debug)]